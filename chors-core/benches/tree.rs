@@ -0,0 +1,68 @@
+use chors_core::{
+    generate,
+    model::{flatten_tasks, FilterList, Model, View},
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+fn model_with(tasks: indexmap::IndexMap<uuid::Uuid, chors_core::model::Task>) -> Model {
+    let mut model = Model::new();
+    model.tasks = tasks;
+    model
+}
+
+fn complex_view() -> View {
+    View {
+        filter_lists: vec![
+            FilterList::parse("#work @office completed:false").unwrap(),
+            FilterList::parse("#home @phone").unwrap(),
+            FilterList::parse("#idea completed:true").unwrap(),
+        ],
+        display: Default::default(),
+    }
+}
+
+fn filtering(c: &mut Criterion) {
+    let view = complex_view();
+    let trees = [
+        ("deep_12", generate::deep_tree(12)),
+        ("deep_1000", generate::deep_tree(1000)),
+        ("wide_10k", generate::wide_tree(10_000)),
+        ("balanced_10k", generate::balanced_tree(10_000, 8, 6)),
+    ];
+    for (name, tasks) in trees {
+        c.bench_function(&format!("filter_cold/{}", name), |b| {
+            b.iter_batched(
+                || model_with(tasks.clone()),
+                |mut model| model.refresh_match_cache(&view),
+                BatchSize::LargeInput,
+            )
+        });
+        c.bench_function(&format!("flatten/{}", name), |b| {
+            b.iter(|| flatten_tasks(black_box(&tasks)))
+        });
+    }
+
+    let mut model = model_with(generate::balanced_tree(10_000, 8, 6));
+    model.refresh_match_cache(&view);
+    let path = model.flattened()[0].1.clone();
+    c.bench_function("filter_incremental/balanced_10k", |b| {
+        b.iter(|| {
+            model.get_task_mut(&path);
+            model.refresh_match_cache(&view);
+        })
+    });
+}
+
+fn serialization(c: &mut Criterion) {
+    let model = model_with(generate::balanced_tree(10_000, 8, 6));
+    let data = serde_json::to_string(&model).unwrap();
+    c.bench_function("serialize/balanced_10k", |b| {
+        b.iter(|| serde_json::to_string(black_box(&model)).unwrap())
+    });
+    c.bench_function("deserialize/balanced_10k", |b| {
+        b.iter(|| serde_json::from_str::<Model>(black_box(&data)).unwrap())
+    });
+}
+
+criterion_group!(benches, filtering, serialization);
+criterion_main!(benches);
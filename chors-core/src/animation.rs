@@ -0,0 +1,88 @@
+//! The short animations the list plays when `:animations on` is set: when
+//! the selection jumps more than a screen away the list scrolls there over a
+//! few frames instead of cutting, and tasks an undo changed flash briefly.
+//!
+//! Nothing here draws. Frontends start a scroll when they notice the jump,
+//! read [`Animation::scroll_offset`] and [`Animation::is_flashing`] while
+//! drawing, and keep ticking quickly while [`Animation::is_running`]; each
+//! [`crate::Msg::Tick`] drops the animations that have finished.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+pub const SCROLL_DURATION: Duration = Duration::from_millis(300);
+pub const FLASH_DURATION: Duration = Duration::from_millis(600);
+
+#[derive(Debug, Clone, Copy)]
+struct Scroll {
+    from: usize,
+    to: usize,
+    started: Instant,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    scroll: Option<Scroll>,
+    flashes: HashMap<Uuid, Instant>,
+}
+
+impl Animation {
+    /// Starts scrolling the list from the row offset `from` to `to`.
+    pub fn scroll(&mut self, from: usize, to: usize, now: Instant) {
+        self.scroll = Some(Scroll {
+            from,
+            to,
+            started: now,
+        });
+    }
+
+    /// The row offset to draw at `now`, while a scroll runs. It eases out,
+    /// covering most of the distance in the first frames.
+    pub fn scroll_offset(&self, now: Instant) -> Option<usize> {
+        let scroll = self.scroll?;
+        let elapsed = now.saturating_duration_since(scroll.started);
+        if elapsed >= SCROLL_DURATION {
+            return None;
+        }
+        let progress = elapsed.as_secs_f64() / SCROLL_DURATION.as_secs_f64();
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let distance = scroll.to as f64 - scroll.from as f64;
+        Some((scroll.from as f64 + distance * eased).round() as usize)
+    }
+
+    /// Highlights the tasks `ids` for [`FLASH_DURATION`] from `now`.
+    pub fn flash(&mut self, ids: impl IntoIterator<Item = Uuid>, now: Instant) {
+        for id in ids {
+            self.flashes.insert(id, now);
+        }
+    }
+
+    pub fn is_flashing(&self, id: Uuid, now: Instant) -> bool {
+        self.flashes
+            .get(&id)
+            .is_some_and(|&started| now.saturating_duration_since(started) < FLASH_DURATION)
+    }
+
+    /// Whether an animation is left for [`Animation::tick`] to drop; the
+    /// frame drawn on that tick shows the list back at rest.
+    pub fn is_running(&self) -> bool {
+        self.scroll.is_some() || !self.flashes.is_empty()
+    }
+
+    /// Forgets the animations that have finished by `now`, returning the
+    /// offset a finished scroll arrived at for the list to stay at.
+    pub fn tick(&mut self, now: Instant) -> Option<usize> {
+        self.flashes
+            .retain(|_, started| now.saturating_duration_since(*started) < FLASH_DURATION);
+        match self.scroll {
+            Some(scroll) if self.scroll_offset(now).is_none() => {
+                self.scroll = None;
+                Some(scroll.to)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,828 @@
+//! Two-way sync with a CalDAV task collection, configured in
+//! `$XDG_CONFIG_HOME/chors/caldav` (or the platform equivalent):
+//!
+//! ```text
+//! url = https://dav.example.com/calendars/me/tasks/
+//! username = me
+//! password = secret
+//! ```
+//!
+//! Every task is a `VTODO`, with subtasks pointing at their parent through
+//! `RELATED-TO`. A sync compares both sides with what they were after the
+//! previous one, kept in [`Model::caldav`]: a side that changed wins, and
+//! when both did, the more recent modification does. Tasks deleted on one
+//! side since are deleted on the other, except that a task deleted on the
+//! server but modified here since the last sync is uploaded again.
+//!
+//! The password is sent with every request, so the collection has to be
+//! `https://` unless it is on this machine, and the server can't point
+//! requests at any other host.
+
+use crate::{
+    dates::{self, DateDisplay},
+    events::Event,
+    model::{flatten_tasks, ChangeKind, Model, Msg, Task},
+    update::update,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs, io,
+    path::PathBuf,
+};
+use uuid::Uuid;
+
+#[derive(Clone, PartialEq)]
+pub struct Config {
+    /// The task collection, e.g. `https://dav.example.com/calendars/me/tasks/`.
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+// Keeps the password out of the debug overlay
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn default_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chors").join("caldav"))
+}
+
+/// Reads the config at `path`; a missing file means no sync.
+pub fn load(path: &PathBuf) -> Result<Option<Config>> {
+    match fs::read_to_string(path) {
+        Ok(source) => parse_config(&source)
+            .map(Some)
+            .map_err(|error| error.wrap_err(format!("in {}", path.to_string_lossy()))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn parse_config(source: &str) -> Result<Config> {
+    let mut values = HashMap::new();
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected `key = value`", number + 1);
+        };
+        match key.trim() {
+            key @ ("url" | "username" | "password") => {
+                values.insert(key, value.trim().to_string());
+            }
+            key => bail!("line {}: unknown key `{}`", number + 1, key),
+        }
+    }
+    let mut take = |key| {
+        values
+            .remove(key)
+            .ok_or_else(|| eyre!("`{}` is missing", key))
+    };
+    let mut url = take("url")?;
+    if !url.starts_with("https://") && !is_loopback(&url) {
+        bail!("`url` has to start with https://, the password would be sent in the clear");
+    }
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    Ok(Config {
+        url,
+        username: take("username")?,
+        password: take("password")?,
+    })
+}
+
+/// Whether plain `http://` at `url` stays on this machine.
+fn is_loopback(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("http://") else {
+        return false;
+    };
+    let host = origin(rest);
+    let host = host
+        .rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|ch| ch.is_ascii_digit()))
+        .map_or(host, |(host, _)| host);
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// The `host[:port]` of `rest`, what follows the scheme of a URL.
+fn origin(rest: &str) -> &str {
+    rest.find(['/', '?', '#']).map_or(rest, |at| &rest[..at])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    NeedsAction,
+    Completed,
+    Cancelled,
+}
+
+/// What is synced of a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fields {
+    pub summary: String,
+    pub notes: String,
+    pub status: Status,
+    pub completed: Option<DateTime<Utc>>,
+    pub due: Option<DateTime<Utc>>,
+    pub start: Option<DateTime<Utc>>,
+    /// The parent's `UID`.
+    pub parent: Option<String>,
+}
+
+impl Fields {
+    fn of(task: &Task, parent: Option<String>) -> Self {
+        let status = match (task.completed, &task.cancelled) {
+            (_, Some(_)) => Status::Cancelled,
+            (true, None) => Status::Completed,
+            (false, None) => Status::NeedsAction,
+        };
+        Fields {
+            summary: task.description.clone(),
+            notes: task.notes.clone(),
+            status,
+            completed: task.completed_at.filter(|_| status == Status::Completed),
+            due: task.due_time,
+            start: task.start_time,
+            parent,
+        }
+    }
+
+    /// Applies the fields to `task`, except for the parent.
+    fn apply(&self, task: &mut Task) {
+        if task.description != self.summary {
+            task.update_description(&self.summary);
+        }
+        task.notes = self.notes.clone();
+        task.due_time = self.due;
+        task.start_time = self.start;
+        match self.status {
+            Status::NeedsAction => task.set_completed(false),
+            Status::Completed => {
+                task.set_completed(true);
+                task.completed_at = self.completed.or(task.completed_at);
+            }
+            Status::Cancelled if task.cancelled.is_none() => task.cancel(None),
+            Status::Cancelled => {}
+        }
+    }
+}
+
+/// A `VTODO` on the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Todo {
+    pub uid: String,
+    /// Where the item lives, as the server lists it.
+    pub href: String,
+    pub fields: Fields,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// A task as it was on both sides after the last sync.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Synced {
+    pub uid: String,
+    pub href: String,
+    pub local: Fields,
+    pub remote: Fields,
+    /// When the task was last modified here, as of that sync.
+    #[serde(default)]
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// The tasks known to be on the server, by local id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub items: HashMap<Uuid, Synced>,
+}
+
+/// What a sync changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Tasks updated from the server, or added from it.
+    pub pulled: usize,
+    /// Tasks uploaded to the server.
+    pub pushed: usize,
+    /// Tasks deleted on either side.
+    pub deleted: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pulled, {} pushed, {} deleted",
+            self.pulled, self.pushed, self.deleted
+        )
+    }
+}
+
+/// The collection a sync talks to.
+pub trait Server {
+    fn list(&mut self) -> Result<Vec<Todo>>;
+    /// Creates or replaces the item at `href` with a calendar object.
+    fn put(&mut self, href: &str, ics: &str) -> Result<()>;
+    fn delete(&mut self, href: &str) -> Result<()>;
+}
+
+/// Syncs the tasks with `server`. What was synced before an error is kept,
+/// so the next sync picks up from there.
+pub fn sync(model: &mut Model, server: &mut dyn Server, now: DateTime<Utc>) -> Result<Report> {
+    let mut state = std::mem::take(&mut model.caldav);
+    let mut report = Report::default();
+    let result = sync_with(model, server, &mut state, &mut report, now);
+    model.caldav = state;
+    result.map(|()| report)
+}
+
+fn sync_with(
+    model: &mut Model,
+    server: &mut dyn Server,
+    state: &mut SyncState,
+    report: &mut Report,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let remote = server.list()?;
+    let local = flatten_tasks(&model.tasks);
+    let paths: HashMap<Uuid, Vec<Uuid>> = local.iter().cloned().collect();
+    let mut by_uid: HashMap<String, Uuid> = state
+        .items
+        .iter()
+        .map(|(id, synced)| (synced.uid.clone(), *id))
+        .collect();
+    let uid_of = |state: &SyncState, id: Uuid| {
+        state
+            .items
+            .get(&id)
+            .map_or_else(|| id.to_string(), |synced| synced.uid.clone())
+    };
+    let local_fields = |model: &Model, state: &SyncState, path: &[Uuid]| {
+        let task = model.get_task(path)?;
+        let parent = (path.len() > 1).then(|| uid_of(state, path[path.len() - 2]));
+        Some(Fields::of(task, parent))
+    };
+
+    let mut seen = HashSet::new();
+    let mut added = Vec::new();
+    for todo in remote {
+        let id = by_uid.get(&todo.uid).copied().or_else(|| {
+            Uuid::parse_str(&todo.uid)
+                .ok()
+                .filter(|id| paths.contains_key(id))
+        });
+        let Some(id) = id else {
+            added.push(todo);
+            continue;
+        };
+        seen.insert(id);
+        let synced = state.items.get(&id);
+        let Some(path) = paths.get(&id) else {
+            // Deleted here since the last sync
+            server.delete(&todo.href)?;
+            state.items.remove(&id);
+            report.deleted += 1;
+            continue;
+        };
+        let Some(fields) = local_fields(model, state, path) else {
+            continue;
+        };
+        let local_changed = synced.is_none_or(|synced| synced.local != fields);
+        let remote_changed = synced.is_none_or(|synced| synced.remote != todo.fields);
+        let task = model.get_task(path).expect("the task was just found");
+        let pull = match (local_changed, remote_changed) {
+            (false, false) => continue,
+            _ if fields == todo.fields => None,
+            (true, false) => Some(false),
+            (false, true) => Some(true),
+            (true, true) => Some(todo.modified > last_modified(task)),
+        };
+        match pull {
+            Some(true) => {
+                let task = model.get_task_mut(path).expect("the task was just found");
+                task.record(ChangeKind::Edited);
+                todo.fields.apply(task);
+                model.emit(Event::TaskChanged(id));
+                report.pulled += 1;
+            }
+            Some(false) => {
+                server.put(&todo.href, &to_ics(&todo.uid, &fields, task, now))?;
+                report.pushed += 1;
+            }
+            None => {}
+        }
+        let local = local_fields(model, state, path).expect("the task was just found");
+        let remote = match pull {
+            Some(false) => local.clone(),
+            _ => todo.fields,
+        };
+        let modified = model.get_task(path).and_then(last_modified);
+        by_uid.insert(todo.uid.clone(), id);
+        state.items.insert(
+            id,
+            Synced {
+                uid: todo.uid,
+                href: todo.href,
+                local,
+                remote,
+                modified,
+            },
+        );
+    }
+
+    // Tasks the server no longer has were deleted there, unless they were
+    // never synced or were modified here since, which wins over the delete
+    let mut removed: Vec<Vec<Uuid>> = Vec::new();
+    for (id, path) in &local {
+        if seen.contains(id) || removed.iter().any(|gone| path.starts_with(gone)) {
+            continue;
+        }
+        let Some(fields) = local_fields(model, state, path) else {
+            continue;
+        };
+        let task = model.get_task(path).expect("the task was just found");
+        let (uid, href) = match state.items.get(id) {
+            Some(synced) => {
+                let modified = synced.local != fields
+                    || synced
+                        .modified
+                        .is_some_and(|at| last_modified(task).is_some_and(|last| last > at));
+                if !modified {
+                    state.items.remove(id);
+                    removed.push(path.clone());
+                    report.deleted += 1;
+                    continue;
+                }
+                (synced.uid.clone(), synced.href.clone())
+            }
+            None => (id.to_string(), format!("{}.ics", id)),
+        };
+        server.put(&href, &to_ics(&uid, &fields, task, now))?;
+        report.pushed += 1;
+        state.items.insert(
+            *id,
+            Synced {
+                uid,
+                href,
+                local: fields.clone(),
+                remote: fields,
+                modified: last_modified(task),
+            },
+        );
+    }
+    for path in removed {
+        update(Msg::RemoveTask(path), model);
+    }
+
+    // New on the server; parents first, as far as they can be told apart
+    while !added.is_empty() {
+        let ready = added.iter().position(|todo| {
+            todo.fields.parent.as_ref().is_none_or(|parent| {
+                by_uid.contains_key(parent) || !added.iter().any(|other| other.uid == *parent)
+            })
+        });
+        let todo = added.remove(ready.unwrap_or(0));
+        let parent = todo
+            .fields
+            .parent
+            .as_ref()
+            .and_then(|parent| by_uid.get(parent))
+            .and_then(|id| model.path_of(*id))
+            .unwrap_or_default();
+        let mut task = Task::new(&todo.fields.summary);
+        todo.fields.apply(&mut task);
+        task.history.clear();
+        let id = task.id;
+        let mut path = parent.clone();
+        path.push(id);
+        update(Msg::AddTasksBulk(vec![(parent, task)]), model);
+        let local = local_fields(model, state, &path).unwrap_or_else(|| todo.fields.clone());
+        let modified = model.get_task(&path).and_then(last_modified);
+        by_uid.insert(todo.uid.clone(), id);
+        state.items.insert(
+            id,
+            Synced {
+                uid: todo.uid,
+                href: todo.href,
+                local,
+                remote: todo.fields,
+                modified,
+            },
+        );
+        report.pulled += 1;
+    }
+    Ok(())
+}
+
+/// When the task was last changed here, as far as its history tells.
+fn last_modified(task: &Task) -> Option<DateTime<Utc>> {
+    task.history
+        .last()
+        .map(|change| change.at)
+        .or_else(|| task.created_at())
+}
+
+/// A calendar object holding the task as a `VTODO`.
+pub fn to_ics(uid: &str, fields: &Fields, task: &Task, now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//chors//EN".to_string(),
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", escape(uid)),
+        format!("DTSTAMP:{}", format_time(now)),
+        format!(
+            "LAST-MODIFIED:{}",
+            format_time(last_modified(task).unwrap_or(now))
+        ),
+        format!("SUMMARY:{}", escape(&fields.summary)),
+    ];
+    if !fields.notes.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&fields.notes)));
+    }
+    lines.push(format!(
+        "STATUS:{}",
+        match fields.status {
+            Status::NeedsAction => "NEEDS-ACTION",
+            Status::Completed => "COMPLETED",
+            Status::Cancelled => "CANCELLED",
+        }
+    ));
+    for (name, time) in [
+        ("COMPLETED", fields.completed),
+        ("DUE", fields.due),
+        ("DTSTART", fields.start),
+    ] {
+        if let Some(time) = time {
+            lines.push(format!("{}:{}", name, format_time(time)));
+        }
+    }
+    if let Some(parent) = &fields.parent {
+        lines.push(format!("RELATED-TO;RELTYPE=PARENT:{}", escape(parent)));
+    }
+    lines.extend(["END:VTODO".to_string(), "END:VCALENDAR".to_string()]);
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// A read-only calendar of the open tasks with a due time, for calendars
+/// to subscribe to. Most phone calendars ignore `VTODO`s in subscriptions,
+/// so each task is a `VEVENT` at its due time unless `todos` asks for
+/// `VTODO`s. Tasks due at local midnight become all-day events.
+pub fn feed(model: &Model, todos: bool, now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//chors//EN".to_string(),
+        "X-WR-CALNAME:chors".to_string(),
+    ];
+    for (id, path) in flatten_tasks(&model.tasks) {
+        let Some(task) = model.get_task(&path) else {
+            continue;
+        };
+        let Some(due) = task.due_time.filter(|_| !task.is_done()) else {
+            continue;
+        };
+        let component = if todos { "VTODO" } else { "VEVENT" };
+        lines.extend([
+            format!("BEGIN:{}", component),
+            format!("UID:{}", id),
+            format!("DTSTAMP:{}", format_time(now)),
+            format!("SUMMARY:{}", escape(&task.description)),
+        ]);
+        if !task.notes.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&task.notes)));
+        }
+        let local = model.date_display.local(due);
+        lines.push(match (todos, local.time() == NaiveTime::MIN) {
+            (true, _) => format!("DUE:{}", format_time(due)),
+            (false, true) => format!("DTSTART;VALUE=DATE:{}", local.format("%Y%m%d")),
+            (false, false) => format!("DTSTART:{}", format_time(due)),
+        });
+        lines.push(format!("END:{}", component));
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// Reads the first `VTODO` of a calendar object. Times without a zone are
+/// taken to be in the display time zone.
+pub fn parse_todo(ics: &str, href: &str, dates: &DateDisplay) -> Option<Todo> {
+    let mut uid = None;
+    let mut modified = None;
+    let mut fields = Fields {
+        summary: String::new(),
+        notes: String::new(),
+        status: Status::NeedsAction,
+        completed: None,
+        due: None,
+        start: None,
+        parent: None,
+    };
+    let mut in_todo = false;
+    for line in unfold(ics) {
+        let Some((name, params, value)) = property(&line) else {
+            continue;
+        };
+        match (in_todo, name.as_str()) {
+            (false, "BEGIN") if value == "VTODO" => in_todo = true,
+            (false, _) => {}
+            (true, "END") if value == "VTODO" => break,
+            (true, "UID") => uid = Some(unescape(value)),
+            (true, "SUMMARY") => fields.summary = unescape(value),
+            (true, "DESCRIPTION") => fields.notes = unescape(value),
+            (true, "STATUS") => {
+                fields.status = match value {
+                    "COMPLETED" => Status::Completed,
+                    "CANCELLED" => Status::Cancelled,
+                    _ => Status::NeedsAction,
+                }
+            }
+            (true, "COMPLETED") => fields.completed = parse_time(value, &params, dates),
+            (true, "DUE") => fields.due = parse_time(value, &params, dates),
+            (true, "DTSTART") => fields.start = parse_time(value, &params, dates),
+            (true, "LAST-MODIFIED") => modified = parse_time(value, &params, dates),
+            (true, "RELATED-TO") => {
+                let parent = params
+                    .iter()
+                    .all(|param| !param.starts_with("RELTYPE=") || param == "RELTYPE=PARENT");
+                if parent {
+                    fields.parent = Some(unescape(value));
+                }
+            }
+            (true, _) => {}
+        }
+    }
+    if fields.status != Status::Completed {
+        fields.completed = None;
+    }
+    Some(Todo {
+        uid: uid?,
+        href: href.to_string(),
+        fields,
+        modified,
+    })
+}
+
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits content lines longer than 75 bytes, as the format asks.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+/// A content line's upper-cased name, its parameters and its value.
+fn property(line: &str) -> Option<(String, Vec<String>, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(at, c)| {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some(at),
+            _ => {}
+        }
+        None
+    })?;
+    let mut parts = line[..colon].split(';');
+    let name = parts.next()?.to_ascii_uppercase();
+    let params = parts.map(|param| param.to_ascii_uppercase()).collect();
+    Some((name, params, &line[colon + 1..]))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n' | 'N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            },
+            (c, false) => out.push(c),
+        }
+    }
+    out
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// `20240308T090000Z`, a local `20240308T090000` (in its `TZID` if there is
+/// one) or a `20240308` date, taken as midnight.
+fn parse_time(value: &str, params: &[String], dates: &DateDisplay) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(naive.and_utc());
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y%m%d").map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .ok()?;
+    let zone = params
+        .iter()
+        .find_map(|param| param.strip_prefix("TZID="))
+        .and_then(|zone| dates::parse_zone(zone.trim_matches('"')).ok());
+    match zone {
+        Some(zone) => DateDisplay {
+            zone: Some(zone),
+            ..dates.clone()
+        }
+        .from_local(naive),
+        None => dates.from_local(naive),
+    }
+}
+
+/// The contents of every `<name>` element in `xml`, whatever its namespace
+/// prefix. Enough for the multistatus replies of a
+/// calendar query.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        let tag_name = tag.split_whitespace().next().unwrap_or("");
+        let local = tag_name.rsplit(':').next().unwrap_or("");
+        if local != name || tag.starts_with('/') {
+            continue;
+        }
+        rest = &rest[end + 1..];
+        if tag.ends_with('/') {
+            found.push("");
+            continue;
+        }
+        let close = format!("</{}>", tag_name);
+        let Some(at) = rest.find(&close) else {
+            break;
+        };
+        found.push(&rest[..at]);
+        rest = &rest[at + close.len()..];
+    }
+    found
+}
+
+/// The text of an element, from a CDATA section or with entities decoded.
+fn decode(text: &str) -> String {
+    let text = text.trim();
+    match text
+        .strip_prefix("<![CDATA[")
+        .and_then(|text| text.strip_suffix("]]>"))
+    {
+        Some(raw) => raw.to_string(),
+        None => text
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&#13;", "\r")
+            .replace("&amp;", "&"),
+    }
+}
+
+/// The `VTODO`s in a multistatus reply.
+pub fn parse_multistatus(xml: &str, dates: &DateDisplay) -> Vec<Todo> {
+    elements(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = decode(elements(response, "href").first()?);
+            let data = decode(elements(response, "calendar-data").first()?);
+            parse_todo(&data, &href, dates)
+        })
+        .collect()
+}
+
+const QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+/// Talks to the collection in a [`Config`] over HTTP.
+pub struct Client {
+    config: Config,
+    dates: DateDisplay,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: Config, dates: DateDisplay) -> Self {
+        Self {
+            config,
+            dates,
+            agent: ureq::AgentBuilder::new()
+                .timeout(std::time::Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn request(&self, method: &str, href: &str) -> Result<ureq::Request> {
+        let credentials = format!("{}:{}", self.config.username, self.config.password);
+        Ok(self
+            .agent
+            .request(method, &resolve(&self.config.url, href)?)
+            .set(
+                "Authorization",
+                &format!("Basic {}", STANDARD.encode(credentials)),
+            ))
+    }
+}
+
+/// `href` against the collection at `url`: absolute paths keep only its
+/// origin, and full URLs have to share it, so a server can't have the
+/// password sent elsewhere.
+pub fn resolve(url: &str, href: &str) -> Result<String> {
+    let host = url.find("://").map_or(0, |at| at + 3);
+    let base = &url[..host + origin(&url[host..]).len()];
+    if let Some(at) = href.find("://") {
+        let other = &href[..at + 3 + origin(&href[at + 3..]).len()];
+        if !other.eq_ignore_ascii_case(base) {
+            bail!("The server pointed at {}, outside {}", href, base);
+        }
+        return Ok(href.to_string());
+    }
+    if href.starts_with('/') {
+        return Ok(format!("{}{}", base, href));
+    }
+    Ok(format!("{}{}", url, href))
+}
+
+fn http_error(method: &str, error: ureq::Error) -> color_eyre::Report {
+    match error {
+        ureq::Error::Status(code, response) => {
+            eyre!("{} {} failed with {}", method, response.get_url(), code)
+        }
+        error => eyre!("{}: {}", method, error),
+    }
+}
+
+impl Server for Client {
+    fn list(&mut self) -> Result<Vec<Todo>> {
+        let response = self
+            .request("REPORT", "")?
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(QUERY)
+            .map_err(|error| http_error("REPORT", error))?;
+        Ok(parse_multistatus(&response.into_string()?, &self.dates))
+    }
+
+    fn put(&mut self, href: &str, ics: &str) -> Result<()> {
+        self.request("PUT", href)?
+            .set("Content-Type", "text/calendar; charset=utf-8")
+            .send_string(ics)
+            .map_err(|error| http_error("PUT", error))?;
+        Ok(())
+    }
+
+    fn delete(&mut self, href: &str) -> Result<()> {
+        match self.request("DELETE", href)?.call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(error) => Err(http_error("DELETE", error)),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Which columns the task list draws for each row, in which order and how
+//! wide. They are saved with the model, so they can also be set in the data
+//! file:
+//!
+//! ```json
+//! "columns": [{ "column": "id" }, { "column": "description" }, { "column": "due", "width": 12 }]
+//! ```
+//!
+//! The description column holds the checkbox, the description and every
+//! marker that doesn't have a column of its own. Without a width it takes
+//! the room the others leave, wrapping when it comes last.
+
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Description,
+    Due,
+    Priority,
+    /// The `estimate:` metadata field, e.g. `estimate:2h`.
+    Estimate,
+    /// Days since the task was created.
+    Age,
+    /// The short id, as `chors done` takes it.
+    Id,
+}
+
+impl Column {
+    pub const ALL: [Column; 6] = [
+        Column::Description,
+        Column::Due,
+        Column::Priority,
+        Column::Estimate,
+        Column::Age,
+        Column::Id,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Column::Description => "description",
+            Column::Due => "due",
+            Column::Priority => "priority",
+            Column::Estimate => "estimate",
+            Column::Age => "age",
+            Column::Id => "id",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.as_str() == name)
+    }
+
+    /// The width used when none is configured; `None` for the description,
+    /// which fills the rest of the row.
+    pub fn default_width(&self) -> Option<u16> {
+        match self {
+            Column::Description => None,
+            Column::Due => Some(16),
+            Column::Priority => Some(4),
+            Column::Estimate => Some(6),
+            Column::Age => Some(4),
+            Column::Id => Some(4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSpec {
+    pub column: Column,
+    #[serde(default)]
+    pub width: Option<u16>,
+}
+
+impl ColumnSpec {
+    pub fn new(column: Column) -> Self {
+        Self {
+            column,
+            width: None,
+        }
+    }
+
+    pub fn width(&self) -> Option<u16> {
+        self.width.or(self.column.default_width())
+    }
+}
+
+/// Just the description, which shows due dates and priorities inline.
+pub fn default_columns() -> Vec<ColumnSpec> {
+    vec![ColumnSpec::new(Column::Description)]
+}
+
+/// Parses `:columns` arguments like `id description due:12`, each a column
+/// with an optional width. The description has to be among them.
+pub fn parse(args: &[String]) -> Result<Vec<ColumnSpec>> {
+    let mut specs: Vec<ColumnSpec> = Vec::new();
+    for arg in args {
+        let (name, width) = match arg.split_once(':') {
+            Some((name, width)) => (name, Some(width)),
+            None => (arg.as_str(), None),
+        };
+        let column = Column::parse(name).ok_or_else(|| {
+            let names: Vec<&str> = Column::ALL.iter().map(Column::as_str).collect();
+            eyre!("Unknown column `{}`, expected {}", name, names.join(", "))
+        })?;
+        let width = match width {
+            Some(width) => Some(
+                width
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|&width| width > 0)
+                    .ok_or_else(|| eyre!("Invalid width `{}` for {}", width, name))?,
+            ),
+            None => None,
+        };
+        if specs.iter().any(|spec| spec.column == column) {
+            return Err(eyre!("{} is listed twice", name));
+        }
+        specs.push(ColumnSpec { column, width });
+    }
+    if !specs.iter().any(|spec| spec.column == Column::Description) {
+        return Err(eyre!("The description column can't be hidden"));
+    }
+    Ok(specs)
+}
@@ -0,0 +1,198 @@
+//! How dates and times are shown to the user.
+//!
+//! Tasks store their timestamps in UTC. Frontends go through [`DateDisplay`]
+//! to turn them into local dates and text, so the list, the calendar and any
+//! later views agree on the time zone, format strings, the first day of the
+//! week and whether times are shown relative to now.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DateDisplay {
+    /// `strftime` format for dates, e.g. `%d.%m.%Y`.
+    pub date_format: String,
+    /// `strftime` format for times of day, e.g. `%I:%M %p`.
+    pub time_format: String,
+    /// First column of the calendar grid.
+    pub week_start: Weekday,
+    /// Shows "in 2 days" or "3 weeks ago" instead of the date.
+    pub relative: bool,
+    /// Overrides the system time zone, set with `--tz`.
+    #[serde(skip)]
+    pub zone: Option<Tz>,
+}
+
+/// Where a due date falls relative to today, for grouping and `due:`
+/// filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    ThisWeek,
+    Later,
+    NoDueDate,
+}
+
+impl DueBucket {
+    pub const ALL: [DueBucket; 5] = [
+        DueBucket::Overdue,
+        DueBucket::Today,
+        DueBucket::ThisWeek,
+        DueBucket::Later,
+        DueBucket::NoDueDate,
+    ];
+
+    /// The name used in `due:` filters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DueBucket::Overdue => "overdue",
+            DueBucket::Today => "today",
+            DueBucket::ThisWeek => "week",
+            DueBucket::Later => "later",
+            DueBucket::NoDueDate => "none",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|bucket| bucket.as_str() == name)
+    }
+}
+
+/// Parses an IANA time zone name such as `Europe/Vilnius` or `UTC`.
+pub fn parse_zone(name: &str) -> Result<Tz> {
+    name.parse()
+        .map_err(|_| eyre!("Unknown time zone: {}", name))
+}
+
+impl Default for DateDisplay {
+    fn default() -> Self {
+        Self {
+            date_format: "%Y-%m-%d".to_string(),
+            time_format: "%H:%M".to_string(),
+            week_start: Weekday::Mon,
+            relative: false,
+            zone: None,
+        }
+    }
+}
+
+impl DateDisplay {
+    pub fn date(&self, date: NaiveDate) -> String {
+        render(date.format(&self.date_format)).unwrap_or_else(|| date.to_string())
+    }
+
+    /// `time` in the display time zone.
+    pub fn local(&self, time: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.zone {
+            Some(zone) => time.with_timezone(&zone).fixed_offset(),
+            None => time.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    /// The calendar date of `time` in the display time zone.
+    pub fn local_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        self.local(time).date_naive()
+    }
+
+    /// Interprets a wall-clock time in the display time zone, or `None` if it
+    /// doesn't exist or is ambiguous there (around DST changes).
+    pub fn from_local(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        let offset = match self.zone {
+            Some(zone) => zone.from_local_datetime(&naive).single()?.offset().fix(),
+            None => Local.from_local_datetime(&naive).single()?.offset().fix(),
+        };
+        Some((naive - offset).and_utc())
+    }
+
+    /// Parses `YYYY-MM-DD HH:MM` or `YYYY-MM-DD` (midnight) typed in the
+    /// display time zone.
+    pub fn parse_local(&self, input: &str) -> Result<DateTime<Utc>, &'static str> {
+        let input = input.trim();
+        let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .or_else(|_| {
+                NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                    .map(|date| date.and_time(NaiveTime::MIN))
+            })
+            .map_err(|_| "Expected YYYY-MM-DD or YYYY-MM-DD HH:MM")?;
+        self.from_local(naive)
+            .ok_or("Ambiguous or nonexistent local time")
+    }
+
+    pub fn bucket(&self, due: Option<DateTime<Utc>>, now: DateTime<Utc>) -> DueBucket {
+        let Some(due) = due else {
+            return DueBucket::NoDueDate;
+        };
+        if due < now {
+            return DueBucket::Overdue;
+        }
+        let (due, today) = (self.local_date(due), self.local_date(now));
+        if due == today {
+            DueBucket::Today
+        } else if due < self.start_of_week(today) + Duration::days(7) {
+            DueBucket::ThisWeek
+        } else {
+            DueBucket::Later
+        }
+    }
+
+    pub fn date_time(&self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        if self.relative {
+            relative(time, now)
+        } else {
+            let time = self.local(time);
+            let format = format!("{} {}", self.date_format, self.time_format);
+            render(time.format(&format))
+                .unwrap_or_else(|| time.format("%Y-%m-%d %H:%M").to_string())
+        }
+    }
+
+    /// The first day of the week containing `date`.
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate {
+        let offset = date.weekday().days_since(self.week_start);
+        date - Duration::days(offset.into())
+    }
+
+    /// The seven weekdays in calendar order.
+    pub fn weekdays(&self) -> impl Iterator<Item = Weekday> {
+        std::iter::successors(Some(self.week_start), |day| Some(day.succ())).take(7)
+    }
+}
+
+/// Renders a chrono format, or `None` if the format string is invalid for
+/// the value. `to_string` would panic instead, and the format strings come
+/// from the data file.
+fn render(formatted: impl Display) -> Option<String> {
+    let mut out = String::new();
+    write!(out, "{}", formatted).ok()?;
+    Some(out)
+}
+
+/// Describes `time` relative to `now` in the largest whole unit, e.g.
+/// "in 2 days", "3 weeks ago" or "now".
+pub fn relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = time.signed_duration_since(now);
+    let seconds = delta.num_seconds().abs();
+    let (amount, unit) = match seconds {
+        0..=59 => return "now".to_string(),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86_399 => (seconds / 3600, "hour"),
+        86_400..=604_799 => (seconds / 86_400, "day"),
+        604_800..=2_629_799 => (seconds / 604_800, "week"),
+        2_629_800..=31_557_599 => (seconds / 2_629_800, "month"),
+        _ => (seconds / 31_557_600, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    if delta.num_seconds() > 0 {
+        format!("in {} {}{}", amount, unit, plural)
+    } else {
+        format!("{} {}{} ago", amount, unit, plural)
+    }
+}
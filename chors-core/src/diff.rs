@@ -0,0 +1,123 @@
+//! What changed between two versions of a task tree, e.g. a model before and
+//! after a batch of messages, so callers can limit work to the tasks that
+//! were touched.
+
+use crate::model::Task;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeDiff {
+    /// Tasks only in the newer tree, in its order.
+    pub added: Vec<Uuid>,
+    /// Tasks only in the older tree, in its order.
+    pub removed: Vec<Uuid>,
+    /// Tasks in both whose own fields or parent differ, in the newer tree's
+    /// order. Changes to subtasks are reported for the subtasks alone.
+    pub changed: Vec<Uuid>,
+}
+
+impl TreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Every task with its parent, in manual order.
+fn walk<'a>(
+    tasks: &'a IndexMap<Uuid, Task>,
+    parent: Option<Uuid>,
+    out: &mut Vec<(Option<Uuid>, &'a Task)>,
+) {
+    for task in tasks.values() {
+        out.push((parent, task));
+        walk(&task.subtasks, Some(task.id), out);
+    }
+}
+
+/// Compares `before` and `after` task by task. Reordering siblings isn't
+/// reported; moving a task under another parent is.
+pub fn diff(before: &IndexMap<Uuid, Task>, after: &IndexMap<Uuid, Task>) -> TreeDiff {
+    if std::ptr::eq(before, after) {
+        return TreeDiff::default();
+    }
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    walk(before, None, &mut old);
+    walk(after, None, &mut new);
+    let old_by_id: HashMap<Uuid, (Option<Uuid>, &Task)> = old
+        .iter()
+        .map(|&(parent, task)| (task.id, (parent, task)))
+        .collect();
+
+    let mut diff = TreeDiff::default();
+    for &(parent, task) in &new {
+        match old_by_id.get(&task.id) {
+            None => diff.added.push(task.id),
+            Some(&(old_parent, old_task)) => {
+                if old_parent != parent || !same_fields(old_task, task) {
+                    diff.changed.push(task.id);
+                }
+            }
+        }
+    }
+    let new_ids: HashSet<Uuid> = new.iter().map(|(_, task)| task.id).collect();
+    diff.removed = old
+        .iter()
+        .map(|(_, task)| task.id)
+        .filter(|id| !new_ids.contains(id))
+        .collect();
+    diff
+}
+
+/// Whether two tasks agree on everything but their subtasks. Destructured so
+/// a new field can't be left out of the comparison.
+fn same_fields(a: &Task, b: &Task) -> bool {
+    let Task {
+        id,
+        description,
+        completed,
+        subtasks: _,
+        tags,
+        contexts,
+        start_time,
+        due_time,
+        priority,
+        notes,
+        attachments,
+        reminders,
+        completed_at,
+        completion,
+        percent,
+        cancelled,
+        history,
+        delegated_to,
+        meta,
+        snoozed,
+        author,
+        completed_by,
+        comments,
+    } = a;
+    *id == b.id
+        && *description == b.description
+        && *completed == b.completed
+        && *tags == b.tags
+        && *contexts == b.contexts
+        && *start_time == b.start_time
+        && *due_time == b.due_time
+        && *priority == b.priority
+        && *notes == b.notes
+        && *attachments == b.attachments
+        && *reminders == b.reminders
+        && *completed_at == b.completed_at
+        && *completion == b.completion
+        && *percent == b.percent
+        && *cancelled == b.cancelled
+        && *history == b.history
+        && *delegated_to == b.delegated_to
+        && *meta == b.meta
+        && *snoozed == b.snoozed
+        && *author == b.author
+        && *completed_by == b.completed_by
+        && *comments == b.comments
+}
@@ -0,0 +1,47 @@
+//! Typed change notifications emitted by [`update`](crate::update::update).
+//!
+//! Handlers record what they changed with [`Model::emit`]; frontends pass
+//! the queued events to their [`Subscriber`]s with [`dispatch`] after each
+//! update, instead of inspecting the model for changes themselves.
+
+use crate::model::Model;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    TaskAdded(Uuid),
+    TaskChanged(Uuid),
+    TaskCompleted(Uuid),
+    TaskReopened(Uuid),
+    /// Also sent for each subtask removed along with the task.
+    TaskRemoved(Uuid),
+    /// A reminder of the task came due and was shown.
+    ReminderDue(Uuid),
+    /// The filters or display options of the effective view changed.
+    FilterChanged,
+    ListSwitched(String),
+}
+
+impl Event {
+    /// Whether the event changed data that ends up in the data file.
+    pub fn is_persistent(&self) -> bool {
+        !matches!(
+            self,
+            Event::FilterChanged | Event::ListSwitched(_) | Event::ReminderDue(_)
+        )
+    }
+}
+
+/// Reacts to model changes, e.g. autosave, hooks or a sync backend.
+pub trait Subscriber {
+    fn notify(&mut self, event: &Event, model: &Model);
+}
+
+/// Hands every event emitted since the last call to each subscriber, in order.
+pub fn dispatch(model: &mut Model, subscribers: &mut [&mut dyn Subscriber]) {
+    for event in model.take_events() {
+        for subscriber in subscribers.iter_mut() {
+            subscriber.notify(&event, model);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Snapshots of the rows on screen as Markdown or standalone HTML, for
+//! pasting into notes or sharing.
+
+use crate::model::{Model, Task};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "md" | "markdown" => Some(Format::Markdown),
+            "html" => Some(Format::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 48em; margin: 2em auto; }
+ul { list-style: none; padding: 0; }
+li { margin: 0.2em 0; }
+.done { color: #888; text-decoration: line-through; }
+.due { color: #b35900; font-size: 0.9em; }";
+
+/// The tasks currently in the list, in their on-screen order and nesting.
+/// Reads the navigation order, so it reflects the filter, sort and zoom of
+/// the last frame.
+fn visible_tasks(model: &Model) -> impl Iterator<Item = (usize, &Task)> {
+    let depth_offset = model.zoom.len() + 1;
+    model.nav.values().filter_map(move |path| {
+        let task = model.get_task(path)?;
+        Some((path.len().saturating_sub(depth_offset), task))
+    })
+}
+
+fn due(model: &Model, task: &Task) -> Option<String> {
+    let due = task.due_time?;
+    Some(model.date_display.date(model.date_display.local_date(due)))
+}
+
+/// Renders the visible rows as `format`, returning the document and the
+/// number of tasks in it.
+pub fn render(model: &Model, format: Format) -> (String, usize) {
+    match format {
+        Format::Markdown => markdown(model),
+        Format::Html => html(model),
+    }
+}
+
+fn markdown(model: &Model) -> (String, usize) {
+    let mut out = format!("# {}\n\n", model.selected_view);
+    let mut count = 0;
+    for (depth, task) in visible_tasks(model) {
+        let check = if task.completed { 'x' } else { ' ' };
+        let _ = write!(out, "{}- [{}] ", "  ".repeat(depth), check);
+        match task.cancelled {
+            Some(_) => {
+                let _ = write!(out, "~~{}~~", task.description);
+            }
+            None => out.push_str(&task.description),
+        }
+        if let Some(due) = due(model, task) {
+            let _ = write!(out, " (due {})", due);
+        }
+        out.push('\n');
+        count += 1;
+    }
+    (out, count)
+}
+
+fn html(model: &Model) -> (String, usize) {
+    let title = escape(&model.selected_view);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n<ul>\n"
+    );
+    let mut count = 0;
+    for (depth, task) in visible_tasks(model) {
+        let (class, check) = if task.completed {
+            (" class=\"done\"", '☑')
+        } else {
+            ("", '☐')
+        };
+        let _ = write!(
+            out,
+            "<li style=\"margin-left: {}em\"><span{}>{} {}</span>",
+            depth * 2,
+            class,
+            check,
+            escape(&task.description)
+        );
+        if let Some(due) = due(model, task) {
+            let _ = write!(out, " <span class=\"due\">due {}</span>", escape(&due));
+        }
+        out.push_str("</li>\n");
+        count += 1;
+    }
+    out.push_str("</ul>\n</body>\n</html>\n");
+    (out, count)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A single line text input with a cursor. The cursor is a byte offset that
+/// always sits on a grapheme cluster boundary.
+#[derive(Debug, Clone, Default)]
+pub struct Field {
+    text: String,
+    cursor: usize,
+}
+
+impl Field {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.text.len();
+    }
+
+    /// Display width of the text before the cursor, used for cursor placement.
+    pub fn cursor_width(&self) -> usize {
+        self.text[..self.cursor].width()
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        self.text.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    /// Removes the grapheme before the cursor, so combining marks and emoji
+    /// sequences are deleted as a whole instead of leaving dangling code points.
+    pub fn delete_before(&mut self) {
+        let start = self.prev_boundary();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Ctrl+U: removes everything before the cursor and returns it.
+    pub fn kill_to_start(&mut self) -> String {
+        let killed = self.text.drain(..self.cursor).collect();
+        self.cursor = 0;
+        killed
+    }
+
+    /// Ctrl+K: removes everything after the cursor and returns it.
+    pub fn kill_to_end(&mut self) -> String {
+        self.text.split_off(self.cursor)
+    }
+
+    /// Alt+D: removes from the cursor to the end of the next word and returns it.
+    pub fn kill_word_forward(&mut self) -> String {
+        let rest = &self.text[self.cursor..];
+        let word_start = rest.len() - rest.trim_start().len();
+        let word_len = rest[word_start..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - word_start);
+        let end = self.cursor + word_start + word_len;
+        self.text.drain(self.cursor..end).collect()
+    }
+
+    /// Ctrl+T: swaps the graphemes around the cursor, or the last two when the
+    /// cursor is at the end of the text, and moves the cursor past them.
+    pub fn transpose(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        if self.cursor == self.text.len() {
+            self.cursor = self.prev_boundary();
+        }
+        let start = self.prev_boundary();
+        let end = self.next_boundary();
+        if start == self.cursor || end == self.cursor {
+            self.cursor = end;
+            return;
+        }
+        let swapped = format!(
+            "{}{}",
+            &self.text[self.cursor..end],
+            &self.text[start..self.cursor]
+        );
+        self.text.replace_range(start..end, &swapped);
+        self.cursor = end;
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormField {
+    pub label: &'static str,
+    pub input: Field,
+    pub error: Option<String>,
+}
+
+/// A set of labelled fields with one of them focused at a time.
+#[derive(Debug, Clone, Default)]
+pub struct Form {
+    pub fields: Vec<FormField>,
+    pub focus: usize,
+}
+
+impl Form {
+    pub fn new(labels: &[&'static str]) -> Self {
+        let fields = labels
+            .iter()
+            .map(|&label| FormField {
+                label,
+                input: Field::default(),
+                error: None,
+            })
+            .collect();
+        Self { fields, focus: 0 }
+    }
+
+    pub fn value(&self, index: usize) -> &str {
+        self.fields[index].input.text()
+    }
+
+    pub fn set_value(&mut self, index: usize, value: &str) {
+        self.fields[index].input.set_text(value);
+    }
+
+    pub fn focused_mut(&mut self) -> &mut Field {
+        &mut self.fields[self.focus].input
+    }
+
+    pub fn focus_next(&mut self) {
+        self.focus = (self.focus + 1) % self.fields.len();
+    }
+
+    pub fn focus_previous(&mut self) {
+        self.focus = (self.focus + self.fields.len() - 1) % self.fields.len();
+    }
+
+    pub fn set_error(&mut self, index: usize, error: &str) {
+        self.fields[index].error = Some(error.to_string());
+    }
+
+    pub fn clear_errors(&mut self) {
+        for field in &mut self.fields {
+            field.error = None;
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.fields.iter().any(|field| field.error.is_some())
+    }
+}
+
+/// Previously submitted inputs of an overlay, browsable with Up/Down.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    #[serde(skip)]
+    position: Option<usize>,
+    #[serde(skip)]
+    draft: String,
+}
+
+impl InputHistory {
+    const MAX_ENTRIES: usize = 100;
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if self.entries.len() > Self::MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Steps back in history, remembering `current` so that stepping forward
+    /// past the newest entry restores what was being typed.
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let position = match self.position {
+            Some(0) => return None,
+            Some(position) => position - 1,
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+        };
+        self.position = Some(position);
+        Some(self.entries[position].clone())
+    }
+
+    pub fn newer(&mut self) -> Option<String> {
+        let position = self.position?;
+        if position + 1 < self.entries.len() {
+            self.position = Some(position + 1);
+            Some(self.entries[position + 1].clone())
+        } else {
+            self.position = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
@@ -0,0 +1,37 @@
+//! Fuzzy matching for jumping to a task by a few characters of its
+//! description.
+
+/// Scores how well `query` matches `text`, or `None` when the query's
+/// characters don't all appear in order. Case is ignored. Runs of adjacent
+/// characters and characters at the start of a word count for more, and
+/// shorter texts win ties.
+pub fn score(query: &str, text: &str) -> Option<i64> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut adjacent = false;
+    for ch in text.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let word_start = previous.is_none_or(|previous| !previous.is_alphanumeric());
+        if ch.to_lowercase().eq([wanted]) {
+            query.next();
+            score += 1;
+            if adjacent {
+                score += 4;
+            }
+            if word_start {
+                score += 6;
+            }
+            adjacent = true;
+        } else {
+            adjacent = false;
+        }
+        previous = Some(ch);
+    }
+    if query.peek().is_some() {
+        return None;
+    }
+    Some(score * 100 - text.chars().count() as i64)
+}
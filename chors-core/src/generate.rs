@@ -0,0 +1,98 @@
+//! Synthetic task trees for benchmarks and the stress test binary.
+
+use crate::model::{Priority, Task};
+use indexmap::IndexMap;
+use uuid::Uuid;
+
+const TAGS: [&str; 5] = ["#work", "#home", "#errand", "#idea", "#someday"];
+const CONTEXTS: [&str; 4] = ["@office", "@phone", "@computer", "@town"];
+
+/// Deterministic pseudo-random numbers, so runs are comparable.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize
+    }
+}
+
+fn task(rng: &mut Lcg, index: usize) -> Task {
+    let description = format!(
+        "Task {} {} {}",
+        index,
+        TAGS[rng.next() % TAGS.len()],
+        CONTEXTS[rng.next() % CONTEXTS.len()]
+    );
+    let mut task = Task::new(&description);
+    task.completed = rng.next().is_multiple_of(3);
+    task.priority = match rng.next() % 4 {
+        0 => Some(Priority::High),
+        1 => Some(Priority::Medium),
+        2 => Some(Priority::Low),
+        _ => None,
+    };
+    task
+}
+
+/// A single chain of tasks `depth` levels deep.
+pub fn deep_tree(depth: usize) -> IndexMap<Uuid, Task> {
+    let mut rng = Lcg(depth as u64);
+    let mut current: Option<Task> = None;
+    for index in (0..depth).rev() {
+        let mut parent = task(&mut rng, index);
+        if let Some(child) = current.take() {
+            parent.subtasks.insert(child.id, child);
+        }
+        current = Some(parent);
+    }
+    current.into_iter().map(|task| (task.id, task)).collect()
+}
+
+/// `width` root tasks without subtasks.
+pub fn wide_tree(width: usize) -> IndexMap<Uuid, Task> {
+    let mut rng = Lcg(width as u64);
+    (0..width)
+        .map(|index| {
+            let task = task(&mut rng, index);
+            (task.id, task)
+        })
+        .collect()
+}
+
+/// A tree of `count` tasks where every level has up to `fanout` children
+/// and no branch is deeper than `depth`.
+pub fn balanced_tree(count: usize, fanout: usize, depth: usize) -> IndexMap<Uuid, Task> {
+    fn fill(
+        rng: &mut Lcg,
+        remaining: &mut usize,
+        fanout: usize,
+        depth: usize,
+    ) -> IndexMap<Uuid, Task> {
+        let mut tasks = IndexMap::new();
+        while *remaining > 0 && tasks.len() < fanout {
+            *remaining -= 1;
+            let mut task = task(rng, *remaining);
+            if depth > 1 {
+                let children = rng.next() % (fanout + 1);
+                let mut budget = children.min(*remaining);
+                *remaining -= budget;
+                task.subtasks = fill(rng, &mut budget, fanout, depth - 1);
+                *remaining += budget;
+            }
+            tasks.insert(task.id, task);
+        }
+        tasks
+    }
+
+    let mut rng = Lcg(count as u64);
+    let mut remaining = count;
+    let mut roots = IndexMap::new();
+    while remaining > 0 {
+        roots.extend(fill(&mut rng, &mut remaining, fanout, depth));
+    }
+    roots
+}
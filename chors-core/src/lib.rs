@@ -0,0 +1,47 @@
+//! The task engine behind chors, free of any terminal UI dependencies so other
+//! frontends can be built on it.
+//!
+//! The application follows the Elm architecture. All state lives in a
+//! [`Model`], and the only way to change it is to pass a [`Msg`] to
+//! [`update`]. A frontend turns user input into messages, calls `update`, and
+//! renders from the model; it may read any model field but should not mutate
+//! the task tree directly, since `update` keeps derived state (parent
+//! completion, filter caches, selection) consistent.
+//!
+//! Tasks form a tree of [`Task`]s keyed by id. Which tasks are shown is
+//! decided by the [`View`] in [`Model::current_view`]: a disjunction of
+//! [`FilterList`]s, each a conjunction of [`Filter`] conditions. The model is
+//! loaded and saved with [`persistence`], which upgrades older files through
+//! [`migrations`].
+//!
+//! Changes made by `update` are reported as [`events::Event`]s, which
+//! frontends forward to their subscribers with [`events::dispatch`].
+
+pub mod animation;
+pub mod caldav;
+pub mod columns;
+pub mod crypto;
+pub mod dates;
+pub mod diff;
+pub mod events;
+pub mod export;
+pub mod form;
+pub mod fuzzy;
+pub mod generate;
+pub mod lint;
+pub mod macros;
+pub mod migrations;
+pub mod model;
+pub mod persistence;
+pub mod resolve;
+pub mod rollover;
+pub mod scripting;
+pub mod status;
+pub mod summary;
+pub mod symbols;
+pub mod update;
+pub mod vault;
+pub mod worker;
+
+pub use model::{Filter, FilterList, Model, Msg, Task, View};
+pub use update::update;
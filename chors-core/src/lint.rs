@@ -0,0 +1,184 @@
+//! Checks a view's filters for criteria that can never match together,
+//! criteria that add nothing, and alternatives that match every task, and
+//! works out the simpler filters that match the same tasks.
+
+use crate::model::{Filter, FilterList, View};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Finding {
+    /// The filter list can't match any task because of these two criteria.
+    Contradiction { list: usize, a: Filter, b: Filter },
+    /// The criterion already follows from `by`, or repeats it.
+    Redundant {
+        list: usize,
+        filter: Filter,
+        by: Filter,
+    },
+    /// The list only matches tasks that the list `by` matches too.
+    Subsumed { list: usize, by: usize },
+    /// The list has no criteria, so the view matches every task.
+    AlwaysTrue { list: usize },
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Finding::Contradiction { list, a, b } => {
+                write!(f, "Filter {} never matches: {} and {}", list + 1, a, b)
+            }
+            Finding::Redundant { list, filter, by } if filter == by => {
+                write!(f, "Filter {} repeats {}", list + 1, filter)
+            }
+            Finding::Redundant { list, filter, by } => {
+                write!(f, "Filter {}: {} already implies {}", list + 1, by, filter)
+            }
+            Finding::Subsumed { list, by } => {
+                write!(f, "Filter {} is covered by filter {}", list + 1, by + 1)
+            }
+            Finding::AlwaysTrue { list } => {
+                write!(f, "Filter {} is empty and matches every task", list + 1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub findings: Vec<Finding>,
+    /// Filter lists matching the same tasks as the view's, without the
+    /// findings. Empty if the view matches every task, `None` if it matches
+    /// none.
+    pub simplified: Option<Vec<FilterList>>,
+}
+
+/// No task matches both criteria.
+fn contradicts(a: &Filter, b: &Filter) -> bool {
+    use Filter::*;
+    let one_way = |a: &Filter, b: &Filter| match (a, b) {
+        (Completed(a), Completed(b)) => a != b,
+        (Due(a), Due(b)) => a != b,
+        (Waiting(Some(a)), Waiting(Some(b))) => a != b,
+        // Cancelled tasks are completed, yet count as neither done nor open
+        (Cancelled, Completed(_) | Waiting(_) | CompletedDaysAgo { .. }) => true,
+        (Waiting(_), Completed(true) | CompletedDaysAgo { .. }) => true,
+        (CompletedDaysAgo { .. }, Completed(false)) => true,
+        (Not(a), b) => **a == *b,
+        _ => false,
+    };
+    one_way(a, b) || one_way(b, a)
+}
+
+/// Every task matching `a` matches `b`.
+fn implies(a: &Filter, b: &Filter) -> bool {
+    use Filter::*;
+    a == b
+        || matches!(
+            (a, b),
+            (Waiting(Some(_)), Waiting(None))
+                | (Snoozed(Some(_)), Snoozed(None))
+                | (Waiting(_), Completed(false))
+                | (CompletedDaysAgo { .. }, Completed(true))
+        )
+}
+
+fn check_list(index: usize, list: &FilterList, findings: &mut Vec<Finding>) -> Option<FilterList> {
+    for (i, a) in list.filters.iter().enumerate() {
+        if let Some(b) = list.filters[i + 1..].iter().find(|b| contradicts(a, b)) {
+            findings.push(Finding::Contradiction {
+                list: index,
+                a: a.clone(),
+                b: b.clone(),
+            });
+            return None;
+        }
+    }
+    let mut kept: Vec<Filter> = Vec::new();
+    for (i, filter) in list.filters.iter().enumerate() {
+        // Of two equal criteria, the first one stays
+        let by = kept.iter().find(|by| implies(by, filter)).or_else(|| {
+            list.filters[i + 1..]
+                .iter()
+                .find(|by| *by != filter && implies(by, filter))
+        });
+        match by {
+            Some(by) => findings.push(Finding::Redundant {
+                list: index,
+                filter: filter.clone(),
+                by: by.clone(),
+            }),
+            None => kept.push(filter.clone()),
+        }
+    }
+    Some(FilterList { filters: kept })
+}
+
+/// Analyzes the filter lists of `view`, which match a task if any of them
+/// does.
+pub fn lint(view: &View) -> Lint {
+    let mut findings = Vec::new();
+    let lists: Vec<(usize, FilterList)> = view
+        .filter_lists
+        .iter()
+        .enumerate()
+        .filter_map(|(index, list)| Some((index, check_list(index, list, &mut findings)?)))
+        .collect();
+
+    if let Some(&(list, _)) = lists.iter().find(|(_, list)| list.filters.is_empty()) {
+        findings.push(Finding::AlwaysTrue { list });
+        return Lint {
+            findings,
+            simplified: Some(Vec::new()),
+        };
+    }
+    if lists.is_empty() && !view.filter_lists.is_empty() {
+        return Lint {
+            findings,
+            simplified: None,
+        };
+    }
+
+    let covers = |a: &FilterList, b: &FilterList| a.filters.iter().all(|f| b.filters.contains(f));
+    let mut simplified = Vec::new();
+    for (position, (index, list)) in lists.iter().enumerate() {
+        // A list requiring everything another one does adds no tasks; of two
+        // equal lists, the first one stays
+        let by = lists.iter().enumerate().find(|(other, (_, by))| {
+            *other != position && covers(by, list) && (*other < position || !covers(list, by))
+        });
+        match by {
+            Some((_, (by, _))) => findings.push(Finding::Subsumed {
+                list: *index,
+                by: *by,
+            }),
+            None => simplified.push(list.clone()),
+        }
+    }
+    Lint {
+        findings,
+        simplified: Some(simplified),
+    }
+}
+
+impl Lint {
+    /// The simplified filters as they would be typed, alternatives separated
+    /// by `|`.
+    pub fn simplified_text(&self) -> String {
+        let simplified = match &self.simplified {
+            None => return "(matches no task)".to_string(),
+            Some(lists) if lists.is_empty() => return "(matches every task)".to_string(),
+            Some(lists) => lists,
+        };
+        simplified
+            .iter()
+            .map(|list| {
+                list.filters
+                    .iter()
+                    .map(Filter::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
@@ -0,0 +1,87 @@
+//! Named filter criteria, read from `$XDG_CONFIG_HOME/chors/filters` (or the
+//! platform equivalent):
+//!
+//! ```text
+//! # Blank lines and comments are ignored
+//! actionable = completed:false due:today
+//! errands = @town actionable
+//! ```
+//!
+//! A filter then mentions a macro by name, e.g. `#home actionable`, and it
+//! stands for all of its criteria. Macros can use other macros, but not
+//! take the name of a built-in criterion.
+
+use crate::model::{Filter, FilterList};
+use color_eyre::{eyre::bail, Result};
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterMacros {
+    definitions: HashMap<String, String>,
+}
+
+impl FilterMacros {
+    /// The criteria `name` stands for, unexpanded.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.definitions.get(name).map(String::as_str)
+    }
+
+    pub(crate) fn get_key_value(&self, name: &str) -> Option<(&str, &str)> {
+        self.definitions
+            .get_key_value(name)
+            .map(|(name, criteria)| (name.as_str(), criteria.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Parses definitions, checking that each one expands to valid criteria.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut macros = FilterMacros::default();
+        let mut lines = Vec::new();
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, criteria)) = line.split_once('=') else {
+                bail!("line {}: expected `name = criteria`", number + 1);
+            };
+            let name = name.trim();
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':') {
+                bail!("line {}: macro names are a single word", number + 1);
+            }
+            if Filter::parse(name).is_ok() {
+                bail!("line {}: `{}` is a built-in criterion", number + 1, name);
+            }
+            if criteria.trim().is_empty() {
+                bail!("line {}: `{}` has no criteria", number + 1, name);
+            }
+            macros
+                .definitions
+                .insert(name.to_string(), criteria.trim().to_string());
+            lines.push((number + 1, name.to_string()));
+        }
+        for (number, name) in lines {
+            if let Err(error) = FilterList::parse_with(&name, &macros) {
+                bail!("line {}: {}", number, error);
+            }
+        }
+        Ok(macros)
+    }
+}
+
+pub fn default_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chors").join("filters"))
+}
+
+/// Reads the macros at `path`; a missing file means no macros.
+pub fn load(path: &PathBuf) -> Result<FilterMacros> {
+    match fs::read_to_string(path) {
+        Ok(source) => FilterMacros::parse(&source)
+            .map_err(|error| error.wrap_err(format!("in {}", path.to_string_lossy()))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(FilterMacros::default()),
+        Err(error) => Err(error.into()),
+    }
+}
@@ -0,0 +1,71 @@
+//! Upgrades data files written by older versions of chors.
+//!
+//! Each entry in [`MIGRATIONS`] takes the raw JSON of version `n` to version
+//! `n + 1`. Files without a `version` field predate versioning and are treated
+//! as version 0. When a change to the model can't be expressed with
+//! `#[serde(default)]` alone, bump [`CURRENT_VERSION`] and add a step here.
+
+use color_eyre::{eyre::bail, Result};
+use serde_json::{Map, Value};
+
+pub const CURRENT_VERSION: u32 = 1;
+
+const MIGRATIONS: [fn(&mut Map<String, Value>); CURRENT_VERSION as usize] = [v0_to_v1];
+
+/// Runs every migration between the file's version and [`CURRENT_VERSION`].
+pub fn migrate(data: &mut Value) -> Result<()> {
+    let Some(model) = data.as_object_mut() else {
+        bail!("data file does not contain a model object");
+    };
+    let version = match model.get("version") {
+        None => 0,
+        Some(version) => match version.as_u64() {
+            Some(version) => version as u32,
+            None => bail!("invalid data file version: {}", version),
+        },
+    };
+    if version > CURRENT_VERSION {
+        bail!(
+            "data file has version {} but this build only understands up to {}",
+            version,
+            CURRENT_VERSION
+        );
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(model);
+    }
+    model.insert("version".to_string(), Value::from(CURRENT_VERSION));
+    Ok(())
+}
+
+/// Version 0 persisted the raw text of the input line and stored tasks with
+/// only `completed`, `start_time` and `due_time` beyond their description.
+/// Drops the stale input and fills in the later task fields explicitly.
+fn v0_to_v1(model: &mut Map<String, Value>) {
+    if model.get("input").is_some_and(Value::is_string) {
+        model.remove("input");
+    }
+    if let Some(Value::Object(tasks)) = model.get_mut("tasks") {
+        for task in tasks.values_mut() {
+            v0_to_v1_task(task);
+        }
+    }
+}
+
+fn v0_to_v1_task(task: &mut Value) {
+    let Some(task) = task.as_object_mut() else {
+        return;
+    };
+    task.entry("completed").or_insert(Value::Bool(false));
+    task.entry("start_time").or_insert(Value::Null);
+    task.entry("due_time").or_insert(Value::Null);
+    task.entry("priority").or_insert(Value::Null);
+    task.entry("notes").or_insert(Value::String(String::new()));
+    task.entry("attachments")
+        .or_insert(Value::Array(Vec::new()));
+    if let Some(Value::Object(subtasks)) = task.get_mut("subtasks") {
+        for subtask in subtasks.values_mut() {
+            v0_to_v1_task(subtask);
+        }
+    }
+}
@@ -0,0 +1,2971 @@
+use crate::{
+    animation::Animation,
+    caldav::{self, SyncState},
+    columns::{self, Column, ColumnSpec},
+    dates::{DateDisplay, DueBucket},
+    events::Event,
+    export,
+    form::{Field, Form, InputHistory},
+    fuzzy,
+    macros::FilterMacros,
+    resolve,
+    rollover::{self, Rollover},
+    scripting::Scripts,
+    status::{Severity, StatusMessages},
+    symbols::Symbols,
+    worker::{self, FilterJob, BACKGROUND_FILTER_THRESHOLD},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use uuid::{NoContext, Timestamp, Uuid};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: Uuid,
+    pub description: String,
+    pub completed: bool,
+    pub subtasks: IndexMap<Uuid, Task>,
+    pub tags: HashSet<String>,
+    pub contexts: HashSet<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub attachments: Vec<PathBuf>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// When the task was last completed; `None` while it is open.
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Overrides [`Model::completion_policy`] for this task's subtasks.
+    #[serde(default)]
+    pub completion: Option<CompletionPolicy>,
+    /// Partial progress of a task without subtasks, 0 to 100.
+    #[serde(default)]
+    pub percent: Option<u8>,
+    /// Set when the task was given up on rather than done.
+    #[serde(default)]
+    pub cancelled: Option<Cancellation>,
+    /// The most recent changes, oldest first.
+    #[serde(default)]
+    pub history: Vec<Change>,
+    /// Who the task is waiting on, from a `>>name` word in the description.
+    #[serde(default)]
+    pub delegated_to: Option<String>,
+    /// Fields chors doesn't know about, from `key:value` words in the
+    /// description such as `client:Acme`.
+    #[serde(default)]
+    pub meta: HashMap<String, String>,
+    /// Keeps the task out of the list until the condition holds, see
+    /// [`View::hides`].
+    #[serde(default)]
+    pub snoozed: Option<Snooze>,
+    /// Who added the task, when [`Model::author`] was set.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Who completed the task; `None` while it is open.
+    #[serde(default)]
+    pub completed_by: Option<String>,
+    /// Oldest first.
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+}
+
+/// A timestamped remark left on a task, for context that doesn't belong in
+/// its notes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+/// When a snoozed task shows up again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Snooze {
+    /// While the focus is on the context.
+    UntilContext(String),
+}
+
+impl Snooze {
+    pub fn describe(&self) -> String {
+        match self {
+            Snooze::UntilContext(context) => format!("until {}", context),
+        }
+    }
+}
+
+/// An entry in a task's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    pub at: DateTime<Utc>,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Edited,
+    Completed,
+    Reopened,
+    Cancelled,
+    Moved,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Edited => "edited",
+            ChangeKind::Completed => "completed",
+            ChangeKind::Reopened => "reopened",
+            ChangeKind::Cancelled => "cancelled",
+            ChangeKind::Moved => "moved",
+        }
+    }
+}
+
+/// Cancelled tasks are closed like completed ones (`completed` is set, so
+/// they hide and let parents complete), but don't count as done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cancellation {
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Task {
+    pub fn new(description: &str) -> Self {
+        let mut task = Task {
+            id: Uuid::new_v7(Timestamp::now(NoContext)),
+            description: description.to_string(),
+            completed: false,
+            subtasks: IndexMap::new(),
+            tags: HashSet::new(),
+            contexts: HashSet::new(),
+            start_time: None,
+            due_time: None,
+            priority: None,
+            notes: String::new(),
+            attachments: Vec::new(),
+            reminders: Vec::new(),
+            completed_at: None,
+            completion: None,
+            percent: None,
+            cancelled: None,
+            history: Vec::new(),
+            delegated_to: None,
+            meta: HashMap::new(),
+            snoozed: None,
+            author: None,
+            completed_by: None,
+            comments: Vec::new(),
+        };
+        task.extract_tags_and_contexts();
+        task
+    }
+
+    fn extract_tags_and_contexts(&mut self) {
+        for word in self.description.split_whitespace() {
+            if word.starts_with('#') {
+                self.tags.insert(word.to_string());
+            } else if word.starts_with('@') {
+                self.contexts.insert(word.to_string());
+            } else if let Some(person) = delegate(word) {
+                self.delegated_to = Some(person.to_string());
+            } else if let Some((key, value)) = meta_field(word) {
+                self.meta.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Collects the ids of this task and all of its descendants.
+    pub fn collect_ids(&self, ids: &mut Vec<Uuid>) {
+        ids.push(self.id);
+        for subtask in self.subtasks.values() {
+            subtask.collect_ids(ids);
+        }
+    }
+
+    pub fn descendant_count(&self) -> usize {
+        self.subtasks
+            .values()
+            .map(|subtask| 1 + subtask.descendant_count())
+            .sum()
+    }
+
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.description
+            .split_whitespace()
+            .filter(|word| is_url(word))
+    }
+
+    /// Sets the completion state, stamping the time when the task becomes
+    /// completed and keeping the original stamp if it already was. Reopening
+    /// a cancelled task clears the cancellation; completing one keeps it.
+    pub fn set_completed(&mut self, completed: bool) {
+        if self.completed != completed {
+            self.record(match completed {
+                true => ChangeKind::Completed,
+                false => ChangeKind::Reopened,
+            });
+        }
+        self.completed = completed;
+        if !completed {
+            self.cancelled = None;
+        }
+        self.completed_at = match completed {
+            true if self.cancelled.is_some() => None,
+            true => self.completed_at.or_else(|| Some(Utc::now())),
+            false => None,
+        };
+    }
+
+    /// Appends to the history, dropping the oldest entry once it is full.
+    pub fn record(&mut self, kind: ChangeKind) {
+        const MAX_HISTORY: usize = 50;
+        self.history.push(Change {
+            at: Utc::now(),
+            kind,
+        });
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    /// When the task was created, taken from its version 7 id; `None` for
+    /// ids of other versions.
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        let (seconds, nanos) = self.id.get_timestamp()?.to_unix();
+        DateTime::from_timestamp(seconds as i64, nanos)
+    }
+
+    /// Snoozed, with the condition not met while focusing on `focus`.
+    pub fn is_snoozed(&self, focus: Option<&str>) -> bool {
+        match &self.snoozed {
+            None => false,
+            Some(Snooze::UntilContext(context)) => focus != Some(context.as_str()),
+        }
+    }
+
+    /// Completed, and not by cancelling it.
+    pub fn is_done(&self) -> bool {
+        self.completed && self.cancelled.is_none()
+    }
+
+    /// Closes the task as won't-do, along with any open subtasks.
+    pub fn cancel(&mut self, reason: Option<String>) {
+        self.record(ChangeKind::Cancelled);
+        self.completed = true;
+        self.completed_at = None;
+        self.cancelled = Some(Cancellation {
+            at: Utc::now(),
+            reason,
+        });
+        for subtask in self.subtasks.values_mut() {
+            if !subtask.completed {
+                subtask.cancel(None);
+            }
+        }
+    }
+
+    /// How far along the task is, from 0 to 1: all the way when completed,
+    /// its own percentage without subtasks, otherwise the average over those
+    /// that weren't cancelled.
+    pub fn progress(&self) -> f64 {
+        if self.completed {
+            1.0
+        } else if self.subtasks.is_empty() {
+            f64::from(self.percent.unwrap_or(0)) / 100.0
+        } else {
+            let open: Vec<f64> = self
+                .subtasks
+                .values()
+                .filter(|task| task.cancelled.is_none())
+                .map(Task::progress)
+                .collect();
+            match open.len() {
+                0 => 0.0,
+                len => open.iter().sum::<f64>() / len as f64,
+            }
+        }
+    }
+
+    /// Deep copy of the task and its subtasks with fresh ids and completion reset.
+    pub fn duplicate(&self) -> Self {
+        let mut task = self.clone();
+        task.id = Uuid::new_v7(Timestamp::now(NoContext));
+        task.set_completed(false);
+        task.history.clear();
+        task.subtasks = self
+            .subtasks
+            .values()
+            .map(|subtask| {
+                let subtask = subtask.duplicate();
+                (subtask.id, subtask)
+            })
+            .collect();
+        task
+    }
+
+    pub fn update_description(&mut self, new_description: &str) {
+        self.description = new_description.to_string();
+        self.tags.clear();
+        self.contexts.clear();
+        self.delegated_to = None;
+        self.meta.clear();
+        self.extract_tags_and_contexts();
+    }
+}
+
+/// The key and value of a `key:value` metadata word. Keys start with a
+/// letter, so times like `10:30` and URLs are left alone.
+pub fn meta_field(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once(':')?;
+    let valid_key = key.starts_with(|c: char| c.is_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    (valid_key && !value.is_empty() && !value.starts_with("//")).then_some((key, value))
+}
+
+/// The person a `>>name` word delegates to.
+pub fn delegate(word: &str) -> Option<&str> {
+    word.strip_prefix(">>").filter(|person| !person.is_empty())
+}
+
+/// The subtasks of the task at `path`, e.g. the zoom root, or all of `tasks`
+/// if the path is empty or no longer leads anywhere.
+pub fn subtasks_at<'a>(tasks: &'a IndexMap<Uuid, Task>, path: &[Uuid]) -> &'a IndexMap<Uuid, Task> {
+    path.iter()
+        .try_fold(tasks, |tasks, id| tasks.get(id).map(|task| &task.subtasks))
+        .unwrap_or(tasks)
+}
+
+/// Tasks matching a filter, out of all tasks in the list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchCount {
+    pub matching: usize,
+    pub total: usize,
+    /// Matching tasks that are done.
+    pub done: usize,
+}
+
+impl fmt::Display for MatchCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} / {} tasks ({} done)",
+            self.matching, self.total, self.done
+        )
+    }
+}
+
+/// [`flatten_tasks`] with an index from task ids to their rows, rebuilt
+/// whenever the tree is restructured.
+#[derive(Debug, Clone, Default)]
+pub struct FlatOrder {
+    pub rows: Vec<(Uuid, Vec<Uuid>)>,
+    pub index: HashMap<Uuid, usize>,
+}
+
+impl FlatOrder {
+    pub fn new(rows: Vec<(Uuid, Vec<Uuid>)>) -> Self {
+        let index = rows
+            .iter()
+            .enumerate()
+            .map(|(row, (id, _))| (*id, row))
+            .collect();
+        Self { rows, index }
+    }
+}
+
+/// Every task in the tree with the path leading to it, in manual order.
+pub fn flatten_tasks(tasks: &IndexMap<Uuid, Task>) -> Vec<(Uuid, Vec<Uuid>)> {
+    fn walk(tasks: &IndexMap<Uuid, Task>, path: &[Uuid], out: &mut Vec<(Uuid, Vec<Uuid>)>) {
+        for task in tasks.values() {
+            let mut current_path = path.to_vec();
+            current_path.push(task.id);
+            out.push((task.id, current_path.clone()));
+            walk(&task.subtasks, &current_path, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(tasks, &[], &mut out);
+    out
+}
+
+/// The task with `id` anywhere in the tree.
+fn find_task_mut(tasks: &mut IndexMap<Uuid, Task>, id: Uuid) -> Option<&mut Task> {
+    if tasks.contains_key(&id) {
+        return tasks.get_mut(&id);
+    }
+    tasks
+        .values_mut()
+        .find_map(|task| find_task_mut(&mut task.subtasks, id))
+}
+
+pub fn is_url(word: &str) -> bool {
+    word.starts_with("https://") || word.starts_with("http://")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "l" | "low" | "3" => Some(Priority::Low),
+            "m" | "medium" | "2" => Some(Priority::Medium),
+            "h" | "high" | "1" => Some(Priority::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+}
+
+/// How a task with subtasks follows their completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionPolicy {
+    /// Completes when all subtasks are, and reopens when one is reopened.
+    #[default]
+    Auto,
+    /// Only completes when completed itself, showing done/total subtasks.
+    Manual,
+    /// Like `Manual`, but shows the progress as a percentage.
+    Percent,
+}
+
+impl CompletionPolicy {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "auto" => Some(CompletionPolicy::Auto),
+            "manual" => Some(CompletionPolicy::Manual),
+            "percent" | "%" => Some(CompletionPolicy::Percent),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompletionPolicy::Auto => "auto",
+            CompletionPolicy::Manual => "manual",
+            CompletionPolicy::Percent => "percent",
+        }
+    }
+}
+
+/// When to remind about a task, independent of its due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reminder {
+    At(DateTime<Utc>),
+    BeforeDue { minutes: i64 },
+}
+
+impl Reminder {
+    /// Parses `30m before`, `2h before`, `1d before` or a date and time in
+    /// the display time zone.
+    pub fn parse(input: &str, dates: &DateDisplay) -> Result<Self, &'static str> {
+        let input = input.trim();
+        if let Some(offset) = input.strip_suffix("before") {
+            let offset = offset.trim();
+            let (amount, unit) = offset.split_at(offset.len().saturating_sub(1));
+            let amount: i64 = amount
+                .parse()
+                .map_err(|_| "Expected e.g. 30m, 2h or 1d before")?;
+            let minutes = match unit {
+                "m" => amount,
+                "h" => amount * 60,
+                "d" => amount * 60 * 24,
+                _ => return Err("Expected e.g. 30m, 2h or 1d before"),
+            };
+            return Ok(Reminder::BeforeDue { minutes });
+        }
+        dates.parse_local(input).map(Reminder::At)
+    }
+
+    /// When the reminder fires for `task`, if it can.
+    pub fn time(&self, task: &Task) -> Option<DateTime<Utc>> {
+        match *self {
+            Reminder::At(time) => Some(time),
+            Reminder::BeforeDue { minutes } => task
+                .due_time
+                .map(|due| due - chrono::Duration::minutes(minutes)),
+        }
+    }
+
+    /// The reminder as [`Reminder::parse`] accepts it.
+    pub fn describe(&self, dates: &DateDisplay) -> String {
+        match *self {
+            Reminder::At(time) => dates.local(time).format("%Y-%m-%d %H:%M").to_string(),
+            Reminder::BeforeDue { minutes } if minutes % (60 * 24) == 0 => {
+                format!("{}d before", minutes / (60 * 24))
+            }
+            Reminder::BeforeDue { minutes } if minutes % 60 == 0 => {
+                format!("{}h before", minutes / 60)
+            }
+            Reminder::BeforeDue { minutes } => format!("{}m before", minutes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Filter {
+    /// Done, or open; cancelled tasks are neither.
+    Completed(bool),
+    Cancelled,
+    /// Open and delegated, to anyone or to the named person.
+    Waiting(Option<String>),
+    /// Completed between `min` and `max` local days ago, inclusive.
+    CompletedDaysAgo {
+        min: u32,
+        max: u32,
+    },
+    /// Created between `min` and `max` local days ago, inclusive.
+    CreatedDaysAgo {
+        min: u32,
+        max: u32,
+    },
+    Tag(String),
+    Context(String),
+    /// Due in the bucket, with today in the local time zone.
+    Due(DueBucket),
+    /// The description contains `text`. When `fold` is set, `text` is
+    /// already folded and the description is folded before comparing, see
+    /// [`fold_text`].
+    Text {
+        text: String,
+        fold: bool,
+    },
+    /// In the subtree of a task, itself included. `target` is the task's
+    /// description or short id as typed, `id` the task it resolved to, see
+    /// [`Model::parse_filter`].
+    Under {
+        target: String,
+        id: Option<Uuid>,
+    },
+    /// Snoozed, for any reason or until the focus is on the context.
+    Snoozed(Option<String>),
+    /// Added or completed by the person.
+    By(String),
+    /// Has the metadata field, with the value if one is given.
+    Meta {
+        key: String,
+        value: Option<String>,
+    },
+    /// The criterion doesn't hold.
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Whether `task` matches, with days in the local time zone.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_at(task, &[], &DateDisplay::default())
+    }
+
+    /// Whether `task`, below the tasks `ancestors` (root first), matches.
+    /// Days, as in `done:today`, are those of the `dates` time zone.
+    pub fn matches_at(&self, task: &Task, ancestors: &[Uuid], dates: &DateDisplay) -> bool {
+        match self {
+            Filter::Completed(completed) => {
+                task.completed == *completed && task.cancelled.is_none()
+            }
+            Filter::Cancelled => task.cancelled.is_some(),
+            Filter::Waiting(person) => {
+                !task.completed
+                    && task.delegated_to.as_ref().is_some_and(|delegate| {
+                        person.as_ref().is_none_or(|person| person == delegate)
+                    })
+            }
+            Filter::CompletedDaysAgo { min, max } => task
+                .completed_at
+                .is_some_and(|at| days_ago(at, *min, *max, dates)),
+            Filter::CreatedDaysAgo { min, max } => task
+                .created_at()
+                .is_some_and(|at| days_ago(at, *min, *max, dates)),
+            Filter::Tag(tag) => task.tags.contains(tag),
+            Filter::Context(context) => task.contexts.contains(context),
+            Filter::Due(bucket) => dates.bucket(task.due_time, Utc::now()) == *bucket,
+            Filter::Text { text, fold: false } => task.description.contains(text.as_str()),
+            Filter::Text { text, fold: true } => {
+                fold_text(&task.description).contains(text.as_str())
+            }
+            Filter::Under { id, .. } => {
+                id.is_some_and(|id| task.id == id || ancestors.contains(&id))
+            }
+            Filter::Snoozed(context) => match (&task.snoozed, context) {
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(Snooze::UntilContext(until)), Some(context)) => until == context,
+            },
+            Filter::By(person) => [&task.author, &task.completed_by]
+                .into_iter()
+                .any(|name| name.as_ref() == Some(person)),
+            Filter::Meta { key, value } => task
+                .meta
+                .get(key)
+                .is_some_and(|found| value.as_ref().is_none_or(|value| value == found)),
+            Filter::Not(filter) => !filter.matches_at(task, ancestors, dates),
+        }
+    }
+}
+
+/// `at` was between `min` and `max` days ago in the `dates` time zone,
+/// inclusive.
+fn days_ago(at: DateTime<Utc>, min: u32, max: u32, dates: &DateDisplay) -> bool {
+    let today = dates.local_date(Utc::now());
+    let days = (today - dates.local_date(at)).num_days();
+    (i64::from(min)..=i64::from(max)).contains(&days)
+}
+
+/// The criterion as it would be typed, so `Filter::parse` reads it back.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Filter::Completed(completed) => write!(f, "completed:{}", completed),
+            Filter::Cancelled => write!(f, "cancelled"),
+            Filter::Waiting(None) => write!(f, "waiting"),
+            Filter::Waiting(Some(person)) => write!(f, "waiting:{}", person),
+            Filter::CompletedDaysAgo { min: 0, max: 0 } => write!(f, "done:today"),
+            Filter::CompletedDaysAgo { min: 1, max: 1 } => write!(f, "done:yesterday"),
+            Filter::CompletedDaysAgo { max, .. } => write!(f, "done:{}d", max + 1),
+            Filter::CreatedDaysAgo { min: 0, max: 0 } => write!(f, "created:today"),
+            Filter::CreatedDaysAgo { min: 1, max: 1 } => write!(f, "created:yesterday"),
+            Filter::CreatedDaysAgo { max, .. } => write!(f, "created:{}d", max + 1),
+            Filter::Tag(tag) => write!(f, "{}", tag),
+            Filter::Context(context) => write!(f, "{}", context),
+            Filter::Due(bucket) => write!(f, "due:{}", bucket.as_str()),
+            Filter::Text { text, fold: false } => write!(f, "text:{}", quote(text)),
+            Filter::Text { text, fold: true } => write!(f, "text:{}/i", quote(text)),
+            Filter::Under { target, .. } => write!(f, "under:{}", quote(target)),
+            Filter::Snoozed(None) => write!(f, "snoozed"),
+            Filter::Snoozed(Some(context)) => write!(f, "snoozed:{}", context),
+            Filter::By(person) => write!(f, "by:{}", person),
+            Filter::Meta { key, value: None } => write!(f, "meta.{}", key),
+            Filter::Meta {
+                key,
+                value: Some(value),
+            } => write!(f, "meta.{}={}", key, quote(value)),
+            Filter::Not(filter) => write!(f, "not {}", filter),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterList {
+    pub filters: Vec<Filter>,
+}
+
+/// Where and why parsing filter criteria failed. `offset` and `len` are byte
+/// positions in the parsed input, used to underline the offending span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterParseError {
+    pub offset: usize,
+    pub len: usize,
+    pub message: String,
+    pub expected: Vec<&'static str>,
+}
+
+impl fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if !self.expected.is_empty() {
+            write!(f, " (expected {})", self.expected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Filter {
+    /// Parses a single criterion: `#tag`, `@context`, `tag:<name>`,
+    /// `context:<name>`, `completed:<true|false>`, `cancelled`, `waiting`,
+    /// `waiting:<name>`, `done:<today|yesterday|Nd>` and
+    /// `created:<today|yesterday|Nd>`, the last meaning within the last N
+    /// days, `due:<overdue|today|week|later|none>`,
+    /// `text:<word>`, which ignores case and accents when followed by `/i`,
+    /// `snoozed`, `snoozed:<context>`, `by:<name>`, `meta.<key>`,
+    /// `meta.<key>=<value>` or `under:<description|short id>`,
+    /// left to resolve. Values with
+    /// spaces are quoted, e.g. `under:"Project X"`.
+    /// Error offsets are relative to `part`.
+    pub fn parse(part: &str) -> Result<Filter, FilterParseError> {
+        if part.starts_with('#') {
+            return Ok(Filter::Tag(part.to_string()));
+        }
+        if part.starts_with('@') {
+            return Ok(Filter::Context(part.to_string()));
+        }
+        match part {
+            "cancelled" => return Ok(Filter::Cancelled),
+            "waiting" => return Ok(Filter::Waiting(None)),
+            "snoozed" => return Ok(Filter::Snoozed(None)),
+            _ => {}
+        }
+        if let Some(field) = part.strip_prefix("meta.") {
+            let (key, value) = match field.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(unquote(value.trim()))),
+                None => (field, None),
+            };
+            if key.is_empty() || value.is_some_and(str::is_empty) {
+                return Err(FilterParseError {
+                    offset: "meta.".len(),
+                    len: field.len().max(1),
+                    message: "Invalid metadata criterion".to_string(),
+                    expected: vec!["meta.<key>", "meta.<key>=<value>"],
+                });
+            }
+            return Ok(Filter::Meta {
+                key: key.to_string(),
+                value: value.map(str::to_string),
+            });
+        }
+        match part.split_once(':') {
+            Some(("completed", "true")) => Ok(Filter::Completed(true)),
+            Some(("completed", "false")) => Ok(Filter::Completed(false)),
+            Some(("completed", value)) => Err(FilterParseError {
+                offset: "completed:".len(),
+                len: value.len().max(1),
+                message: "Invalid completion state".to_string(),
+                expected: vec!["true", "false"],
+            }),
+            Some(("done", value)) => parse_days_ago(value)
+                .map(|(min, max)| Filter::CompletedDaysAgo { min, max })
+                .ok_or_else(|| FilterParseError {
+                    offset: "done:".len(),
+                    len: value.len().max(1),
+                    message: "Invalid completion date".to_string(),
+                    expected: vec!["today", "yesterday", "a number of days like 7d"],
+                }),
+            Some(("created", value)) => parse_days_ago(value)
+                .map(|(min, max)| Filter::CreatedDaysAgo { min, max })
+                .ok_or_else(|| FilterParseError {
+                    offset: "created:".len(),
+                    len: value.len().max(1),
+                    message: "Invalid creation date".to_string(),
+                    expected: vec!["today", "yesterday", "a number of days like 7d"],
+                }),
+            Some(("due", value)) => {
+                DueBucket::parse(value)
+                    .map(Filter::Due)
+                    .ok_or_else(|| FilterParseError {
+                        offset: "due:".len(),
+                        len: value.len().max(1),
+                        message: "Invalid due date range".to_string(),
+                        expected: DueBucket::ALL.iter().map(DueBucket::as_str).collect(),
+                    })
+            }
+            Some(("text", text)) if !text.is_empty() => Ok(match text.strip_suffix("/i") {
+                Some(text) if !text.is_empty() => Filter::Text {
+                    text: fold_text(unquote(text)),
+                    fold: true,
+                },
+                _ => Filter::Text {
+                    text: unquote(text).to_string(),
+                    fold: false,
+                },
+            }),
+            Some(("under", target)) if !unquote(target).is_empty() => Ok(Filter::Under {
+                target: unquote(target).to_string(),
+                id: None,
+            }),
+            Some(("waiting", person)) if !person.is_empty() => {
+                Ok(Filter::Waiting(Some(person.to_string())))
+            }
+            Some(("snoozed", context)) if !context.is_empty() => {
+                Ok(Filter::Snoozed(Some(with_sigil('@', context))))
+            }
+            Some(("by", person)) if !person.is_empty() => Ok(Filter::By(person.to_string())),
+            Some(("tag", tag)) if !tag.is_empty() => Ok(Filter::Tag(with_sigil('#', tag))),
+            Some(("context", context)) if !context.is_empty() => {
+                Ok(Filter::Context(with_sigil('@', context)))
+            }
+            Some((
+                key @ ("tag" | "context" | "waiting" | "text" | "under" | "snoozed" | "by"),
+                _,
+            )) => Err(FilterParseError {
+                offset: key.len() + 1,
+                len: 1,
+                message: format!("Missing {} name", key),
+                expected: vec!["a name"],
+            }),
+            _ => Err(FilterParseError {
+                offset: 0,
+                len: part.len(),
+                message: "Unknown criterion".to_string(),
+                expected: vec![
+                    "#tag",
+                    "@context",
+                    "tag:",
+                    "context:",
+                    "completed:",
+                    "cancelled",
+                    "waiting",
+                    "done:",
+                    "created:",
+                    "due:",
+                    "text:",
+                    "under:",
+                    "snoozed",
+                    "by:",
+                    "meta.",
+                    "not",
+                ],
+            }),
+        }
+    }
+}
+
+/// The `min` and `max` days ago of `today`, `yesterday` or `Nd`, the last
+/// meaning within the last N days.
+fn parse_days_ago(value: &str) -> Option<(u32, u32)> {
+    match value {
+        "today" => Some((0, 0)),
+        "yesterday" => Some((1, 1)),
+        _ => match value.strip_suffix('d')?.parse::<u32>() {
+            Ok(days) if days > 0 => Some((0, days - 1)),
+            _ => None,
+        },
+    }
+}
+
+/// Parses `input` into `filters`. `expanding` holds the macros being
+/// expanded, to catch ones that refer to themselves. Errors inside a macro
+/// point at its name in `input`.
+fn expand_criteria<'a>(
+    input: &str,
+    macros: &'a FilterMacros,
+    expanding: &mut Vec<&'a str>,
+    filters: &mut Vec<Filter>,
+) -> Result<(), FilterParseError> {
+    let mut negated: Option<&str> = None;
+    for part in criteria(input)? {
+        // `part` is a subslice of `input`, so this is its byte offset.
+        let part_offset = part.as_ptr() as usize - input.as_ptr() as usize;
+        if part == "not" && negated.is_none() {
+            negated = Some(part);
+            continue;
+        }
+        let mut parsed = Vec::new();
+        expand_criterion(part, part_offset, macros, expanding, &mut parsed)?;
+        match (negated.take(), parsed.len()) {
+            (None, _) => filters.extend(parsed),
+            // A macro can be negated if it stands for a single criterion
+            (Some(_), 1) => filters.push(Filter::Not(Box::new(parsed.remove(0)))),
+            (Some(_), _) => {
+                return Err(FilterParseError {
+                    offset: part_offset,
+                    len: part.len(),
+                    message: "Only a single criterion can follow `not`".to_string(),
+                    expected: Vec::new(),
+                })
+            }
+        }
+    }
+    if let Some(not) = negated {
+        return Err(FilterParseError {
+            offset: not.as_ptr() as usize - input.as_ptr() as usize,
+            len: not.len(),
+            message: "Missing criterion after `not`".to_string(),
+            expected: vec!["a criterion"],
+        });
+    }
+    Ok(())
+}
+
+/// Parses one criterion, or expands one macro, into `filters`.
+fn expand_criterion<'a>(
+    part: &str,
+    part_offset: usize,
+    macros: &'a FilterMacros,
+    expanding: &mut Vec<&'a str>,
+    filters: &mut Vec<Filter>,
+) -> Result<(), FilterParseError> {
+    let error = match Filter::parse(part) {
+        Ok(filter) => {
+            filters.push(filter);
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+    let Some((name, criteria)) = macros.get_key_value(part) else {
+        return Err(FilterParseError {
+            offset: part_offset + error.offset,
+            ..error
+        });
+    };
+    let in_macro = |message: String| FilterParseError {
+        offset: part_offset,
+        len: part.len(),
+        message,
+        expected: Vec::new(),
+    };
+    if expanding.contains(&name) {
+        return Err(in_macro(format!("Macro `{}` refers to itself", name)));
+    }
+    expanding.push(name);
+    expand_criteria(criteria, macros, expanding, filters)
+        .map_err(|error| in_macro(format!("In `{}`: {}", name, error)))?;
+    expanding.pop();
+    Ok(())
+}
+
+/// Splits `input` at whitespace outside double quotes.
+fn criteria(input: &str) -> Result<Vec<&str>, FilterParseError> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (offset, ch) in input.char_indices() {
+        if ch.is_whitespace() && !quoted {
+            parts.extend(start.take().map(|start| &input[start..offset]));
+            continue;
+        }
+        start.get_or_insert(offset);
+        if ch == '"' {
+            quoted = !quoted;
+        }
+    }
+    if let (true, Some(start)) = (quoted, start) {
+        return Err(FilterParseError {
+            offset: start,
+            len: input.len() - start,
+            message: "Unterminated quote".to_string(),
+            expected: vec!["\""],
+        });
+    }
+    parts.extend(start.map(|start| &input[start..]));
+    Ok(join_comparisons(input, parts))
+}
+
+/// Joins `meta.key = value`, typed with spaces around the `=`, back into a
+/// single criterion.
+fn join_comparisons<'a>(input: &'a str, parts: Vec<&'a str>) -> Vec<&'a str> {
+    let offset = |part: &str| part.as_ptr() as usize - input.as_ptr() as usize;
+    let mut joined: Vec<&str> = Vec::new();
+    for part in parts {
+        match joined.last_mut() {
+            Some(last)
+                if last.starts_with("meta.")
+                    && (last.ends_with('=') || !last.contains('=') && part.starts_with('=')) =>
+            {
+                *last = &input[offset(last)..offset(part) + part.len()];
+            }
+            _ => joined.push(part),
+        }
+    }
+    joined
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// `text` lowercased and without accents, so `Café` and `cafe` compare
+/// equal.
+pub fn fold_text(text: &str) -> String {
+    text.nfd()
+        .filter(|&ch| !is_combining_mark(ch))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub(crate) fn with_sigil(sigil: char, name: &str) -> String {
+    if name.starts_with(sigil) {
+        name.to_string()
+    } else {
+        format!("{}{}", sigil, name)
+    }
+}
+
+impl FilterList {
+    /// Parses whitespace separated criteria, all of which have to match.
+    pub fn parse(input: &str) -> Result<FilterList, FilterParseError> {
+        Self::parse_with(input, &FilterMacros::default())
+    }
+
+    /// Like [`FilterList::parse`], expanding the names of `macros` into their
+    /// criteria.
+    pub fn parse_with(input: &str, macros: &FilterMacros) -> Result<FilterList, FilterParseError> {
+        let mut filters = Vec::new();
+        expand_criteria(input, macros, &mut Vec::new(), &mut filters)?;
+        Ok(FilterList { filters })
+    }
+
+    /// Whether `task` matches, with days in the local time zone.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_at(task, &[], &DateDisplay::default())
+    }
+
+    /// Whether `task`, below the tasks `ancestors` (root first), matches.
+    pub fn matches_at(&self, task: &Task, ancestors: &[Uuid], dates: &DateDisplay) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        self.filters
+            .iter()
+            .all(|filter| filter.matches_at(task, ancestors, dates))
+    }
+
+    /// The criteria, looking inside `not`.
+    fn criteria(&self) -> impl Iterator<Item = &Filter> {
+        self.filters.iter().map(|mut filter| {
+            while let Filter::Not(inner) = filter {
+                filter = inner;
+            }
+            filter
+        })
+    }
+
+    fn criteria_mut(&mut self) -> impl Iterator<Item = &mut Filter> {
+        self.filters.iter_mut().map(|mut filter| {
+            while let Filter::Not(inner) = filter {
+                filter = inner;
+            }
+            filter
+        })
+    }
+
+    /// Makes every text criterion ignore case and accents.
+    pub fn fold_text(&mut self) {
+        for filter in self.criteria_mut() {
+            if let Filter::Text { text, fold } = filter {
+                if !*fold {
+                    *text = fold_text(text);
+                    *fold = true;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortKey {
+    #[default]
+    Manual,
+    Description,
+    Priority,
+    Due,
+    Created,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Manual => SortKey::Description,
+            SortKey::Description => SortKey::Priority,
+            SortKey::Priority => SortKey::Due,
+            SortKey::Due => SortKey::Created,
+            SortKey::Created => SortKey::Manual,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Manual => "manual",
+            SortKey::Description => "description",
+            SortKey::Priority => "priority",
+            SortKey::Due => "due",
+            SortKey::Created => "created",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupBy {
+    #[default]
+    None,
+    Priority,
+    Completion,
+    /// Overdue, today, this week, later, then undated.
+    Due,
+    /// Who the task is delegated to, by name, then tasks nobody else has.
+    Delegate,
+}
+
+impl GroupBy {
+    pub fn next(self) -> Self {
+        match self {
+            GroupBy::None => GroupBy::Priority,
+            GroupBy::Priority => GroupBy::Completion,
+            GroupBy::Completion => GroupBy::Due,
+            GroupBy::Due => GroupBy::Delegate,
+            GroupBy::Delegate => GroupBy::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::Priority => "priority",
+            GroupBy::Completion => "completion",
+            GroupBy::Due => "due",
+            GroupBy::Delegate => "delegate",
+        }
+    }
+}
+
+/// How many days the timeline spans across its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimelineScale {
+    #[default]
+    Week,
+    Month,
+    Quarter,
+}
+
+impl TimelineScale {
+    pub fn next(self) -> Self {
+        match self {
+            TimelineScale::Week => TimelineScale::Month,
+            TimelineScale::Month => TimelineScale::Quarter,
+            TimelineScale::Quarter => TimelineScale::Week,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimelineScale::Week => "week",
+            TimelineScale::Month => "month",
+            TimelineScale::Quarter => "quarter",
+        }
+    }
+
+    /// The number of days shown, in whole weeks.
+    pub fn days(&self) -> i64 {
+        match self {
+            TimelineScale::Week => 7,
+            TimelineScale::Month => 35,
+            TimelineScale::Quarter => 91,
+        }
+    }
+
+    /// Whole weeks shown before the current one, so recent history stays in
+    /// sight at the wider scales.
+    pub fn lead_weeks(&self) -> i64 {
+        match self {
+            TimelineScale::Week => 0,
+            TimelineScale::Month => 1,
+            TimelineScale::Quarter => 2,
+        }
+    }
+}
+
+/// How far back the activity heatmap reaches, in weeks before the current one.
+pub const ACTIVITY_WEEKS: i64 = 52;
+
+/// How many colors the terminal can show. Rendering is written for 256
+/// colors and falls back to fewer, or none, when the terminal can't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSupport {
+    #[default]
+    Indexed,
+    /// The 16 named ANSI colors.
+    Basic,
+    None,
+}
+
+/// What the activity heatmap counts per day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ActivityMetric {
+    #[default]
+    Created,
+    Completed,
+}
+
+impl ActivityMetric {
+    pub fn toggle(self) -> Self {
+        match self {
+            ActivityMetric::Created => ActivityMetric::Completed,
+            ActivityMetric::Completed => ActivityMetric::Created,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityMetric::Created => "created",
+            ActivityMetric::Completed => "completed",
+        }
+    }
+}
+
+/// How completed and cancelled tasks are set apart in the list, on top of
+/// their checkbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletedStyle {
+    pub dim: bool,
+    pub strike: bool,
+}
+
+/// How the tasks matched by a view are presented. Saved together with the
+/// view so that switching views also switches the presentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayOptions {
+    pub sort: SortKey,
+    pub group_by: GroupBy,
+    pub show_completed: bool,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            sort: SortKey::Manual,
+            group_by: GroupBy::None,
+            show_completed: true,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Siblings in display order: grouped first, then sorted within groups.
+    /// The sort is stable, so ties keep their manual order. `dates` decides
+    /// which day is today when grouping by due date.
+    pub fn order<'a>(&self, tasks: &'a IndexMap<Uuid, Task>, dates: &DateDisplay) -> Vec<&'a Task> {
+        let now = Utc::now();
+        let mut ordered: Vec<&Task> = tasks.values().collect();
+        ordered.sort_by(|a, b| {
+            let group = match self.group_by {
+                GroupBy::None => Ordering::Equal,
+                GroupBy::Priority => b.priority.cmp(&a.priority),
+                GroupBy::Completion => a.completed.cmp(&b.completed),
+                GroupBy::Due => dates
+                    .bucket(a.due_time, now)
+                    .cmp(&dates.bucket(b.due_time, now)),
+                GroupBy::Delegate => match (&a.delegated_to, &b.delegated_to) {
+                    (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+            };
+            group.then_with(|| match self.sort {
+                SortKey::Manual => Ordering::Equal,
+                SortKey::Description => a
+                    .description
+                    .to_lowercase()
+                    .cmp(&b.description.to_lowercase()),
+                SortKey::Priority => b.priority.cmp(&a.priority),
+                SortKey::Due => match (a.due_time, b.due_time) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+                SortKey::Created => a.created_at().cmp(&b.created_at()),
+            })
+        });
+        ordered
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    pub filter_lists: Vec<FilterList>,
+    #[serde(default)]
+    pub display: DisplayOptions,
+}
+
+impl View {
+    /// Views that exist without being saved, loaded by name unless a saved
+    /// view shadows them.
+    pub fn builtin(name: &str) -> Option<View> {
+        match name {
+            "waiting" => Some(View {
+                filter_lists: vec![FilterList {
+                    filters: vec![Filter::Waiting(None)],
+                }],
+                display: DisplayOptions {
+                    group_by: GroupBy::Delegate,
+                    show_completed: false,
+                    ..DisplayOptions::default()
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    /// The view with `filter` additionally required by every filter list.
+    pub fn with_conjunct(&self, filter: &Filter) -> View {
+        let mut view = self.clone();
+        if view.filter_lists.is_empty() {
+            view.filter_lists.push(FilterList {
+                filters: Vec::new(),
+            });
+        }
+        for filter_list in &mut view.filter_lists {
+            filter_list.filters.push(filter.clone());
+        }
+        view
+    }
+
+    /// Whether what the view matches changes with the clock alone, as for
+    /// `due:overdue` or `done:today`.
+    pub fn depends_on_time(&self) -> bool {
+        self.filter_lists
+            .iter()
+            .flat_map(FilterList::criteria)
+            .any(|filter| {
+                matches!(
+                    filter,
+                    Filter::Due(_)
+                        | Filter::CompletedDaysAgo { .. }
+                        | Filter::CreatedDaysAgo { .. }
+                )
+            })
+    }
+
+    /// Whether `task` is left out of the list even under a matching parent,
+    /// as if by an implicit conjunct: when it is completed and those are
+    /// hidden, or snoozed while focusing on `focus` and the view doesn't ask
+    /// for snoozed tasks.
+    pub fn hides(&self, task: &Task, focus: Option<&str>) -> bool {
+        (!self.display.show_completed && task.completed)
+            || (task.is_snoozed(focus)
+                && !self
+                    .filter_lists
+                    .iter()
+                    .flat_map(FilterList::criteria)
+                    .any(|filter| matches!(filter, Filter::Snoozed(_))))
+    }
+
+    /// Whether `task` matches, with days in the local time zone.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_at(task, &[], &DateDisplay::default())
+    }
+
+    /// Whether `task`, below the tasks `ancestors` (root first), matches.
+    pub fn matches_at(&self, task: &Task, ancestors: &[Uuid], dates: &DateDisplay) -> bool {
+        if self.filter_lists.is_empty() {
+            return true;
+        }
+        self.filter_lists
+            .iter()
+            .any(|filter_list| filter_list.matches_at(task, ancestors, dates))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Mode {
+    List,
+    Calendar,
+    /// The next seven days as columns, to schedule the backlog into.
+    Planning,
+    /// Dated tasks as bars from their start to their due date.
+    Timeline,
+    /// A heatmap of the tasks created or completed on each day.
+    Stats,
+    Quit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Overlay {
+    None,
+    AddingTask,
+    AddingSubtask,
+    AddingFilterCriterion,
+    View,
+    Navigation,
+    Help,
+    Debug,
+    Messages,
+    TaskForm,
+    Confirm,
+    AddingAttachment,
+    Welcome,
+    SwitchingList,
+    Command,
+    MovingTask,
+    CancellingTask,
+    /// Every tag with its count, to rename, merge or delete them.
+    Tags,
+    FocusPicker,
+    RenamingTag,
+    MergingTag,
+    /// The selected task's history.
+    History,
+    /// Startup filtering was slow, see [`StartupProfile`].
+    SlowStartup,
+    /// Fuzzy search over every task, to jump to one.
+    Jump,
+    /// Waiting for the register to bookmark the selected task under.
+    SettingMark,
+    /// Every mark, waiting for the register to jump to.
+    Marks,
+    /// Filters derived from the selected task, applied by digit.
+    FilterSuggestions,
+    /// The saved views, to load them or bind them to digit keys.
+    Views,
+    /// What the last rollover changed, offering to undo it.
+    Rollover,
+    /// Today's completed and added tasks and tomorrow's due ones.
+    Summary,
+    /// The selected task's comments, with an input to add one.
+    Comments,
+    /// Every column, to show, hide, reorder and resize them.
+    Columns,
+    /// Saving failed; stays up until acknowledged.
+    SaveFailed,
+}
+
+/// Selection and scroll offset of a rendered list. Mirrors ratatui's
+/// `ListState` so the model stays independent of the terminal UI; the view
+/// converts between the two when drawing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListState {
+    selected: Option<usize>,
+    /// Saved so a restart opens the list scrolled where it was left.
+    #[serde(default)]
+    offset: usize,
+}
+
+impl ListState {
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+        if index.is_none() {
+            self.offset = 0;
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+}
+
+/// Tasks left by jumps (search, inbox, line numbers, zoom), walked with
+/// `Ctrl-O`/`Ctrl-N` like vim's jump list. Oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct JumpList {
+    entries: Vec<Uuid>,
+    /// Where `back`/`forward` stand; `entries.len()` when past the newest.
+    position: usize,
+}
+
+impl JumpList {
+    pub const CAPACITY: usize = 100;
+
+    /// Remembers `from` as the task a jump left, dropping any earlier entry
+    /// for it so each task appears once.
+    pub fn record(&mut self, from: Uuid) {
+        self.entries.retain(|&id| id != from);
+        self.entries.push(from);
+        if self.entries.len() > Self::CAPACITY {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len();
+    }
+
+    /// The entry before the current position. The first step back also
+    /// records `current`, so `forward` can return to it.
+    pub fn back(&mut self, current: Option<Uuid>) -> Option<Uuid> {
+        if self.position == self.entries.len() {
+            if let Some(current) = current {
+                self.entries.retain(|&id| id != current);
+                self.entries.push(current);
+            }
+            self.position = self.entries.len().saturating_sub(1);
+        }
+        self.position = self.position.checked_sub(1)?;
+        Some(self.entries[self.position])
+    }
+
+    /// The entry after the current position, if `back` went past it.
+    pub fn forward(&mut self) -> Option<Uuid> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
+        }
+        self.position += 1;
+        Some(self.entries[self.position])
+    }
+}
+
+/// Suggestions get the digit keys 1 to 9.
+pub const FILTER_SUGGESTIONS: usize = 9;
+
+/// Draw timings of the TUI, shown in the debug overlay.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStats {
+    pub frames: u64,
+    pub last: Duration,
+    pub max: Duration,
+    total: Duration,
+}
+
+impl FrameStats {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.frames += 1;
+        self.last = elapsed;
+        self.max = self.max.max(elapsed);
+        self.total += elapsed;
+    }
+
+    pub fn average(&self) -> Duration {
+        match u32::try_from(self.frames) {
+            Ok(0) => Duration::ZERO,
+            Ok(frames) => self.total / frames,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.frames as f64),
+        }
+    }
+}
+
+/// How many matches the jump overlay lists.
+pub const JUMP_RESULTS: usize = 50;
+
+/// Initial filtering slower than this brings up a warning with
+/// `--profile-startup`.
+pub const SLOW_FILTER_THRESHOLD: Duration = Duration::from_millis(200);
+
+/// Where startup time went, collected with `--profile-startup`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StartupProfile {
+    pub tasks: usize,
+    /// Reading, decrypting and deserializing the data file.
+    pub deserialize: Duration,
+    /// Evaluating the initial view against every task.
+    pub filter: Duration,
+    pub first_draw: Option<Duration>,
+}
+
+/// A named task tree that isn't currently shown. The active list lives
+/// directly in the [`Model`] fields, see [`Model::switch_list`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskList {
+    pub tasks: IndexMap<Uuid, Task>,
+    pub current_view: View,
+    pub selected_view: String,
+    pub selected: Option<Uuid>,
+    #[serde(default)]
+    pub inbox: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Model {
+    /// Schema version of the data file, see [`crate::migrations`].
+    #[serde(default)]
+    pub version: u32,
+    pub tasks: IndexMap<Uuid, Task>,
+    pub list_state: ListState,
+    pub mode: Mode,
+    pub overlay: Overlay,
+    #[serde(skip)]
+    pub input: Field,
+    pub command_input: String,
+    pub taskbar_info: String,
+    #[serde(skip)]
+    pub messages: StatusMessages,
+    pub nav: IndexMap<Uuid, Vec<Uuid>>,
+    pub selected: Option<Uuid>,
+    pub tags: HashSet<String>,
+    pub contexts: HashSet<String>,
+    pub autocomplete_suggestions: Vec<String>,
+    pub debug_scroll: u16,
+    pub current_view: View,
+    pub selected_view: String,
+    pub saved_views: IndexMap<String, View>,
+    /// Saved views loaded by a digit key in the list, bound in the views
+    /// overlay.
+    #[serde(default)]
+    pub view_keys: BTreeMap<char, String>,
+    /// Selection in the views overlay.
+    #[serde(skip)]
+    pub views_state: ListState,
+    pub navigation_input: String,
+    #[serde(default)]
+    pub relative_line_numbers: bool,
+    #[serde(skip)]
+    pub pending_count: Option<usize>,
+    #[serde(default)]
+    pub show_short_ids: bool,
+    #[serde(default)]
+    pub show_sidebar: bool,
+    #[serde(default)]
+    pub date_display: DateDisplay,
+    #[serde(default)]
+    pub symbols: Symbols,
+    #[serde(default)]
+    pub completed_style: CompletedStyle,
+    /// The columns of each task row, see [`crate::columns`].
+    #[serde(default = "columns::default_columns")]
+    pub columns: Vec<ColumnSpec>,
+    /// Selection in the columns overlay.
+    #[serde(skip)]
+    pub column_state: ListState,
+    /// Scroll to far jumps and flash tasks an undo changed, see
+    /// [`crate::animation`].
+    #[serde(default)]
+    pub animations: bool,
+    #[serde(skip)]
+    pub animation: Animation,
+    /// Reminders up to this time have been shown; later ones fire on the
+    /// next tick that passes them, even after a restart.
+    #[serde(default)]
+    pub reminders_checked_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub sidebar_focused: bool,
+    #[serde(skip)]
+    pub sidebar_state: ListState,
+    /// Selection in the tag management overlay.
+    #[serde(skip)]
+    pub tag_state: ListState,
+    #[serde(default)]
+    pub sidebar_filter: Option<Filter>,
+    /// A context required on top of every view while set, e.g. `@office`.
+    #[serde(default)]
+    pub focus_context: Option<String>,
+    /// Selection in the focus context picker.
+    #[serde(skip)]
+    pub focus_state: ListState,
+    /// Bookmarked tasks by register, set with `M` and jumped to with `'`.
+    #[serde(default)]
+    pub marks: BTreeMap<char, Uuid>,
+    /// Selection among the results of the jump overlay.
+    #[serde(skip)]
+    pub jump_state: ListState,
+    #[serde(skip)]
+    pub jumps: JumpList,
+    /// Selection in the backlog of the planning board.
+    #[serde(skip)]
+    pub planning_state: ListState,
+    #[serde(skip)]
+    pub timeline_scale: TimelineScale,
+    #[serde(skip)]
+    pub activity_metric: ActivityMetric,
+    /// The day picked in the heatmap, today when unset.
+    #[serde(skip)]
+    pub activity_day: Option<NaiveDate>,
+    #[serde(skip)]
+    pub match_cache: HashMap<Uuid, bool>,
+    #[serde(skip)]
+    pub match_cache_view: Option<View>,
+    /// The minute since the epoch the cached view was last evaluated in, see
+    /// [`View::depends_on_time`].
+    #[serde(skip)]
+    pub match_cache_minute: Option<i64>,
+    #[serde(skip)]
+    pub dirty: HashSet<Uuid>,
+    #[serde(skip)]
+    pub flat_order: OnceLock<FlatOrder>,
+    #[serde(skip)]
+    pub filter_job: Option<FilterJob>,
+    #[serde(skip)]
+    pub filter_generation: u64,
+    #[serde(skip)]
+    pub job_dirty: HashSet<Uuid>,
+    #[serde(skip)]
+    pub filter_preview: Option<FilterList>,
+    #[serde(skip)]
+    pub filter_error: Option<FilterParseError>,
+    #[serde(skip)]
+    pub filter_preview_input: String,
+    #[serde(skip)]
+    pub filter_preview_changed_at: Option<Instant>,
+    #[serde(skip)]
+    pub kill_ring: Vec<String>,
+    #[serde(default)]
+    pub task_history: InputHistory,
+    #[serde(default)]
+    pub filter_history: InputHistory,
+    #[serde(skip)]
+    pub form: Form,
+    #[serde(skip)]
+    pub form_target: Option<Vec<Uuid>>,
+    #[serde(skip)]
+    pub pending_confirmation: Option<(String, Box<Msg>)>,
+    /// Why the last save failed, until a save succeeds or the failure is
+    /// dismissed. Quitting while it is set skips the final save.
+    #[serde(skip)]
+    pub save_error: Option<String>,
+    #[serde(default = "default_confirm_delete_threshold")]
+    pub confirm_delete_threshold: usize,
+    #[serde(skip)]
+    pub data_file: Option<String>,
+    #[serde(default = "default_list_name")]
+    pub current_list: String,
+    #[serde(default)]
+    pub lists: IndexMap<String, TaskList>,
+    /// The view to return to while the done-today review replaces it.
+    #[serde(skip)]
+    pub review_stash: Option<View>,
+    /// The top-level task collecting captured tasks, see [`Model::capture`].
+    #[serde(default)]
+    pub inbox: Option<Uuid>,
+    /// How parents follow their subtasks unless a task overrides it.
+    #[serde(default)]
+    pub completion_policy: CompletionPolicy,
+    /// Text criteria ignore case and accents even without `/i`.
+    #[serde(default)]
+    pub fold_text_filters: bool,
+    /// The rollover to run when the app starts, if any.
+    #[serde(default)]
+    pub rollover_on_start: Option<Rollover>,
+    /// The last rollover pass, until it is undone.
+    #[serde(skip)]
+    pub last_rollover: Option<rollover::Report>,
+    /// A Markdown file every completion is appended to, for a done-log
+    /// outside the data file.
+    #[serde(default)]
+    pub journal: Option<PathBuf>,
+    /// The tasks synced with the CalDAV server, as of the last sync.
+    #[serde(default)]
+    pub caldav: SyncState,
+    /// Where to sync with, from [`caldav::default_file`].
+    #[serde(skip)]
+    pub caldav_config: Option<caldav::Config>,
+    /// Path of the task whose subtree the list is narrowed to, empty when
+    /// showing the whole tree.
+    #[serde(default)]
+    pub zoom: Vec<Uuid>,
+    #[serde(skip)]
+    pub scripts: Scripts,
+    #[serde(skip)]
+    pub filter_macros: FilterMacros,
+    #[serde(skip)]
+    pub events: Vec<Event>,
+    #[serde(skip)]
+    pub frame_stats: FrameStats,
+    #[serde(skip)]
+    pub startup_profile: Option<StartupProfile>,
+    #[serde(skip)]
+    pub color_support: ColorSupport,
+    /// Render with plain ASCII and spelled-out markers instead of glyphs
+    /// and colors, for screen readers and dumb terminals.
+    #[serde(skip)]
+    pub accessible: bool,
+    /// Browse without changing anything: messages that would edit tasks are
+    /// turned away and nothing is written back.
+    #[serde(skip)]
+    pub read_only: bool,
+    /// The name stamped onto tasks added and completed in this session, so
+    /// people sharing a file can tell who did what. Set from `--author` or
+    /// `$USER` rather than stored in the shared file.
+    #[serde(skip)]
+    pub author: Option<String>,
+}
+
+const FILTER_PREVIEW_DEBOUNCE: Duration = Duration::from_millis(150);
+
+fn default_confirm_delete_threshold() -> usize {
+    3
+}
+
+fn default_list_name() -> String {
+    "Tasks".to_string()
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(None);
+
+        let current_view = View {
+            filter_lists: Vec::new(),
+            display: DisplayOptions::default(),
+        };
+        let mut saved_views = IndexMap::new();
+        let selected_view = "default".to_string();
+        saved_views.insert(selected_view.clone(), current_view.clone());
+
+        Self {
+            version: crate::migrations::CURRENT_VERSION,
+            tasks: IndexMap::new(),
+            list_state,
+            mode: Mode::List,
+            overlay: Overlay::None,
+            input: Field::default(),
+            command_input: String::new(),
+            taskbar_info: String::new(),
+            messages: StatusMessages::default(),
+            nav: IndexMap::new(),
+            selected: None,
+            tags: HashSet::new(),
+            contexts: HashSet::new(),
+            autocomplete_suggestions: Vec::new(),
+            debug_scroll: 0,
+            current_view,
+            selected_view,
+            saved_views,
+            view_keys: BTreeMap::new(),
+            views_state: ListState::default(),
+            navigation_input: String::new(),
+            relative_line_numbers: false,
+            pending_count: None,
+            show_short_ids: false,
+            show_sidebar: false,
+            date_display: DateDisplay::default(),
+            symbols: Symbols::default(),
+            completed_style: CompletedStyle::default(),
+            columns: columns::default_columns(),
+            column_state: ListState::default(),
+            animations: false,
+            animation: Animation::default(),
+            reminders_checked_at: None,
+            sidebar_focused: false,
+            sidebar_state: ListState::default(),
+            tag_state: ListState::default(),
+            sidebar_filter: None,
+            focus_context: None,
+            focus_state: ListState::default(),
+            jump_state: ListState::default(),
+            jumps: JumpList::default(),
+            marks: BTreeMap::new(),
+            planning_state: ListState::default(),
+            timeline_scale: TimelineScale::default(),
+            activity_metric: ActivityMetric::default(),
+            activity_day: None,
+            match_cache: HashMap::new(),
+            match_cache_view: None,
+            match_cache_minute: None,
+            dirty: HashSet::new(),
+            flat_order: OnceLock::new(),
+            filter_job: None,
+            filter_generation: 0,
+            job_dirty: HashSet::new(),
+            filter_preview: None,
+            filter_error: None,
+            filter_preview_input: String::new(),
+            filter_preview_changed_at: None,
+            kill_ring: Vec::new(),
+            task_history: InputHistory::default(),
+            filter_history: InputHistory::default(),
+            form: Form::default(),
+            form_target: None,
+            pending_confirmation: None,
+            save_error: None,
+            confirm_delete_threshold: default_confirm_delete_threshold(),
+            data_file: None,
+            current_list: default_list_name(),
+            lists: IndexMap::new(),
+            review_stash: None,
+            inbox: None,
+            completion_policy: CompletionPolicy::default(),
+            fold_text_filters: false,
+            rollover_on_start: None,
+            last_rollover: None,
+            journal: None,
+            caldav: SyncState::default(),
+            caldav_config: None,
+            zoom: Vec::new(),
+            scripts: Scripts::default(),
+            filter_macros: FilterMacros::default(),
+            events: Vec::new(),
+            frame_stats: FrameStats::default(),
+            startup_profile: None,
+            color_support: ColorSupport::default(),
+            accessible: false,
+            read_only: false,
+            author: None,
+        }
+    }
+
+    /// Queues a message for the status bar, see [`crate::status`].
+    pub fn notify(&mut self, severity: Severity, message: &str) {
+        self.messages.push(severity, message);
+    }
+
+    pub fn get_path(&self) -> Vec<Uuid> {
+        self.selected
+            .and_then(|selected| self.nav.get(&selected))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The siblings of the task at `path`, if all its ancestors exist.
+    fn get_task_list(&self, path: &[Uuid]) -> Option<&IndexMap<Uuid, Task>> {
+        path[..path.len().saturating_sub(1)]
+            .iter()
+            .try_fold(&self.tasks, |tasks, id| {
+                tasks.get(id).map(|task| &task.subtasks)
+            })
+    }
+
+    /// Mutable access to a list of siblings. Callers may restructure the tree
+    /// through it, so the cached flattened order is dropped.
+    pub fn get_task_list_mut(&mut self, path: &[Uuid]) -> &mut IndexMap<Uuid, Task> {
+        self.flat_order.take();
+        let mut current_tasks = &mut self.tasks;
+        for &uuid in &path[..path.len().saturating_sub(1)] {
+            current_tasks = &mut current_tasks[&uuid].subtasks;
+        }
+        current_tasks
+    }
+
+    pub fn get_task(&self, path: &[Uuid]) -> Option<&Task> {
+        match path.last() {
+            Some(last) => self.get_task_list(path)?.get(last),
+            None => None,
+        }
+    }
+
+    /// Mutable access to a task. Since the caller may change the task or
+    /// anything below it, the whole subtree is marked for filter re-evaluation.
+    pub fn get_task_mut(&mut self, path: &[Uuid]) -> Option<&mut Task> {
+        let last = path.last()?;
+        let mut subtree = Vec::new();
+        self.get_task(path)?.collect_ids(&mut subtree);
+        self.dirty.extend(subtree);
+        self.get_task_list_mut(path).get_mut(last)
+    }
+
+    /// Descriptions of the tasks along `path`, from the top level down, for
+    /// breadcrumbs. Stops at the first id that isn't found.
+    pub fn path_descriptions(&self, path: &[Uuid]) -> Vec<&str> {
+        let mut tasks = &self.tasks;
+        let mut descriptions = Vec::new();
+        for id in path {
+            let Some(task) = tasks.get(id) else {
+                break;
+            };
+            descriptions.push(task.description.as_str());
+            tasks = &task.subtasks;
+        }
+        descriptions
+    }
+
+    /// Zooms out to the nearest ancestor that still exists after the zoom
+    /// root was removed or moved elsewhere.
+    pub fn clamp_zoom(&mut self) {
+        let mut tasks = &self.tasks;
+        let mut depth = 0;
+        while let Some(task) = self.zoom.get(depth).and_then(|id| tasks.get(id)) {
+            tasks = &task.subtasks;
+            depth += 1;
+        }
+        self.zoom.truncate(depth);
+    }
+
+    /// All tasks with their paths in manual order, cached until the tree is
+    /// next mutated.
+    pub fn flattened(&self) -> &[(Uuid, Vec<Uuid>)] {
+        &self.flat_order().rows
+    }
+
+    fn flat_order(&self) -> &FlatOrder {
+        self.flat_order
+            .get_or_init(|| FlatOrder::new(flatten_tasks(&self.tasks)))
+    }
+
+    /// The path to the task with `id` anywhere in the tree, looked up in the
+    /// cached flattened order rather than by walking the tree.
+    pub fn path_of(&self, id: Uuid) -> Option<Vec<Uuid>> {
+        let order = self.flat_order();
+        let row = *order.index.get(&id)?;
+        Some(order.rows[row].1.clone())
+    }
+
+    /// Re-evaluates `view` for tasks changed since the last call, reusing
+    /// cached results for everything else. A different view drops the cache,
+    /// or for large trees, re-evaluates everything on a worker thread while
+    /// the stale results keep being shown. So does a new minute for views
+    /// that follow the clock.
+    pub fn refresh_match_cache(&mut self, view: &View) {
+        if let Some(job) = &self.filter_job {
+            if let Some(mut matches) = job.try_take() {
+                if job.generation == self.filter_generation {
+                    // Tasks changed while the worker ran were evaluated against
+                    // a stale snapshot; let `fill` below redo them.
+                    for id in self.job_dirty.drain() {
+                        matches.remove(&id);
+                    }
+                    self.match_cache = matches;
+                }
+                self.filter_job = None;
+            }
+        }
+
+        let minute = Utc::now().timestamp().div_euclid(60);
+        if view.depends_on_time() && self.match_cache_minute != Some(minute) {
+            self.match_cache_view = None;
+        }
+        if self.match_cache_view.as_ref() != Some(view) {
+            self.match_cache_view = Some(view.clone());
+            self.match_cache_minute = Some(minute);
+            if self.flattened().len() > BACKGROUND_FILTER_THRESHOLD {
+                self.filter_generation += 1;
+                self.job_dirty.clear();
+                self.filter_job = Some(FilterJob::spawn(
+                    self.filter_generation,
+                    self.tasks.clone(),
+                    view.clone(),
+                    self.date_display.clone(),
+                ));
+            } else {
+                self.filter_job = None;
+                self.match_cache = worker::evaluate(&self.tasks, view, &self.date_display);
+                self.dirty.clear();
+            }
+        }
+        for id in self.dirty.drain() {
+            self.match_cache.remove(&id);
+            if self.filter_job.is_some() {
+                self.job_dirty.insert(id);
+            }
+        }
+
+        fn fill(
+            tasks: &IndexMap<Uuid, Task>,
+            view: &View,
+            dates: &DateDisplay,
+            ancestors: &mut Vec<Uuid>,
+            cache: &mut HashMap<Uuid, bool>,
+        ) {
+            for task in tasks.values() {
+                cache
+                    .entry(task.id)
+                    .or_insert_with(|| view.matches_at(task, ancestors, dates));
+                ancestors.push(task.id);
+                fill(&task.subtasks, view, dates, ancestors, cache);
+                ancestors.pop();
+            }
+        }
+        fill(
+            &self.tasks,
+            view,
+            &self.date_display,
+            &mut Vec::new(),
+            &mut self.match_cache,
+        );
+    }
+
+    /// Evaluates `view` for every task on this thread, even for trees that
+    /// would go to a worker, so the time it takes can be measured.
+    pub fn filter_now(&mut self, view: &View) {
+        self.filter_job = None;
+        self.job_dirty.clear();
+        self.dirty.clear();
+        self.match_cache = worker::evaluate(&self.tasks, view, &self.date_display);
+        self.match_cache_view = Some(view.clone());
+        self.match_cache_minute = Some(Utc::now().timestamp().div_euclid(60));
+    }
+
+    /// How many tasks the view last given to [`Model::refresh_match_cache`]
+    /// matches, or `None` if it has no filters and matches them all.
+    pub fn match_count(&self) -> Option<MatchCount> {
+        fn count(tasks: &IndexMap<Uuid, Task>, cache: &HashMap<Uuid, bool>, acc: &mut MatchCount) {
+            for task in tasks.values() {
+                acc.total += 1;
+                if cache.get(&task.id).copied().unwrap_or(false) {
+                    acc.matching += 1;
+                    if task.is_done() {
+                        acc.done += 1;
+                    }
+                }
+                count(&task.subtasks, cache, acc);
+            }
+        }
+        if self.match_cache_view.as_ref()?.filter_lists.is_empty() {
+            return None;
+        }
+        let mut acc = MatchCount::default();
+        count(&self.tasks, &self.match_cache, &mut acc);
+        Some(acc)
+    }
+
+    /// Names of all lists, the active one included, sorted by name.
+    pub fn list_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.lists.keys().map(String::as_str).collect();
+        names.push(&self.current_list);
+        names.sort_unstable();
+        names
+    }
+
+    /// Stashes the active task tree, its view and selection under the current
+    /// list name and brings up `name`, creating an empty list if needed.
+    pub fn switch_list(&mut self, name: &str) {
+        if name == self.current_list {
+            return;
+        }
+        let next = self.lists.shift_remove(name).unwrap_or_else(|| TaskList {
+            selected_view: self.selected_view.clone(),
+            ..TaskList::default()
+        });
+        let previous = TaskList {
+            tasks: std::mem::replace(&mut self.tasks, next.tasks),
+            current_view: std::mem::replace(&mut self.current_view, next.current_view),
+            selected_view: std::mem::replace(&mut self.selected_view, next.selected_view),
+            selected: std::mem::replace(&mut self.selected, next.selected),
+            inbox: std::mem::replace(&mut self.inbox, next.inbox),
+        };
+        let previous_name = std::mem::replace(&mut self.current_list, name.to_string());
+        self.lists.insert(previous_name, previous);
+        self.emit(Event::ListSwitched(name.to_string()));
+
+        self.nav.clear();
+        self.zoom.clear();
+        self.flat_order.take();
+        self.match_cache.clear();
+        self.match_cache_view = None;
+        self.dirty.clear();
+        self.filter_job = None;
+        self.job_dirty.clear();
+    }
+
+    pub fn add_task(&mut self) {
+        let new_task = Task::new(self.input.text());
+        self.insert_task(new_task);
+    }
+
+    /// Inserts `task` as a sibling of the selected task, or when nothing is
+    /// selected, under the zoom root or into the inbox, and selects it.
+    pub fn insert_task(&mut self, task: Task) {
+        let new_id = task.id;
+        let path = self.get_path();
+        if path.is_empty() && self.zoom.is_empty() {
+            self.capture(task);
+        } else if path.is_empty() {
+            let zoom = self.zoom.clone();
+            if let Some(root) = self.get_task_mut(&zoom) {
+                root.subtasks.insert(new_id, task);
+            }
+            self.flat_order.take();
+            self.emit(Event::TaskAdded(new_id));
+        } else {
+            self.get_task_list_mut(&path).insert(task.id, task);
+            self.emit(Event::TaskAdded(new_id));
+        }
+        self.selected = Some(new_id);
+    }
+
+    /// The id of the inbox, creating it at the top of the list if it doesn't
+    /// exist (yet, or anymore).
+    pub fn inbox_id(&mut self) -> Uuid {
+        if let Some(id) = self.inbox.filter(|id| self.tasks.contains_key(id)) {
+            return id;
+        }
+        let inbox = Task::new("Inbox");
+        let id = inbox.id;
+        self.get_task_list_mut(&[]).shift_insert(0, id, inbox);
+        self.inbox = Some(id);
+        self.emit(Event::TaskAdded(id));
+        id
+    }
+
+    /// Adds `task` to the end of the inbox, where tasks captured from outside
+    /// the tree (or added with nothing selected) wait to be triaged.
+    pub fn capture(&mut self, task: Task) {
+        let inbox_id = self.inbox_id();
+        let id = task.id;
+        if let Some(inbox) = self.get_task_mut(&[inbox_id]) {
+            let completed = inbox.completed && task.completed;
+            inbox.set_completed(completed);
+            inbox.subtasks.insert(id, task);
+        }
+        self.flat_order.take();
+        self.emit(Event::TaskAdded(id));
+    }
+
+    /// Queues a change notification, see [`crate::events`]. Tasks being
+    /// added or completed are stamped with the [`Model::author`] on the way.
+    pub fn emit(&mut self, event: Event) {
+        let stamped = match &event {
+            Event::TaskAdded(id) if self.author.is_some() => Some(*id),
+            Event::TaskCompleted(id) | Event::TaskReopened(id) => Some(*id),
+            _ => None,
+        };
+        if let Some(task) = stamped.and_then(|id| find_task_mut(&mut self.tasks, id)) {
+            match event {
+                Event::TaskAdded(_) => task.author = self.author.clone(),
+                Event::TaskCompleted(_) => task.completed_by = self.author.clone(),
+                _ => task.completed_by = None,
+            }
+            self.dirty.insert(task.id);
+        }
+        self.events.push(event);
+    }
+
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn add_subtask(&mut self) {
+        let new_task = Task::new(self.input.text());
+        let new_id = new_task.id;
+        let path = self.get_path();
+        if let Some(task) = self.get_task_mut(&path) {
+            task.subtasks.insert(new_task.id, new_task);
+            self.selected = Some(new_id);
+            self.emit(Event::TaskAdded(new_id));
+        } else {
+            self.notify(
+                Severity::Warning,
+                "Can't add a subtask without a selected task!",
+            );
+        }
+    }
+
+    /// The view tasks are rendered with: the current view narrowed by the
+    /// sidebar selection, if any.
+    pub fn effective_view(&self) -> View {
+        let mut view = self.current_view.clone();
+        if let (Overlay::AddingFilterCriterion, Some(preview)) =
+            (&self.overlay, &self.filter_preview)
+        {
+            view.filter_lists.push(preview.clone());
+        }
+        if let Some(context) = &self.focus_context {
+            view = view.with_conjunct(&Filter::Context(context.clone()));
+        }
+        match &self.sidebar_filter {
+            Some(filter) => view.with_conjunct(filter),
+            None => view,
+        }
+    }
+
+    /// Paths of the tasks whose descriptions fuzzy-match `query`, best first,
+    /// regardless of the view. At most [`JUMP_RESULTS`] are returned.
+    pub fn jump_results(&self, query: &str) -> Vec<Vec<Uuid>> {
+        let mut results: Vec<(i64, Vec<Uuid>)> = flatten_tasks(&self.tasks)
+            .into_iter()
+            .filter_map(|(_, path)| {
+                let task = self.get_task(&path)?;
+                Some((fuzzy::score(query, &task.description)?, path))
+            })
+            .collect();
+        // Stable, so equal scores keep the manual order
+        results.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        results.truncate(JUMP_RESULTS);
+        results.into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Whether the task at `path` makes it into the list under `view`, the
+    /// way the list is built: a task shows when it matches or its parent
+    /// shows, unless the view hides it.
+    fn is_listed(&self, path: &[Uuid], view: &View) -> bool {
+        if path.len() <= self.zoom.len() || !path.starts_with(&self.zoom) {
+            return false;
+        }
+        // A matching zoom root or ancestor of it shows the whole subtree
+        let mut parent_shown = (1..=self.zoom.len())
+            .filter_map(|depth| Some((self.get_task(&path[..depth])?, &path[..depth - 1])))
+            .any(|(task, ancestors)| view.matches_at(task, ancestors, &self.date_display));
+        for depth in self.zoom.len() + 1..=path.len() {
+            let Some(task) = self.get_task(&path[..depth]) else {
+                return false;
+            };
+            let hidden = view.hides(task, self.focus_context.as_deref());
+            parent_shown = !hidden
+                && (parent_shown || view.matches_at(task, &path[..depth - 1], &self.date_display));
+        }
+        parent_shown
+    }
+
+    /// Selects the task at `path`, zooming out as far as needed and clearing
+    /// the filters, focus and sidebar filter if they would hide it.
+    pub fn reveal(&mut self, path: &[Uuid]) {
+        let Some((&id, parent)) = path.split_last() else {
+            return;
+        };
+        let common = self
+            .zoom
+            .iter()
+            .zip(parent)
+            .take_while(|(zoom, parent)| zoom == parent)
+            .count();
+        self.zoom.truncate(common);
+        if !self.is_listed(path, &self.effective_view()) {
+            self.current_view.filter_lists.clear();
+            self.focus_context = None;
+            self.sidebar_filter = None;
+            if !self.is_listed(path, &self.effective_view()) {
+                self.current_view.display.show_completed = true;
+            }
+            if !self.is_listed(path, &self.effective_view()) {
+                self.current_view.filter_lists = vec![FilterList {
+                    filters: vec![Filter::Snoozed(None)],
+                }];
+            }
+            self.notify(Severity::Info, "Cleared the filter to show the task.");
+        }
+        self.selected = Some(id);
+    }
+
+    /// Paths of the tasks the planning board offers to schedule: open tasks
+    /// without subtasks that are undated or overdue, in manual order.
+    pub fn planning_backlog(&self, now: DateTime<Utc>) -> Vec<Vec<Uuid>> {
+        flatten_tasks(&self.tasks)
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| {
+                self.get_task(path).is_some_and(|task| {
+                    !task.completed
+                        && task.subtasks.is_empty()
+                        && task.due_time.is_none_or(|due| due < now)
+                })
+            })
+            .collect()
+    }
+
+    /// Paths of the tasks the timeline draws: those in the current view with
+    /// a start or due date, in manual order.
+    pub fn timeline_rows(&self) -> Vec<Vec<Uuid>> {
+        let view = self.effective_view();
+        flatten_tasks(&self.tasks)
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| {
+                self.get_task(path).is_some_and(|task| {
+                    (task.start_time.is_some() || task.due_time.is_some())
+                        && !view.hides(task, self.focus_context.as_deref())
+                        && view.matches_at(task, &path[..path.len() - 1], &self.date_display)
+                })
+            })
+            .collect()
+    }
+
+    /// Paths of the tasks created, or completed, on each local day. Creation
+    /// times come from the version 7 ids; cancelled tasks never count as
+    /// completed.
+    pub fn activity(&self, metric: ActivityMetric) -> HashMap<NaiveDate, Vec<Vec<Uuid>>> {
+        let mut days: HashMap<NaiveDate, Vec<Vec<Uuid>>> = HashMap::new();
+        for (_, path) in flatten_tasks(&self.tasks) {
+            let Some(task) = self.get_task(&path) else {
+                continue;
+            };
+            let time = match metric {
+                ActivityMetric::Created => task.created_at(),
+                ActivityMetric::Completed => task.completed_at.filter(|_| task.is_done()),
+            };
+            if let Some(time) = time {
+                let day = self.date_display.local_date(time);
+                days.entry(day).or_default().push(path);
+            }
+        }
+        days
+    }
+
+    /// Filters narrowing the list to tasks like the selected one: its tags,
+    /// contexts, delegate and due date range, one per digit key.
+    pub fn filter_suggestions(&self) -> Vec<Filter> {
+        let Some(task) = self.get_task(&self.get_path()) else {
+            return Vec::new();
+        };
+        let mut tags: Vec<&String> = task.tags.iter().collect();
+        let mut contexts: Vec<&String> = task.contexts.iter().collect();
+        tags.sort();
+        contexts.sort();
+        tags.into_iter()
+            .map(|tag| Filter::Tag(tag.clone()))
+            .chain(
+                contexts
+                    .into_iter()
+                    .map(|context| Filter::Context(context.clone())),
+            )
+            .chain(
+                task.delegated_to
+                    .clone()
+                    .map(|person| Filter::Waiting(Some(person))),
+            )
+            .chain(std::iter::once(Filter::Due(
+                self.date_display.bucket(task.due_time, Utc::now()),
+            )))
+            .take(FILTER_SUGGESTIONS)
+            .collect()
+    }
+
+    /// The shown columns in their order, then the hidden ones.
+    pub fn column_choices(&self) -> Vec<Column> {
+        let shown: Vec<Column> = self.columns.iter().map(|spec| spec.column).collect();
+        let hidden = Column::ALL
+            .into_iter()
+            .filter(|column| !shown.contains(column));
+        shown.iter().copied().chain(hidden).collect()
+    }
+
+    /// The entries of the focus context picker: no focus, then every context
+    /// in the tree.
+    pub fn focus_choices(&self) -> Vec<Option<String>> {
+        let contexts = self
+            .sidebar_entries()
+            .into_iter()
+            .filter_map(|(filter, _)| match filter {
+                Filter::Context(context) => Some(Some(context)),
+                _ => None,
+            });
+        std::iter::once(None).chain(contexts).collect()
+    }
+
+    /// Re-parses the filter being typed once the input has been stable for
+    /// `FILTER_PREVIEW_DEBOUNCE`, keeping the last valid criteria on errors.
+    pub fn refresh_filter_preview(&mut self) {
+        if !matches!(self.overlay, Overlay::AddingFilterCriterion) {
+            return;
+        }
+        if self.input.text() != self.filter_preview_input {
+            self.filter_preview_input = self.input.text().to_string();
+            self.filter_preview_changed_at = Some(Instant::now());
+            return;
+        }
+        let Some(changed_at) = self.filter_preview_changed_at else {
+            return;
+        };
+        if changed_at.elapsed() < FILTER_PREVIEW_DEBOUNCE {
+            return;
+        }
+        self.filter_preview_changed_at = None;
+        match self.parse_filter(&self.filter_preview_input) {
+            Ok(filter_list) => {
+                self.filter_preview = Some(filter_list);
+                self.filter_error = None;
+            }
+            Err(error) => self.filter_error = Some(error),
+        }
+    }
+
+    /// Parses filter criteria typed by the user, with their macros and
+    /// text matching preference.
+    pub fn parse_filter(&self, input: &str) -> Result<FilterList, FilterParseError> {
+        let mut list = FilterList::parse_with(input, &self.filter_macros)?;
+        if self.fold_text_filters {
+            list.fold_text();
+        }
+        for filter in list.criteria_mut() {
+            if let Filter::Under { target, id } = filter {
+                let path = self
+                    .find_by_description(target)
+                    .map_or_else(|| resolve::resolve(&flatten_tasks(&self.tasks), target), Ok)
+                    .map_err(|error| FilterParseError {
+                        offset: input.find(target.as_str()).unwrap_or(0),
+                        len: target.len(),
+                        message: error.to_string(),
+                        expected: vec!["a task description or short id"],
+                    })?;
+                *id = path.last().copied();
+            }
+        }
+        Ok(list)
+    }
+
+    /// The path of the one task whose description is `description`, ignoring
+    /// case.
+    fn find_by_description(&self, description: &str) -> Option<Vec<Uuid>> {
+        let mut found = flatten_tasks(&self.tasks).into_iter().filter(|(_, path)| {
+            self.get_task(path)
+                .is_some_and(|task| task.description.eq_ignore_ascii_case(description))
+        });
+        match (found.next(), found.next()) {
+            (Some((_, path)), None) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn clear_filter_preview(&mut self) {
+        self.filter_preview = None;
+        self.filter_error = None;
+        self.filter_preview_input.clear();
+        self.filter_preview_changed_at = None;
+    }
+
+    /// Every tag in the tree with its task count, sorted by name.
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        self.sidebar_entries()
+            .into_iter()
+            .filter_map(|(filter, count)| match filter {
+                Filter::Tag(tag) => Some((tag, count)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The saved view highlighted in the views overlay.
+    pub fn selected_saved_view(&self) -> Option<&str> {
+        let index = self.views_state.selected()?;
+        self.saved_views.keys().nth(index).map(String::as_str)
+    }
+
+    /// The tag selected in the tag management overlay.
+    pub fn selected_tag(&self) -> Option<String> {
+        let index = self.tag_state.selected()?;
+        self.tag_counts().into_iter().nth(index).map(|(tag, _)| tag)
+    }
+
+    /// Replaces the tag `from` with `to` in every description of the tree, or
+    /// removes it, and returns how many tasks changed. Tasks that already
+    /// have `to` only lose `from`, which is what merging two tags means.
+    /// Saved views filtering on `from` follow a rename.
+    pub fn rewrite_tag(&mut self, from: &str, to: Option<&str>) -> usize {
+        fn rewrite(
+            tasks: &mut IndexMap<Uuid, Task>,
+            from: &str,
+            to: Option<&str>,
+            changed: &mut Vec<Uuid>,
+        ) {
+            for task in tasks.values_mut() {
+                if task.tags.contains(from) {
+                    let mut to = to.filter(|to| !task.tags.contains(*to));
+                    let description: String = task
+                        .description
+                        .split_inclusive(char::is_whitespace)
+                        .filter_map(|piece| {
+                            let word = piece.trim_end();
+                            if word != from {
+                                Some(piece.to_string())
+                            } else {
+                                // A repeated tag is only replaced once
+                                to.take()
+                                    .map(|to| format!("{}{}", to, &piece[word.len()..]))
+                            }
+                        })
+                        .collect();
+                    task.record(ChangeKind::Edited);
+                    task.update_description(description.trim_end());
+                    changed.push(task.id);
+                }
+                rewrite(&mut task.subtasks, from, to, changed);
+            }
+        }
+
+        let mut changed = Vec::new();
+        rewrite(&mut self.tasks, from, to, &mut changed);
+        for &id in &changed {
+            self.dirty.insert(id);
+            self.emit(Event::TaskChanged(id));
+        }
+        if let Some(to) = to {
+            let views = self
+                .saved_views
+                .values_mut()
+                .chain(std::iter::once(&mut self.current_view));
+            for view in views {
+                for filter in view
+                    .filter_lists
+                    .iter_mut()
+                    .flat_map(|list| &mut list.filters)
+                {
+                    if matches!(filter, Filter::Tag(tag) if tag == from) {
+                        *filter = Filter::Tag(to.to_string());
+                    }
+                }
+            }
+        }
+        changed.len()
+    }
+
+    /// All tags followed by all contexts in the tree, with their task counts.
+    pub fn sidebar_entries(&self) -> Vec<(Filter, usize)> {
+        fn count(tasks: &IndexMap<Uuid, Task>, counts: &mut IndexMap<Filter, usize>) {
+            for task in tasks.values() {
+                for tag in &task.tags {
+                    *counts.entry(Filter::Tag(tag.clone())).or_default() += 1;
+                }
+                for context in &task.contexts {
+                    *counts.entry(Filter::Context(context.clone())).or_default() += 1;
+                }
+                count(&task.subtasks, counts);
+            }
+        }
+
+        let mut counts = IndexMap::new();
+        count(&self.tasks, &mut counts);
+        let mut entries: Vec<(Filter, usize)> = counts.into_iter().collect();
+        entries.sort_by_key(|(filter, _)| match filter {
+            Filter::Tag(tag) => (0, tag.clone()),
+            Filter::Context(context) => (1, context.clone()),
+            Filter::Completed(_)
+            | Filter::Cancelled
+            | Filter::Waiting(_)
+            | Filter::CompletedDaysAgo { .. }
+            | Filter::CreatedDaysAgo { .. }
+            | Filter::Due(_)
+            | Filter::Text { .. }
+            | Filter::Under { .. }
+            | Filter::Snoozed(_)
+            | Filter::By(_)
+            | Filter::Meta { .. }
+            | Filter::Not(_) => (2, String::new()),
+        });
+        entries
+    }
+
+    /// Defers `msg` until the user accepts `prompt` in the confirmation overlay.
+    pub fn request_confirmation(&mut self, prompt: String, msg: Msg) {
+        self.pending_confirmation = Some((prompt, Box::new(msg)));
+        self.overlay = Overlay::Confirm;
+    }
+
+    /// The field keyboard input is currently directed at.
+    pub fn active_field_mut(&mut self) -> &mut Field {
+        match self.overlay {
+            Overlay::TaskForm => self.form.focused_mut(),
+            _ => &mut self.input,
+        }
+    }
+
+    /// The history belonging to the currently open input overlay, if any.
+    pub fn input_history_mut(&mut self) -> Option<&mut InputHistory> {
+        match self.overlay {
+            Overlay::AddingTask | Overlay::AddingSubtask => Some(&mut self.task_history),
+            Overlay::AddingFilterCriterion => Some(&mut self.filter_history),
+            _ => None,
+        }
+    }
+
+    pub fn push_kill(&mut self, killed: String) {
+        if !killed.is_empty() {
+            self.kill_ring.push(killed);
+        }
+    }
+
+    pub fn jump_to_line(&mut self, line: usize) {
+        let max_line = self.nav.len().saturating_sub(1);
+        let target_line = line.min(max_line);
+        if let Some((id, _)) = self.nav.get_index(target_line) {
+            self.selected = Some(*id);
+            self.list_state.select(Some(target_line));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Msg {
+    NoOp,
+    /// Sent periodically by the frontend's event loop so time based state
+    /// (debounces, expiring messages, reminders) advances without input.
+    Tick,
+    Quit,
+    PushChar(char),
+    PopChar,
+    CursorLeft,
+    CursorRight,
+    CursorHome,
+    CursorEnd,
+    KillToStart,
+    KillToEnd,
+    KillWordForward,
+    Yank,
+    TransposeChars,
+    HistoryPrevious,
+    HistoryNext,
+    NewTaskForm,
+    EditTaskForm,
+    FormNextField,
+    FormPreviousField,
+    SubmitTaskForm,
+    DeleteTask,
+    RemoveTask(Vec<Uuid>),
+    /// Removes the tasks at each path, as a counted delete does.
+    RemoveTasks(Vec<Vec<Uuid>>),
+    Confirm,
+    DuplicateTask(Vec<Uuid>),
+    /// Inserts each task at the end of the subtasks of the parent at its
+    /// path (the top level for an empty path). Parents are updated once at
+    /// the end, and the batch is a single entry in a recorded log.
+    AddTasksBulk(Vec<(Vec<Uuid>, Task)>),
+    OpenUrl,
+    AttachFile,
+    CompletePath,
+    OpenAttachment,
+    CompleteAllFiltered,
+    CompleteTasks(Vec<Vec<Uuid>>),
+    AddTask,
+    AddSubtask,
+    ToggleTaskCompletion,
+    SwitchMode(Mode),
+    SetOverlay(Overlay),
+    NavigateTasks(Direction),
+    ScrollDebug(Direction),
+    HandleNavigation,
+    JumpToEnd,
+    PushCount(usize),
+    ToggleRelativeLineNumbers,
+    ToggleShortIds,
+    NavigateColumns(Direction),
+    /// Shows the column at the end of the row, or hides it.
+    ToggleColumn(Column),
+    /// Swaps the column with its neighbour in the row.
+    MoveColumn(Column, Direction),
+    /// Widens or narrows the column by a number of cells.
+    ResizeColumn(Column, i16),
+    ToggleSidebar,
+    FocusSidebar(bool),
+    NavigateSidebar(Direction),
+    SelectSidebarEntry,
+    CycleSortKey,
+    ToggleShowCompleted,
+    CycleGroupBy,
+    AddFilterCriterion,
+    SaveCurrentView(String),
+    SwitchList(String),
+    RunCommand(String),
+    LoadView(String),
+    JumpToInbox,
+    NavigateTags(Direction),
+    NavigateFocusChoices(Direction),
+    NavigatePlanning(Direction),
+    CycleTimelineScale,
+    SaveFailed(String),
+    DismissSaveError,
+    /// Dismisses a vault save error; the frontend then reads the notes
+    /// changed on disk again.
+    ReloadChangedNotes,
+    NavigateJump(Direction),
+    /// Selects the task at the path, zooming out and clearing filters that
+    /// would hide it.
+    JumpToTask(Vec<Uuid>),
+    /// Returns to the task before the last jump (`Ctrl-O`).
+    JumpBack,
+    /// Undoes a `JumpBack` (`Ctrl-N`, or `Ctrl-I` where the terminal
+    /// tells it apart from Tab).
+    JumpForward,
+    /// Bookmarks the selected task under a register.
+    SetMark(char),
+    /// Selects the task bookmarked under a register.
+    JumpToMark(char),
+    /// Narrows the current view by one more criterion.
+    ApplyFilter(Filter),
+    NavigateViews(Direction),
+    /// Binds the saved view selected in the views overlay to a digit key,
+    /// or unbinds it if it already is.
+    BindViewKey(char),
+    ToggleActivityMetric,
+    /// Writes the rows on screen to a file in the working directory.
+    ExportView(export::Format),
+    /// Moves the heatmap selection by a number of days.
+    MoveActivityDay(i64),
+    /// Moves the due date of the task at `path` to `day`, keeping its time
+    /// of day, or clears it.
+    ScheduleTask {
+        path: Vec<Uuid>,
+        day: Option<NaiveDate>,
+    },
+    /// Requires the context on top of every view, or stops doing so.
+    SetFocusContext(Option<String>),
+    /// Renames or merges the tag selected in the tag overlay into the one
+    /// typed into the input.
+    SubmitTagRename,
+    /// Asks to delete the tag selected in the tag overlay from every task.
+    DeleteTag,
+    /// Replaces the tag `from` with `to` across the tree, or removes it.
+    RewriteTag {
+        from: String,
+        to: Option<String>,
+    },
+    /// Cancels the selected task, with the input as the optional reason.
+    CancelTask,
+    /// Appends the input as a comment on the selected task.
+    AddComment,
+    /// Hides the selected task until the condition holds, or shows it again.
+    SnoozeTask(Option<Snooze>),
+    /// Catches up on open tasks due on past days and shows what changed.
+    Rollover(Rollover),
+    /// Reverts the last rollover.
+    UndoRollover,
+    /// Two-way sync with the CalDAV server.
+    Sync,
+    /// Copies today's summary to the clipboard as Markdown.
+    CopySummary,
+    /// Changes the selected task's progress by the given percentage points,
+    /// completing it at 100%.
+    AdjustProgress(i32),
+    /// Narrows the list to the subtree of the task at the path, or shows the
+    /// whole tree again if it is empty.
+    Zoom(Vec<Uuid>),
+    /// Swaps the current view for a read-only one of tasks completed today
+    /// or yesterday, and back.
+    ToggleDoneReview,
+    /// Moves the selected task under the line number or short id typed into
+    /// the move overlay, or to the top level if it is empty.
+    SubmitMove,
+    /// Moves the task at `from` to the end of the subtasks of `to`, or of the
+    /// top level if `to` is empty.
+    MoveTask {
+        from: Vec<Uuid>,
+        to: Vec<Uuid>,
+    },
+}
+
+/// The `:commands` handled by `update` itself; any other name runs a script.
+const BUILTIN_COMMANDS: [&str; 17] = [
+    "messages",
+    "focus",
+    "snooze",
+    "tags",
+    "views",
+    "export",
+    "history",
+    "columns",
+    "comments",
+    "sync",
+    "summary",
+    "completion",
+    "done-style",
+    "text-match",
+    "rollover",
+    "journal",
+    "animations",
+];
+
+impl Msg {
+    /// Whether the message reaches outside the model: opens URLs or files,
+    /// writes files, uses the clipboard or the network, or runs a script.
+    /// Replays skip these, so a log from elsewhere can't do any of that.
+    pub fn has_side_effects(&self) -> bool {
+        match self {
+            Msg::OpenUrl
+            | Msg::OpenAttachment
+            | Msg::ExportView(_)
+            | Msg::CopySummary
+            | Msg::Sync => true,
+            Msg::RunCommand(command) => match command.split_whitespace().next() {
+                Some(name) => {
+                    matches!(name, "export" | "journal" | "sync")
+                        || !BUILTIN_COMMANDS.contains(&name)
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the message changes tasks or tags, or opens a prompt that only
+    /// leads to such a change. Refused in read-only mode.
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Msg::SetOverlay(overlay) => matches!(
+                overlay,
+                Overlay::AddingTask
+                    | Overlay::AddingSubtask
+                    | Overlay::AddingAttachment
+                    | Overlay::MovingTask
+                    | Overlay::CancellingTask
+                    | Overlay::RenamingTag
+                    | Overlay::MergingTag
+            ),
+            Msg::AddTask
+            | Msg::AddSubtask
+            | Msg::ToggleTaskCompletion
+            | Msg::NewTaskForm
+            | Msg::EditTaskForm
+            | Msg::SubmitTaskForm
+            | Msg::DeleteTask
+            | Msg::RemoveTask(_)
+            | Msg::RemoveTasks(_)
+            | Msg::DuplicateTask(_)
+            | Msg::AddTasksBulk(_)
+            | Msg::AttachFile
+            | Msg::CompleteAllFiltered
+            | Msg::CompleteTasks(_)
+            | Msg::ScheduleTask { .. }
+            | Msg::SubmitTagRename
+            | Msg::DeleteTag
+            | Msg::RewriteTag { .. }
+            | Msg::CancelTask
+            | Msg::AddComment
+            | Msg::SnoozeTask(_)
+            | Msg::Rollover(_)
+            | Msg::UndoRollover
+            | Msg::Sync
+            | Msg::AdjustProgress(_)
+            | Msg::SubmitMove
+            | Msg::MoveTask { .. } => true,
+            // Commands that edit tasks or saved settings, and scripts, which
+            // edit tasks through their actions
+            Msg::RunCommand(command) => match command.split_whitespace().next() {
+                Some(name) => {
+                    matches!(
+                        name,
+                        "completion" | "rollover" | "journal" | "snooze" | "sync"
+                    ) || !BUILTIN_COMMANDS.contains(&name)
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Rewrites the task ids the message carries, e.g. to replay a recorded
+    /// message against a model whose tasks were created with other ids.
+    pub fn map_ids(self, map: impl Fn(Uuid) -> Uuid) -> Msg {
+        let map_path = |path: Vec<Uuid>| path.into_iter().map(&map).collect();
+        match self {
+            Msg::RemoveTask(path) => Msg::RemoveTask(map_path(path)),
+            Msg::RemoveTasks(paths) => Msg::RemoveTasks(paths.into_iter().map(map_path).collect()),
+            Msg::DuplicateTask(path) => Msg::DuplicateTask(map_path(path)),
+            Msg::AddTasksBulk(tasks) => Msg::AddTasksBulk(
+                tasks
+                    .into_iter()
+                    .map(|(parent, task)| (map_path(parent), task))
+                    .collect(),
+            ),
+            Msg::Zoom(path) => Msg::Zoom(map_path(path)),
+            Msg::JumpToTask(path) => Msg::JumpToTask(map_path(path)),
+            Msg::ScheduleTask { path, day } => Msg::ScheduleTask {
+                path: map_path(path),
+                day,
+            },
+            Msg::MoveTask { from, to } => Msg::MoveTask {
+                from: map_path(from),
+                to: map_path(to),
+            },
+            Msg::CompleteTasks(paths) => {
+                Msg::CompleteTasks(paths.into_iter().map(map_path).collect())
+            }
+            msg => msg,
+        }
+    }
+}
+
+// The core types are plain owned data (no `Rc`/`RefCell`), so a model can be
+// handed to a worker thread for filtering or saving. Keep it that way.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Task>();
+    assert_send_sync::<View>();
+    assert_send_sync::<Model>();
+    assert_send_sync::<Msg>();
+};
@@ -0,0 +1,162 @@
+use crate::{
+    crypto, migrations,
+    model::{Mode, Model, Overlay},
+};
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use std::{
+    fs::{self, File},
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+
+/// Files smaller than this load fast enough that reporting progress is noise.
+pub const PROGRESS_THRESHOLD: u64 = 1 << 20;
+
+/// Where the data lives when no `-f` is given: `$XDG_DATA_HOME/chors/tasks.json`
+/// on Linux, `~/Library/Application Support/chors/tasks.json` on macOS and
+/// `%APPDATA%\chors\tasks.json` on Windows.
+pub fn default_data_file() -> Option<String> {
+    dirs::data_dir().map(|dir| {
+        dir.join("chors")
+            .join("tasks.json")
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+/// Loads the model from `file_path`, or starts fresh if the file doesn't exist.
+pub fn load(file_path: &str) -> Result<Model> {
+    load_with_progress(file_path, None, |_, _| {})
+}
+
+/// Whether the file at `file_path` exists and was saved with a passphrase.
+pub fn is_encrypted(file_path: &str) -> Result<bool> {
+    if !Path::new(file_path).exists() {
+        return Ok(false);
+    }
+    let mut header = Vec::new();
+    File::open(file_path)?
+        .take(crypto::MAGIC.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(crypto::is_encrypted(&header))
+}
+
+/// Like [`load`], but deserializes straight from the file instead of reading it
+/// into a string first, calling `on_progress(read, total)` as bytes come in.
+/// Files from older versions are upgraded on the way in. Encrypted files
+/// need the `passphrase` they were saved with; plaintext files load as they
+/// are even with one, so they can be saved encrypted from then on.
+pub fn load_with_progress(
+    file_path: &str,
+    passphrase: Option<&str>,
+    on_progress: impl FnMut(u64, u64),
+) -> Result<Model> {
+    if !Path::new(file_path).exists() {
+        return Ok(Model::new());
+    }
+    let file = File::open(file_path)?;
+    let total = file.metadata()?.len();
+    let mut reader = BufReader::new(ProgressReader {
+        inner: file,
+        read: 0,
+        total,
+        on_progress,
+    });
+    let mut data = match (is_encrypted(file_path)?, passphrase) {
+        (true, Some(passphrase)) => {
+            let mut encrypted = Vec::new();
+            reader.read_to_end(&mut encrypted)?;
+            serde_json::from_slice(&crypto::decrypt(&encrypted, passphrase)?)?
+        }
+        (true, None) => bail!("{} is encrypted and no passphrase was given", file_path),
+        (false, _) => serde_json::from_reader(reader)?,
+    };
+    migrations::migrate(&mut data)?;
+    let mut model: Model = serde_json::from_value(data)?;
+    model.mode = Mode::List;
+    // The failure it reported belonged to the previous session
+    if let Overlay::SaveFailed = model.overlay {
+        model.overlay = Overlay::None;
+    }
+    // The selection, scroll offset and zoom are restored as saved, unless a
+    // one-off command has since removed the zoomed task
+    model.clamp_zoom();
+    Ok(model)
+}
+
+/// Saves the model as JSON, encrypted when a `passphrase` is given.
+///
+/// The data goes to a temporary file in the same directory first, is flushed
+/// to disk and read back, and only then renamed over the original. A crash
+/// or a full disk mid-save leaves the previous file intact.
+pub fn save(file_path: &str, model: &Model, passphrase: Option<&str>) -> Result<()> {
+    let data = serde_json::to_string_pretty(model)?;
+    let bytes = match passphrase {
+        Some(passphrase) => crypto::encrypt(data.as_bytes(), passphrase)?,
+        None => data.into_bytes(),
+    };
+
+    let path = Path::new(file_path);
+    let Some(name) = path.file_name() else {
+        bail!("{} is not a file path", file_path);
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let temp = dir.join(format!(".{}.tmp", name.to_string_lossy()));
+    let result = write_and_verify(&temp, &bytes, passphrase).and_then(|()| {
+        // Keep the permissions of the file being replaced
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp, metadata.permissions())?;
+        }
+        fs::rename(&temp, path)?;
+        sync_dir(dir);
+        Ok(())
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_and_verify(temp: &Path, bytes: &[u8], passphrase: Option<&str>) -> Result<()> {
+    let mut file = File::create(temp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+    let temp = temp.to_string_lossy();
+    load_with_progress(&temp, passphrase, |_, _| {})
+        .map_err(|error| eyre!("the saved copy doesn't read back: {}", error))?;
+    Ok(())
+}
+
+/// Flushes the rename itself to disk. Only possible on Unix; elsewhere the
+/// rename is as durable as the file system makes it.
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+struct ProgressReader<R, F> {
+    inner: R,
+    read: u64,
+    total: u64,
+    on_progress: F,
+}
+
+impl<R: Read, F: FnMut(u64, u64)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        (self.on_progress)(self.read, self.total);
+        Ok(n)
+    }
+}
@@ -0,0 +1,50 @@
+//! Short, human-typeable task ids.
+//!
+//! UUIDv7s start with a timestamp, so tasks created around the same time share
+//! long prefixes. Short ids are therefore taken from the end of the UUID (its
+//! random part), using the fewest hex digits that keep every id in the tree
+//! unique. Any unambiguous suffix of the full id resolves to its task.
+
+use color_eyre::{eyre::bail, Result};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const MIN_SHORT_ID_LEN: usize = 4;
+
+/// Short ids for every task in `flattened` (see `Model::flattened`).
+pub fn short_ids(flattened: &[(Uuid, Vec<Uuid>)]) -> HashMap<Uuid, String> {
+    let hexes: Vec<(Uuid, String)> = flattened
+        .iter()
+        .map(|(id, _)| (*id, id.simple().to_string()))
+        .collect();
+
+    let mut short_ids = HashMap::new();
+    let mut len = MIN_SHORT_ID_LEN;
+    while short_ids.len() < hexes.len() && len <= 32 {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, hex) in &hexes {
+            *counts.entry(&hex[32 - len..]).or_default() += 1;
+        }
+        for (id, hex) in &hexes {
+            let suffix = &hex[32 - len..];
+            if counts[suffix] == 1 {
+                short_ids.entry(*id).or_insert_with(|| suffix.to_string());
+            }
+        }
+        len += 1;
+    }
+    short_ids
+}
+
+/// Finds the path of the task whose id ends with `short_id`.
+pub fn resolve(flattened: &[(Uuid, Vec<Uuid>)], short_id: &str) -> Result<Vec<Uuid>> {
+    let short_id = short_id.to_lowercase().replace('-', "");
+    let mut matches = flattened
+        .iter()
+        .filter(|(id, _)| id.simple().to_string().ends_with(&short_id));
+    match (matches.next(), matches.next()) {
+        (Some((_, path)), None) => Ok(path.clone()),
+        (Some(_), Some(_)) => bail!("Task id '{}' is ambiguous", short_id),
+        (None, _) => bail!("No task with id '{}'", short_id),
+    }
+}
@@ -0,0 +1,119 @@
+//! Catching up on open tasks that were due on a past day, either by moving
+//! them to today or by tagging them `#overdue`. Each pass keeps what it
+//! changed so it can be undone.
+
+use crate::{
+    events::Event,
+    model::{flatten_tasks, ChangeKind, Model},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const OVERDUE_TAG: &str = "#overdue";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rollover {
+    /// Due today, at the same time of day.
+    Move,
+    /// Tagged [`OVERDUE_TAG`], keeping the due date.
+    Flag,
+}
+
+impl Rollover {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rollover::Move => "move",
+            Rollover::Flag => "flag",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "move" => Some(Rollover::Move),
+            "flag" => Some(Rollover::Flag),
+            _ => None,
+        }
+    }
+}
+
+/// A task as it was before a rollover changed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RolledOver {
+    pub id: Uuid,
+    pub description: String,
+    pub due_time: DateTime<Utc>,
+}
+
+/// What a rollover pass changed, shown in the rollover overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub kind: Rollover,
+    pub tasks: Vec<RolledOver>,
+}
+
+/// Rolls over open tasks due before today in the local time zone. Tasks
+/// already tagged [`OVERDUE_TAG`] are left alone when flagging.
+pub fn run(model: &mut Model, kind: Rollover, now: DateTime<Utc>) -> Report {
+    let dates = model.date_display.clone();
+    let today = dates.local_date(now);
+    let mut tasks = Vec::new();
+    for (_, path) in flatten_tasks(&model.tasks) {
+        let Some(task) = model.get_task(&path) else {
+            continue;
+        };
+        let Some(due) = task.due_time.filter(|due| dates.local_date(*due) < today) else {
+            continue;
+        };
+        if task.completed || (kind == Rollover::Flag && task.tags.contains(OVERDUE_TAG)) {
+            continue;
+        }
+        let moved = match kind {
+            Rollover::Move => match dates.from_local(today.and_time(dates.local(due).time())) {
+                Some(moved) => Some(moved),
+                None => continue,
+            },
+            Rollover::Flag => None,
+        };
+        let task = model.get_task_mut(&path).expect("the task was just found");
+        tasks.push(RolledOver {
+            id: task.id,
+            description: task.description.clone(),
+            due_time: due,
+        });
+        task.record(ChangeKind::Edited);
+        match moved {
+            Some(moved) => task.due_time = Some(moved),
+            None => {
+                let flagged = format!("{} {}", task.description, OVERDUE_TAG);
+                task.update_description(&flagged);
+            }
+        }
+        let id = task.id;
+        model.emit(Event::TaskChanged(id));
+    }
+    Report { kind, tasks }
+}
+
+/// Restores the due dates and descriptions `report` recorded, skipping
+/// tasks deleted since. Returns how many were restored.
+pub fn undo(model: &mut Model, report: &Report) -> usize {
+    let mut restored = 0;
+    for before in &report.tasks {
+        let Some(path) = model.path_of(before.id) else {
+            continue;
+        };
+        let Some(task) = model.get_task_mut(&path) else {
+            continue;
+        };
+        task.record(ChangeKind::Edited);
+        task.due_time = Some(before.due_time);
+        if task.description != before.description {
+            task.update_description(&before.description);
+        }
+        model.emit(Event::TaskChanged(before.id));
+        restored += 1;
+    }
+    restored
+}
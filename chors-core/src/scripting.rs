@@ -0,0 +1,303 @@
+//! User commands written in [Rhai](https://rhai.rs).
+//!
+//! Every `*.rhai` file in the scripts directory becomes a command named after
+//! the file, run with `:name [args...]`. A `// key: x` comment on the first
+//! line also binds it to `x` in list mode. Scripts get a read-only snapshot of
+//! the tasks in `tasks` and change the model only through the functions
+//! registered in [`run`], which are applied with regular messages once the
+//! script has finished.
+//!
+//! ```rhai
+//! // key: W
+//! // Move #today tasks that are more than a week overdue to #backlog
+//! for task in tasks {
+//!     if "#today" in task.tags && task.due != () && now - task.due > 7 * 86400 {
+//!         remove_tag(task.id, "#today");
+//!         add_tag(task.id, "#backlog");
+//!     }
+//! }
+//! ```
+
+use crate::{
+    events::Event,
+    model::{ChangeKind, Model, Msg, Task},
+    status::Severity,
+    update::update,
+};
+use color_eyre::{eyre::eyre, Result};
+use indexmap::IndexMap;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+/// Upper bound on interpreter steps so a runaway loop can't hang the UI.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+#[derive(Debug, Clone)]
+pub struct Script {
+    pub ast: AST,
+    pub key: Option<char>,
+}
+
+/// Scripts loaded from disk, by command name.
+#[derive(Debug, Clone, Default)]
+pub struct Scripts {
+    pub commands: IndexMap<String, Script>,
+}
+
+impl Scripts {
+    /// Compiles every `*.rhai` file in `dir`. A missing directory just means
+    /// no scripts; files that fail to compile are reported by name.
+    pub fn load_dir(dir: &Path) -> (Self, Vec<String>) {
+        let mut scripts = Scripts::default();
+        let mut errors = Vec::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (scripts, errors);
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        let engine = engine();
+        for path in paths {
+            let Some(name) = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+            else {
+                continue;
+            };
+            let source = match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(err) => {
+                    errors.push(format!("{}: {}", name, err));
+                    continue;
+                }
+            };
+            match engine.compile(&source) {
+                Ok(ast) => {
+                    let key = parse_key_binding(&source);
+                    scripts.commands.insert(name, Script { ast, key });
+                }
+                Err(err) => errors.push(format!("{}: {}", name, err)),
+            }
+        }
+        (scripts, errors)
+    }
+
+    /// The script bound to `key` in list mode, if any.
+    pub fn bound_to(&self, key: char) -> Option<&str> {
+        self.commands
+            .iter()
+            .find(|(_, script)| script.key == Some(key))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// `$XDG_CONFIG_HOME/chors/scripts` or the platform equivalent.
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chors").join("scripts"))
+}
+
+fn parse_key_binding(source: &str) -> Option<char> {
+    let binding = source.lines().next()?.trim().strip_prefix("//")?.trim();
+    let mut key = binding.strip_prefix("key:")?.trim().chars();
+    match (key.next(), key.next()) {
+        (Some(key), None) => Some(key),
+        _ => None,
+    }
+}
+
+/// A change requested by a script, applied after it returns.
+#[derive(Debug, Clone)]
+enum Action {
+    AddTask(String),
+    Complete(String),
+    Delete(String),
+    SetDescription(String, String),
+    AddTag(String, String),
+    RemoveTag(String, String),
+    Message(String),
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine
+}
+
+/// Runs the script `name` with `args` and applies the changes it asked for.
+pub fn run(model: &mut Model, name: &str, args: &[String]) -> Result<()> {
+    let script = model
+        .scripts
+        .commands
+        .get(name)
+        .ok_or_else(|| eyre!("Unknown command: {}", name))?
+        .clone();
+
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let mut engine = engine();
+    {
+        let push = |actions: &Arc<Mutex<Vec<Action>>>, action: Action| {
+            actions.lock().unwrap().push(action);
+        };
+        let a = actions.clone();
+        engine.register_fn("add_task", move |description: &str| {
+            push(&a, Action::AddTask(description.to_string()))
+        });
+        let a = actions.clone();
+        engine.register_fn("complete", move |id: &str| {
+            push(&a, Action::Complete(id.to_string()))
+        });
+        let a = actions.clone();
+        engine.register_fn("delete", move |id: &str| {
+            push(&a, Action::Delete(id.to_string()))
+        });
+        let a = actions.clone();
+        engine.register_fn("set_description", move |id: &str, description: &str| {
+            push(
+                &a,
+                Action::SetDescription(id.to_string(), description.to_string()),
+            )
+        });
+        let a = actions.clone();
+        engine.register_fn("add_tag", move |id: &str, tag: &str| {
+            push(&a, Action::AddTag(id.to_string(), tag.to_string()))
+        });
+        let a = actions.clone();
+        engine.register_fn("remove_tag", move |id: &str, tag: &str| {
+            push(&a, Action::RemoveTag(id.to_string(), tag.to_string()))
+        });
+        let a = actions.clone();
+        engine.register_fn("message", move |text: &str| {
+            push(&a, Action::Message(text.to_string()))
+        });
+    }
+
+    let mut paths = HashMap::new();
+    let mut snapshot = Array::new();
+    for (id, path) in model.flattened().to_vec() {
+        if let Some(task) = model.get_task(&path) {
+            snapshot.push(Dynamic::from_map(task_to_map(task, path.len())));
+        }
+        paths.insert(id.to_string(), path);
+    }
+    let mut scope = Scope::new();
+    scope.push("tasks", snapshot);
+    scope.push(
+        "args",
+        args.iter().cloned().map(Dynamic::from).collect::<Array>(),
+    );
+    scope.push("now", chrono::Utc::now().timestamp());
+
+    engine
+        .run_ast_with_scope(&mut scope, &script.ast)
+        .map_err(|err| eyre!("{}: {}", name, err))?;
+
+    let actions = std::mem::take(&mut *actions.lock().unwrap());
+    let mut completed = Vec::new();
+    for action in actions {
+        match action {
+            Action::AddTask(description) => {
+                let task = Task::new(&description);
+                let id = task.id;
+                model.get_task_list_mut(&[]).insert(id, task);
+                model.emit(Event::TaskAdded(id));
+            }
+            Action::Complete(id) => completed.extend(paths.get(&id).cloned()),
+            Action::Delete(id) => {
+                if let Some(path) = paths.get(&id).filter(|path| model.get_task(path).is_some()) {
+                    update(Msg::RemoveTask(path.clone()), model);
+                }
+            }
+            Action::SetDescription(id, description) => {
+                edit_description(model, &paths, &id, |_| description.clone());
+            }
+            Action::AddTag(id, tag) => {
+                let tag = with_hash(&tag);
+                edit_description(model, &paths, &id, |description| {
+                    if description.split_whitespace().any(|word| word == tag) {
+                        description.to_string()
+                    } else {
+                        format!("{} {}", description, tag)
+                    }
+                });
+            }
+            Action::RemoveTag(id, tag) => {
+                let tag = with_hash(&tag);
+                edit_description(model, &paths, &id, |description| {
+                    description
+                        .split_whitespace()
+                        .filter(|&word| word != tag)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+            }
+            Action::Message(text) => model.notify(Severity::Info, &text),
+        }
+    }
+    if !completed.is_empty() {
+        update(Msg::CompleteTasks(completed), model);
+    }
+    Ok(())
+}
+
+fn task_to_map(task: &Task, depth: usize) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), task.id.to_string().into());
+    map.insert("description".into(), task.description.clone().into());
+    map.insert("completed".into(), task.completed.into());
+    map.insert("depth".into(), (depth as i64).into());
+    let strings = |set: &std::collections::HashSet<String>| {
+        let mut values: Vec<_> = set.iter().cloned().collect();
+        values.sort();
+        values.into_iter().map(Dynamic::from).collect::<Array>()
+    };
+    map.insert("tags".into(), strings(&task.tags).into());
+    map.insert("contexts".into(), strings(&task.contexts).into());
+    let timestamp = |time: Option<chrono::DateTime<chrono::Utc>>| match time {
+        Some(time) => Dynamic::from(time.timestamp()),
+        None => Dynamic::UNIT,
+    };
+    map.insert("start".into(), timestamp(task.start_time));
+    map.insert("due".into(), timestamp(task.due_time));
+    map.insert(
+        "priority".into(),
+        match task.priority {
+            Some(priority) => priority.as_str().into(),
+            None => Dynamic::UNIT,
+        },
+    );
+    map
+}
+
+fn with_hash(tag: &str) -> String {
+    if tag.starts_with('#') {
+        tag.to_string()
+    } else {
+        format!("#{}", tag)
+    }
+}
+
+fn edit_description(
+    model: &mut Model,
+    paths: &HashMap<String, Vec<Uuid>>,
+    id: &str,
+    edit: impl FnOnce(&str) -> String,
+) {
+    if let Some(task) = paths.get(id).and_then(|path| model.get_task_mut(path)) {
+        let description = edit(&task.description);
+        if description != task.description {
+            task.record(ChangeKind::Edited);
+        }
+        task.update_description(&description);
+        let id = task.id;
+        model.emit(Event::TaskChanged(id));
+    }
+}
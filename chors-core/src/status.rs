@@ -0,0 +1,124 @@
+//! Notifications shown in the status bar.
+//!
+//! Messages queue up and are shown one at a time. Informational and success
+//! messages expire on their own a few seconds after they reach the status bar,
+//! driven by [`Msg::Tick`](crate::model::Msg::Tick); warnings and errors stay
+//! until the next action dismisses them. Every message is also kept in a log
+//! for the `:messages` overlay.
+
+use chrono::{DateTime, Local};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// How long a message stays once shown, or `None` to keep it until it is
+    /// dismissed.
+    fn lifetime(self) -> Option<Duration> {
+        match self {
+            Severity::Info | Severity::Success => Some(Duration::from_secs(3)),
+            Severity::Warning | Severity::Error => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub severity: Severity,
+    pub at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatusMessages {
+    queue: VecDeque<StatusMessage>,
+    /// When the front of the queue reached the status bar.
+    shown_at: Option<Instant>,
+    log: VecDeque<StatusMessage>,
+    /// Bumped whenever what the status bar shows changes.
+    revision: u64,
+}
+
+impl StatusMessages {
+    const MAX_LOG: usize = 100;
+
+    pub fn push(&mut self, severity: Severity, text: &str) {
+        let message = StatusMessage {
+            text: text.to_string(),
+            severity,
+            at: Local::now(),
+        };
+        self.log.push_back(message.clone());
+        if self.log.len() > Self::MAX_LOG {
+            self.log.pop_front();
+        }
+        if self.queue.is_empty() {
+            self.shown_at = Some(Instant::now());
+        }
+        self.queue.push_back(message);
+        self.revision += 1;
+    }
+
+    /// The message to show now, if any.
+    pub fn current(&self) -> Option<&StatusMessage> {
+        self.queue.front()
+    }
+
+    /// Whether the current message goes away on its own, and so needs ticks
+    /// to expire on time.
+    pub fn expiring(&self) -> bool {
+        self.queue
+            .front()
+            .is_some_and(|message| message.severity.lifetime().is_some())
+    }
+
+    /// Number of messages waiting behind the current one.
+    pub fn waiting(&self) -> usize {
+        self.queue.len().saturating_sub(1)
+    }
+
+    /// Drops the current message once its lifetime has passed.
+    pub fn tick(&mut self, now: Instant) {
+        let (Some(front), Some(shown_at)) = (self.queue.front(), self.shown_at) else {
+            return;
+        };
+        if front
+            .severity
+            .lifetime()
+            .is_some_and(|lifetime| now.duration_since(shown_at) >= lifetime)
+        {
+            self.queue.pop_front();
+            self.shown_at = self.queue.front().map(|_| now);
+            self.revision += 1;
+        }
+    }
+
+    /// Dismisses every queued message; they stay in the log.
+    pub fn dismiss(&mut self) {
+        if !self.queue.is_empty() {
+            self.revision += 1;
+        }
+        self.queue.clear();
+        self.shown_at = None;
+    }
+
+    /// Changes whenever the message to show (or the number waiting) does, so
+    /// a frontend can tell whether the status bar needs redrawing.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Past messages, oldest first.
+    pub fn log(&self) -> impl DoubleEndedIterator<Item = &StatusMessage> {
+        self.log.iter()
+    }
+}
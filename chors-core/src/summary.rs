@@ -0,0 +1,82 @@
+//! The end-of-day summary: what was completed and added today and what is
+//! due tomorrow, shown in an overlay and copied as Markdown for a work log.
+
+use crate::{
+    dates::DateDisplay,
+    model::{flatten_tasks, ActivityMetric, Model},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Today in the display time zone.
+    pub day: NaiveDate,
+    pub completed: Vec<String>,
+    pub added: Vec<String>,
+    /// Open tasks due tomorrow, soonest first.
+    pub due_tomorrow: Vec<String>,
+}
+
+/// Summarizes the local day `now` falls on. Cancelled tasks don't count as
+/// completed, see [`Model::activity`].
+pub fn summarize(model: &Model, now: DateTime<Utc>) -> Summary {
+    let dates = &model.date_display;
+    let day = dates.local_date(now);
+    let descriptions = |metric| {
+        let paths = model.activity(metric).remove(&day).unwrap_or_default();
+        paths
+            .iter()
+            .filter_map(|path| model.get_task(path))
+            .map(|task| task.description.clone())
+            .collect()
+    };
+    let tomorrow = day.succ_opt();
+    let mut due: Vec<_> = flatten_tasks(&model.tasks)
+        .into_iter()
+        .filter_map(|(_, path)| model.get_task(&path))
+        .filter(|task| !task.completed)
+        .filter_map(|task| Some((task.due_time?, task.description.clone())))
+        .filter(|(due, _)| Some(dates.local_date(*due)) == tomorrow)
+        .collect();
+    due.sort_by_key(|(due, _)| *due);
+    Summary {
+        day,
+        completed: descriptions(ActivityMetric::Completed),
+        added: descriptions(ActivityMetric::Created),
+        due_tomorrow: due
+            .into_iter()
+            .map(|(_, description)| description)
+            .collect(),
+    }
+}
+
+impl Summary {
+    /// The sections with their tasks, in display order.
+    pub fn sections(&self) -> [(&'static str, &[String]); 3] {
+        [
+            ("Completed", &self.completed),
+            ("Added", &self.added),
+            ("Due tomorrow", &self.due_tomorrow),
+        ]
+    }
+
+    pub fn title(&self, dates: &DateDisplay) -> String {
+        format!("Summary of {}", dates.date(self.day))
+    }
+
+    /// A Markdown document with a heading per non-empty section.
+    pub fn markdown(&self, dates: &DateDisplay) -> String {
+        let mut out = format!("# {}\n", self.title(dates));
+        for (heading, tasks) in self.sections() {
+            if tasks.is_empty() {
+                continue;
+            }
+            let _ = write!(out, "\n## {} ({})\n\n", heading, tasks.len());
+            for task in tasks {
+                let _ = writeln!(out, "- {}", task);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,79 @@
+//! The glyphs frontends draw for task states and markers. They are saved with
+//! the model, so they can be swapped in the data file, e.g. for Nerd Font
+//! icons:
+//!
+//! ```json
+//! "symbols": { "open": "", "done": "", "priority_high": "" }
+//! ```
+//!
+//! Any glyph left out keeps its default.
+
+use crate::model::{Priority, Task};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Symbols {
+    pub open: String,
+    pub done: String,
+    pub cancelled: String,
+    pub priority_high: String,
+    pub priority_medium: String,
+    pub priority_low: String,
+    pub attachment: String,
+    pub reminder: String,
+    pub comment: String,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Self {
+            open: "[ ]".to_string(),
+            done: "[x]".to_string(),
+            cancelled: "[-]".to_string(),
+            priority_high: "!!!".to_string(),
+            priority_medium: "!!".to_string(),
+            priority_low: "!".to_string(),
+            attachment: "📎".to_string(),
+            reminder: "🔔".to_string(),
+            comment: "💬".to_string(),
+        }
+    }
+}
+
+impl Symbols {
+    /// Spelled-out markers for screen readers and dumb terminals. These win
+    /// over configured glyphs while the accessible profile is on.
+    pub fn accessible() -> Self {
+        Self {
+            open: "[TODO]".to_string(),
+            done: "[DONE]".to_string(),
+            cancelled: "[WONT]".to_string(),
+            priority_high: "[!1]".to_string(),
+            priority_medium: "[!2]".to_string(),
+            priority_low: "[!3]".to_string(),
+            attachment: "[file]".to_string(),
+            reminder: "[reminder]".to_string(),
+            comment: "[comments]".to_string(),
+        }
+    }
+
+    /// The checkbox for the state of `task`.
+    pub fn status(&self, task: &Task) -> &str {
+        if task.cancelled.is_some() {
+            &self.cancelled
+        } else if task.completed {
+            &self.done
+        } else {
+            &self.open
+        }
+    }
+
+    pub fn priority(&self, priority: Priority) -> &str {
+        match priority {
+            Priority::High => &self.priority_high,
+            Priority::Medium => &self.priority_medium,
+            Priority::Low => &self.priority_low,
+        }
+    }
+}
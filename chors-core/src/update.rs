@@ -0,0 +1,1713 @@
+use crate::{
+    animation::Animation,
+    caldav,
+    columns::{self, Column, ColumnSpec},
+    dates::DateDisplay,
+    events::Event,
+    export,
+    form::Form,
+    lint,
+    model::{
+        flatten_tasks, meta_field, with_sigil, ChangeKind, Comment, CompletedStyle,
+        CompletionPolicy, Direction, DisplayOptions, FilterList, ListState, Mode, Model, Msg,
+        Overlay, Priority, Reminder, Snooze, Task, View, ACTIVITY_WEEKS,
+    },
+    resolve,
+    rollover::{self, Rollover},
+    scripting,
+    status::Severity,
+    summary,
+};
+use chrono::{DateTime, NaiveTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use indexmap::IndexMap;
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Instant,
+};
+use uuid::Uuid;
+
+/// Applies `msg` to the model. Changes are queued as [`Event`]s for
+/// [`crate::events::dispatch`].
+pub fn update(msg: Msg, model: &mut Model) {
+    if model.read_only && msg.is_mutating() {
+        model.notify(Severity::Warning, "Read-only: changes are disabled");
+        return;
+    }
+    let view_before = model.current_view.clone();
+    let sidebar_filter_before = model.sidebar_filter.clone();
+    let focus_before = model.focus_context.clone();
+    let jump_query = matches!(model.overlay, Overlay::Jump).then(|| model.input.text().to_string());
+    let jump_from = model.selected.filter(|_| {
+        matches!(
+            msg,
+            Msg::JumpToTask(_)
+                | Msg::JumpToInbox
+                | Msg::JumpToEnd
+                | Msg::HandleNavigation
+                | Msg::JumpToMark(_)
+                | Msg::Zoom(_)
+        )
+    });
+    handle(msg, model);
+    if let Some(from) = jump_from.filter(|&from| model.selected != Some(from)) {
+        model.jumps.record(from);
+    }
+    // A new query ranks the results anew, so start from the best match
+    if jump_query.is_some_and(|query| query != model.input.text()) {
+        model.jump_state.select(Some(0));
+    }
+    model.clamp_zoom();
+    if model.current_view != view_before
+        || model.sidebar_filter != sidebar_filter_before
+        || model.focus_context != focus_before
+    {
+        model.emit(Event::FilterChanged);
+    }
+}
+
+fn handle(msg: Msg, model: &mut Model) {
+    let count = match msg {
+        Msg::PushCount(_) | Msg::Tick => None,
+        _ => model.pending_count.take(),
+    };
+
+    match msg {
+        Msg::NoOp => (),
+        Msg::Tick => {
+            model.messages.tick(Instant::now());
+            if let Some(offset) = model.animation.tick(Instant::now()) {
+                model.list_state.set_offset(offset);
+            }
+            check_reminders(model);
+        }
+        Msg::Quit => model.mode = Mode::Quit,
+        Msg::AddTask => {
+            model.task_history.push(model.input.text());
+            model.add_task();
+            select_current(model);
+            model.input.clear();
+            model.overlay = Overlay::None;
+        }
+        Msg::AddSubtask => {
+            model.task_history.push(model.input.text());
+            model.add_subtask();
+            select_current(model);
+            model.input.clear();
+            model.overlay = Overlay::None;
+        }
+        Msg::ToggleTaskCompletion => {
+            for path in counted_paths(model, count) {
+                if let Some(task) = model.get_task_mut(&path) {
+                    task.set_completed(!task.completed);
+                    toggle_subtasks_completion(task);
+                    let event = if task.completed {
+                        Event::TaskCompleted(task.id)
+                    } else {
+                        Event::TaskReopened(task.id)
+                    };
+                    model.emit(event);
+                    update_parent_task_completion(model, &path);
+                }
+            }
+        }
+        Msg::NavigateTags(direction) => {
+            let len = model.tag_counts().len();
+            cycle_selection(&mut model.tag_state, len, direction);
+        }
+        Msg::NavigateColumns(direction) => {
+            let len = model.column_choices().len();
+            cycle_selection(&mut model.column_state, len, direction);
+        }
+        Msg::ToggleColumn(column) => {
+            if column == Column::Description {
+                model.notify(Severity::Warning, "The description column can't be hidden.");
+                return;
+            }
+            match model.columns.iter().position(|spec| spec.column == column) {
+                Some(index) => {
+                    model.columns.remove(index);
+                }
+                None => model.columns.push(ColumnSpec::new(column)),
+            }
+            select_column(model, column);
+        }
+        Msg::MoveColumn(column, direction) => {
+            if let Some(index) = model.columns.iter().position(|spec| spec.column == column) {
+                let other = match direction {
+                    Direction::Up => index.checked_sub(1),
+                    Direction::Down => Some(index + 1).filter(|&other| other < model.columns.len()),
+                };
+                if let Some(other) = other {
+                    model.columns.swap(index, other);
+                }
+                select_column(model, column);
+            }
+        }
+        Msg::ResizeColumn(column, delta) => {
+            if let Some(spec) = model.columns.iter_mut().find(|spec| spec.column == column) {
+                // The description fills the row until it is given a width
+                let width = spec.width().unwrap_or(DESCRIPTION_WIDTH);
+                spec.width = Some(
+                    width
+                        .saturating_add_signed(delta)
+                        .clamp(1, MAX_COLUMN_WIDTH),
+                );
+            }
+        }
+        Msg::NavigateFocusChoices(direction) => {
+            let len = model.focus_choices().len();
+            cycle_selection(&mut model.focus_state, len, direction);
+        }
+        Msg::NavigatePlanning(direction) => {
+            let len = model.planning_backlog(Utc::now()).len();
+            cycle_selection(&mut model.planning_state, len, direction);
+        }
+        Msg::NavigateJump(direction) => {
+            let len = model.jump_results(model.input.text()).len();
+            cycle_selection(&mut model.jump_state, len, direction);
+        }
+        Msg::JumpToTask(path) => {
+            model.input.clear();
+            model.overlay = Overlay::None;
+            if model.get_task(&path).is_some() {
+                model.reveal(&path);
+            }
+        }
+        Msg::JumpBack | Msg::JumpForward => {
+            let back = matches!(msg, Msg::JumpBack);
+            loop {
+                let next = if back {
+                    model.jumps.back(model.selected)
+                } else {
+                    model.jumps.forward()
+                };
+                let Some(id) = next else {
+                    break;
+                };
+                // Skip tasks deleted since the jump
+                if let Some(path) = model.path_of(id) {
+                    model.reveal(&path);
+                    break;
+                }
+            }
+        }
+        Msg::NavigateViews(direction) => {
+            let len = model.saved_views.len();
+            cycle_selection(&mut model.views_state, len, direction);
+        }
+        Msg::BindViewKey(key) => {
+            if let Some(name) = model.selected_saved_view().map(str::to_string) {
+                if model.view_keys.get(&key) == Some(&name) {
+                    model.view_keys.remove(&key);
+                    model.notify(Severity::Info, &format!("Unbound {} from {}", key, name));
+                } else {
+                    model.view_keys.retain(|_, view| *view != name);
+                    model.notify(Severity::Info, &format!("{} now loads {}", key, name));
+                    model.view_keys.insert(key, name);
+                }
+            }
+        }
+        Msg::ApplyFilter(filter) => {
+            model.overlay = Overlay::None;
+            model.current_view = model.current_view.with_conjunct(&filter);
+        }
+        Msg::SetMark(register) => {
+            model.overlay = Overlay::None;
+            if let Some(id) = model.selected {
+                model.marks.insert(register, id);
+                model.notify(
+                    Severity::Info,
+                    &format!("Marked the task as '{}'", register),
+                );
+            }
+        }
+        Msg::JumpToMark(register) => {
+            model.overlay = Overlay::None;
+            match model.marks.get(&register).copied() {
+                None => model.notify(Severity::Info, &format!("No mark '{}'", register)),
+                Some(id) => match model.path_of(id) {
+                    Some(path) => model.reveal(&path),
+                    None => {
+                        model.marks.remove(&register);
+                        model.notify(
+                            Severity::Warning,
+                            &format!("The task marked '{}' was deleted", register),
+                        );
+                    }
+                },
+            }
+        }
+        Msg::SaveFailed(error) => {
+            model.save_error = Some(error);
+            model.overlay = Overlay::SaveFailed;
+            // Stay open rather than exit without the changes
+            if let Mode::Quit = model.mode {
+                model.mode = Mode::List;
+            }
+        }
+        Msg::DismissSaveError | Msg::ReloadChangedNotes => {
+            model.save_error = None;
+            model.overlay = Overlay::None;
+        }
+        Msg::CycleTimelineScale => {
+            model.timeline_scale = model.timeline_scale.next();
+        }
+        Msg::ExportView(format) => {
+            let (document, count) = export::render(model, format);
+            let file = format!(
+                "{}-{}.{}",
+                model.selected_view,
+                Utc::now().format("%Y-%m-%d"),
+                format.extension()
+            );
+            match fs::write(&file, document) {
+                Ok(()) => model.notify(
+                    Severity::Success,
+                    &format!("Exported {} tasks to {}", count, file),
+                ),
+                Err(error) => model.notify(
+                    Severity::Error,
+                    &format!("Failed to export to {}: {}", file, error),
+                ),
+            }
+        }
+        Msg::ToggleActivityMetric => {
+            model.activity_metric = model.activity_metric.toggle();
+        }
+        Msg::MoveActivityDay(days) => {
+            let today = model.date_display.local_date(Utc::now());
+            let day = model.activity_day.unwrap_or(today) + chrono::Duration::days(days);
+            // The heatmap covers the last year
+            let earliest = today - chrono::Duration::weeks(ACTIVITY_WEEKS);
+            model.activity_day = Some(day.clamp(earliest, today));
+        }
+        Msg::ScheduleTask { path, day } => {
+            let dates = model.date_display.clone();
+            let Some(task) = model.get_task_mut(&path) else {
+                return;
+            };
+            // Undated tasks are due by the end of the day
+            let time = task
+                .due_time
+                .map(|due| dates.local(due).time())
+                .unwrap_or_else(|| NaiveTime::from_hms_opt(23, 59, 0).unwrap());
+            let due_time = match day {
+                Some(day) => match dates.from_local(day.and_time(time)) {
+                    Some(due) => Some(due),
+                    None => return,
+                },
+                None => None,
+            };
+            if task.due_time != due_time {
+                task.record(ChangeKind::Edited);
+                task.due_time = due_time;
+                let (id, description) = (task.id, task.description.clone());
+                model.emit(Event::TaskChanged(id));
+                let message = match day {
+                    Some(day) => format!("Planned \"{}\" for {}", description, dates.date(day)),
+                    None => format!("Unscheduled \"{}\"", description),
+                };
+                model.notify(Severity::Success, &message);
+            }
+            // The backlog shrank; keep the selection in range
+            let len = model.planning_backlog(Utc::now()).len();
+            let selected = model.planning_state.selected().filter(|_| len > 0);
+            model
+                .planning_state
+                .select(selected.map(|index| index.min(len - 1)));
+        }
+        Msg::SetFocusContext(context) => {
+            model.overlay = Overlay::None;
+            model.focus_context = context.map(|context| with_sigil('@', &context));
+        }
+        Msg::SubmitTagRename => {
+            let input = model.input.text().trim().to_string();
+            let merging = matches!(model.overlay, Overlay::MergingTag);
+            model.input.clear();
+            model.overlay = Overlay::Tags;
+            let Some(from) = model.selected_tag() else {
+                return;
+            };
+            if input.is_empty() || input.contains(char::is_whitespace) || input == "#" {
+                model.notify(Severity::Warning, "Type a single tag name.");
+                return;
+            }
+            let to = with_sigil('#', &input);
+            let exists = model.tag_counts().iter().any(|(tag, _)| *tag == to);
+            if merging && !exists {
+                model.notify(
+                    Severity::Warning,
+                    &format!("There is no {} to merge into.", to),
+                );
+            } else if to != from {
+                update(Msg::RewriteTag { from, to: Some(to) }, model);
+            }
+        }
+        Msg::DeleteTag => {
+            if let Some(tag) = model.selected_tag() {
+                let count = model
+                    .tag_counts()
+                    .iter()
+                    .find(|(t, _)| *t == tag)
+                    .map_or(0, |(_, n)| *n);
+                let prompt = format!("Remove {} from {} tasks?", tag, count);
+                model.request_confirmation(
+                    prompt,
+                    Msg::RewriteTag {
+                        from: tag,
+                        to: None,
+                    },
+                );
+            }
+        }
+        Msg::RewriteTag { from, to } => {
+            let changed = model.rewrite_tag(&from, to.as_deref());
+            let message = match &to {
+                Some(to) => format!("Replaced {} with {} in {} tasks.", from, to, changed),
+                None => format!("Removed {} from {} tasks.", from, changed),
+            };
+            model.notify(Severity::Success, &message);
+            // The list shrank or reordered; keep the selection in range
+            let len = model.tag_counts().len();
+            let selected = model.tag_state.selected().filter(|_| len > 0);
+            model
+                .tag_state
+                .select(selected.map(|index| index.min(len - 1)));
+        }
+        Msg::CancelTask => {
+            let reason = model.input.text().trim().to_string();
+            model.input.clear();
+            model.overlay = Overlay::None;
+            let path = model.get_path();
+            if let Some(task) = model.get_task_mut(&path) {
+                task.cancel(Some(reason).filter(|reason| !reason.is_empty()));
+                let id = task.id;
+                update_parent_task_completion(model, &path);
+                model.emit(Event::TaskChanged(id));
+            }
+        }
+        Msg::AddComment => {
+            let text = model.input.text().trim().to_string();
+            model.input.clear();
+            let path = model.get_path();
+            let author = model.author.clone();
+            match model.get_task_mut(&path) {
+                Some(task) if !text.is_empty() => {
+                    task.comments.push(Comment {
+                        at: Utc::now(),
+                        author,
+                        text,
+                    });
+                    let id = task.id;
+                    model.emit(Event::TaskChanged(id));
+                }
+                Some(_) => {}
+                None => model.notify(Severity::Warning, "Select a task to comment on first."),
+            }
+        }
+        Msg::SnoozeTask(snooze) => {
+            let path = model.get_path();
+            let message = match &snooze {
+                Some(snooze) => format!("Snoozed {}.", snooze.describe()),
+                None => "Woke the task up.".to_string(),
+            };
+            if let Some(task) = model.get_task_mut(&path) {
+                task.snoozed = snooze;
+                let id = task.id;
+                model.emit(Event::TaskChanged(id));
+                model.notify(Severity::Success, &message);
+            } else {
+                model.notify(Severity::Warning, "Select a task to snooze first.");
+            }
+        }
+        Msg::Rollover(kind) => {
+            let report = rollover::run(model, kind, Utc::now());
+            if report.tasks.is_empty() {
+                model.notify(Severity::Info, "Nothing is overdue from past days.");
+            } else {
+                model.last_rollover = Some(report);
+                model.overlay = Overlay::Rollover;
+                model.debug_scroll = 0;
+            }
+        }
+        Msg::UndoRollover => {
+            model.overlay = Overlay::None;
+            if let Some(report) = model.last_rollover.take() {
+                let restored = rollover::undo(model, &report);
+                if model.animations {
+                    let ids = report.tasks.iter().map(|task| task.id);
+                    model.animation.flash(ids, Instant::now());
+                }
+                model.notify(
+                    Severity::Success,
+                    &format!("Undid the rollover of {} tasks.", restored),
+                );
+            }
+        }
+        Msg::Sync => match model.caldav_config.clone() {
+            Some(config) => {
+                let mut client = caldav::Client::new(config, model.date_display.clone());
+                match caldav::sync(model, &mut client, Utc::now()) {
+                    Ok(report) => model.notify(Severity::Success, &format!("Synced: {}.", report)),
+                    Err(error) => model.notify(Severity::Error, &format!("Sync failed: {}", error)),
+                }
+            }
+            None => model.notify(
+                Severity::Warning,
+                "Set up the CalDAV server in the caldav config file first.",
+            ),
+        },
+        Msg::CopySummary => {
+            let summary = summary::summarize(model, Utc::now());
+            match copy_to_clipboard(&summary.markdown(&model.date_display)) {
+                Ok(()) => model.notify(Severity::Success, "Copied the summary as Markdown."),
+                Err(error) => model.notify(
+                    Severity::Error,
+                    &format!("Failed to copy the summary: {}", error),
+                ),
+            }
+        }
+        Msg::AdjustProgress(step) => {
+            let path = model.get_path();
+            let Some(task) = model.get_task(&path) else {
+                return;
+            };
+            if !task.subtasks.is_empty() {
+                model.notify(
+                    Severity::Info,
+                    "The progress of a task with subtasks follows them.",
+                );
+                return;
+            }
+            let current = if task.completed {
+                100
+            } else {
+                i32::from(task.percent.unwrap_or(0))
+            };
+            let percent = (current + step * count.unwrap_or(1) as i32).clamp(0, 100);
+            if let Some(task) = model.get_task_mut(&path) {
+                task.percent = Some(percent as u8);
+                let id = task.id;
+                let completed = percent == 100;
+                if task.completed != completed {
+                    task.set_completed(completed);
+                    update_parent_task_completion(model, &path);
+                    model.emit(if completed {
+                        Event::TaskCompleted(id)
+                    } else {
+                        Event::TaskReopened(id)
+                    });
+                } else {
+                    model.emit(Event::TaskChanged(id));
+                }
+            }
+        }
+        Msg::SwitchMode(new_mode) => {
+            if matches!(new_mode, Mode::Planning) && model.planning_state.selected().is_none() {
+                model.planning_state.select(Some(0));
+            }
+            model.mode = new_mode;
+            model.overlay = Overlay::None;
+            model.input.clear();
+            model.navigation_input.clear();
+            model.debug_scroll = 0;
+        }
+        Msg::SetOverlay(new_overlay) => {
+            if let Some(history) = model.input_history_mut() {
+                history.reset();
+            }
+            model.pending_confirmation = None;
+            model.clear_filter_preview();
+            model.overlay = new_overlay;
+            model.messages.dismiss();
+            model.input.clear();
+            model.navigation_input.clear();
+            model.debug_scroll = 0;
+            model.jump_state.select(Some(0));
+        }
+        Msg::NavigateTasks(direction) => {
+            let nav_len = model.nav.len();
+            if nav_len == 0 {
+                model.selected = None;
+                model.list_state.select(None);
+                return;
+            }
+
+            let new_selected = match model.selected {
+                Some(current) => {
+                    let current_index = model.nav.get_index_of(&current).unwrap_or(0);
+                    // A single step wraps around the list; a counted one
+                    // stops at its ends, as in vim
+                    match (direction, count) {
+                        (Direction::Up, None) => (current_index + nav_len - 1) % nav_len,
+                        (Direction::Down, None) => (current_index + 1) % nav_len,
+                        (Direction::Up, Some(steps)) => current_index.saturating_sub(steps),
+                        (Direction::Down, Some(steps)) => {
+                            current_index.saturating_add(steps).min(nav_len - 1)
+                        }
+                    }
+                }
+                None => 0,
+            };
+
+            let (new_selected_id, _) = model.nav.get_index(new_selected).unwrap();
+            model.selected = Some(*new_selected_id);
+            model.list_state.select(Some(new_selected));
+        }
+        Msg::HandleNavigation => {
+            if model.navigation_input.is_empty() {
+                model.jump_to_line(0);
+            } else if let Ok(line) = model.navigation_input.parse::<usize>() {
+                model.jump_to_line(line.saturating_sub(1));
+            }
+            model.overlay = Overlay::None;
+            model.navigation_input.clear();
+        }
+        Msg::JumpToEnd => {
+            match count.or(model.navigation_input.parse::<usize>().ok()) {
+                Some(line) => model.jump_to_line(line.saturating_sub(1)),
+                None => model.jump_to_line(usize::MAX),
+            }
+            model.overlay = Overlay::None;
+            model.navigation_input.clear();
+        }
+        Msg::PushCount(digit) => {
+            let pending = model.pending_count.unwrap_or(0);
+            model.pending_count = Some(pending.saturating_mul(10).saturating_add(digit));
+        }
+        Msg::ToggleRelativeLineNumbers => {
+            model.relative_line_numbers = !model.relative_line_numbers;
+        }
+        Msg::ToggleShortIds => model.show_short_ids = !model.show_short_ids,
+        Msg::ToggleSidebar => {
+            model.show_sidebar = !model.show_sidebar;
+            model.sidebar_focused = model.show_sidebar;
+        }
+        Msg::FocusSidebar(focused) => model.sidebar_focused = focused && model.show_sidebar,
+        Msg::NavigateSidebar(direction) => {
+            let len = model.sidebar_entries().len();
+            cycle_selection(&mut model.sidebar_state, len, direction);
+        }
+        Msg::CycleSortKey => {
+            let display = &mut model.current_view.display;
+            display.sort = display.sort.next();
+        }
+        Msg::ToggleShowCompleted => {
+            let display = &mut model.current_view.display;
+            display.show_completed = !display.show_completed;
+        }
+        Msg::CycleGroupBy => {
+            let display = &mut model.current_view.display;
+            display.group_by = display.group_by.next();
+        }
+        Msg::SelectSidebarEntry => {
+            let entries = model.sidebar_entries();
+            if let Some((filter, _)) = model
+                .sidebar_state
+                .selected()
+                .and_then(|index| entries.into_iter().nth(index))
+            {
+                model.sidebar_filter = if model.sidebar_filter.as_ref() == Some(&filter) {
+                    None
+                } else {
+                    Some(filter)
+                };
+            }
+        }
+        Msg::PushChar(ch) => match model.overlay {
+            Overlay::Navigation => model.navigation_input.push(ch),
+            _ => model.active_field_mut().insert_char(ch),
+        },
+        Msg::PopChar => {
+            match model.overlay {
+                Overlay::Navigation => {
+                    model.navigation_input.pop();
+                }
+                _ => model.active_field_mut().delete_before(),
+            };
+        }
+        Msg::CursorLeft => model.active_field_mut().move_left(),
+        Msg::CursorRight => model.active_field_mut().move_right(),
+        Msg::CursorHome => model.active_field_mut().move_home(),
+        Msg::CursorEnd => model.active_field_mut().move_end(),
+        Msg::KillToStart => {
+            let killed = model.active_field_mut().kill_to_start();
+            model.push_kill(killed);
+        }
+        Msg::KillToEnd => {
+            let killed = model.active_field_mut().kill_to_end();
+            model.push_kill(killed);
+        }
+        Msg::KillWordForward => {
+            let killed = model.active_field_mut().kill_word_forward();
+            model.push_kill(killed);
+        }
+        Msg::Yank => {
+            if let Some(killed) = model.kill_ring.last().cloned() {
+                model.active_field_mut().insert_str(&killed);
+            }
+        }
+        Msg::TransposeChars => model.active_field_mut().transpose(),
+        Msg::HistoryPrevious => {
+            let current = model.input.text().to_string();
+            if let Some(entry) = model
+                .input_history_mut()
+                .and_then(|history| history.older(&current))
+            {
+                model.input.set_text(&entry);
+            }
+        }
+        Msg::HistoryNext => {
+            if let Some(entry) = model
+                .input_history_mut()
+                .and_then(|history| history.newer())
+            {
+                model.input.set_text(&entry);
+            }
+        }
+        Msg::AddFilterCriterion | Msg::SaveCurrentView(_) if model.review_stash.is_some() => {
+            model.input.clear();
+            model.overlay = Overlay::None;
+            model.notify(
+                Severity::Warning,
+                "The done review is read-only; press T to leave it.",
+            );
+        }
+        Msg::AddFilterCriterion => match model.parse_filter(model.input.text()) {
+            Ok(filter_list) => {
+                model.filter_history.push(model.input.text());
+                model.current_view.filter_lists.push(filter_list);
+                model.clear_filter_preview();
+                model.input.clear();
+                model.overlay = Overlay::None;
+                report_lint(model);
+            }
+            Err(error) => model.filter_error = Some(error),
+        },
+        Msg::SaveCurrentView(view_name) => {
+            model
+                .saved_views
+                .insert(view_name, model.current_view.clone());
+            report_lint(model);
+        }
+        Msg::SwitchList(name) => {
+            let name = name.trim();
+            if !name.is_empty() {
+                model.switch_list(name);
+            }
+            model.input.clear();
+            model.overlay = Overlay::None;
+        }
+        Msg::RunCommand(command) => {
+            model.input.clear();
+            model.overlay = Overlay::None;
+            let mut words = command.split_whitespace();
+            if command.trim() == "messages" {
+                model.overlay = Overlay::Messages;
+                model.debug_scroll = 0;
+            } else if name_is(&command, "focus") {
+                let args: Vec<&str> = command.split_whitespace().skip(1).collect();
+                match args[..] {
+                    [] => {
+                        model.overlay = Overlay::FocusPicker;
+                        let choices = model.focus_choices();
+                        let current = choices
+                            .iter()
+                            .position(|choice| *choice == model.focus_context);
+                        model.focus_state.select(Some(current.unwrap_or(0)));
+                    }
+                    ["off"] => update(Msg::SetFocusContext(None), model),
+                    [context] => update(Msg::SetFocusContext(Some(context.to_string())), model),
+                    _ => model.notify(Severity::Error, "Usage: focus [@context|off]"),
+                }
+            } else if name_is(&command, "snooze") {
+                let args: Vec<&str> = command.split_whitespace().skip(1).collect();
+                match args[..] {
+                    ["off"] => update(Msg::SnoozeTask(None), model),
+                    [context] => update(
+                        Msg::SnoozeTask(Some(Snooze::UntilContext(with_sigil('@', context)))),
+                        model,
+                    ),
+                    _ => model.notify(Severity::Error, "Usage: snooze <@context|off>"),
+                }
+            } else if command.trim() == "tags" {
+                model.overlay = Overlay::Tags;
+                if model.tag_state.selected().is_none() && !model.tag_counts().is_empty() {
+                    model.tag_state.select(Some(0));
+                }
+            } else if command.trim() == "views" {
+                model.overlay = Overlay::Views;
+                if model.views_state.selected().is_none() && !model.saved_views.is_empty() {
+                    model.views_state.select(Some(0));
+                }
+            } else if name_is(&command, "export") {
+                let args: Vec<&str> = command.split_whitespace().skip(1).collect();
+                let format = match args[..] {
+                    [] => Some(export::Format::Markdown),
+                    [name] => export::Format::parse(name),
+                    _ => None,
+                };
+                match format {
+                    Some(format) => update(Msg::ExportView(format), model),
+                    None => model.notify(Severity::Error, "Usage: export [md|html]"),
+                }
+            } else if command.trim() == "history" {
+                model.overlay = Overlay::History;
+                model.debug_scroll = 0;
+            } else if command.trim() == "columns" {
+                model.overlay = Overlay::Columns;
+                model.column_state.select(Some(0));
+            } else if command.trim() == "comments" {
+                model.overlay = Overlay::Comments;
+                model.debug_scroll = 0;
+            } else if command.trim() == "sync" {
+                update(Msg::Sync, model);
+            } else if command.trim() == "summary" {
+                model.overlay = Overlay::Summary;
+                model.debug_scroll = 0;
+            } else if let Some(name) = words.next() {
+                let args: Vec<String> = words.map(str::to_string).collect();
+                let result = match name {
+                    "completion" => set_completion_policy(model, &args),
+                    "done-style" => set_completed_style(model, &args),
+                    "columns" => set_columns(model, &args),
+                    "text-match" => set_text_match(model, &args),
+                    "rollover" => rollover_command(model, &args),
+                    "journal" => set_journal(model, &args),
+                    "animations" => set_animations(model, &args),
+                    _ => scripting::run(model, name, &args),
+                };
+                if let Err(err) = result {
+                    model.notify(Severity::Error, &err.to_string());
+                }
+            }
+        }
+        Msg::JumpToInbox => {
+            let inbox_id = model.inbox_id();
+            if model.zoom.first().is_some_and(|&root| root != inbox_id) {
+                // The list isn't rebuilt until the next draw, so pick the
+                // target without checking it is visible
+                model.zoom.clear();
+                let inbox = &model.tasks[&inbox_id];
+                let first = inbox.subtasks.values().find(|task| !task.completed);
+                model.selected = Some(first.map_or(inbox_id, |task| task.id));
+                return;
+            }
+            let inbox = &model.tasks[&inbox_id];
+            let target = inbox
+                .subtasks
+                .values()
+                .find(|task| !task.completed && model.nav.contains_key(&task.id))
+                .map_or(inbox_id, |task| task.id);
+            if model.nav.contains_key(&target) {
+                model.selected = Some(target);
+                select_current(model);
+            } else {
+                model.notify(Severity::Info, "The inbox is hidden by the current view.");
+            }
+        }
+        Msg::Zoom(path) => {
+            if path == model.zoom || (!path.is_empty() && model.get_task(&path).is_none()) {
+                return;
+            }
+            if let Some(&left) = model
+                .zoom
+                .get(path.len())
+                .filter(|_| model.zoom.starts_with(&path))
+            {
+                // Zooming out: keep the subtree we came from selected
+                model.selected = Some(left);
+            } else {
+                model.selected = model
+                    .get_task(&path)
+                    .and_then(|root| root.subtasks.keys().next().copied());
+            }
+            model.zoom = path;
+        }
+        Msg::SubmitMove => {
+            let input = model.input.text().trim().to_string();
+            model.input.clear();
+            model.overlay = Overlay::None;
+            let from = model.get_path();
+            if from.is_empty() {
+                model.notify(Severity::Warning, "Select a task to move first.");
+                return;
+            }
+            let target = if input.is_empty() {
+                Ok(Vec::new())
+            } else if let Ok(line) = input.parse::<usize>() {
+                line.checked_sub(1)
+                    .and_then(|index| model.nav.get_index(index))
+                    .map(|(_, path)| path.clone())
+                    .ok_or_else(|| format!("No line {}", line))
+            } else {
+                resolve::resolve(model.flattened(), &input).map_err(|err| err.to_string())
+            };
+            match target {
+                Ok(to) => update(Msg::MoveTask { from, to }, model),
+                Err(err) => model.notify(Severity::Error, &err),
+            }
+        }
+        Msg::MoveTask { from, to } => {
+            let Some(&id) = from.last() else {
+                return;
+            };
+            if to.starts_with(&from) {
+                model.notify(Severity::Warning, "Can't move a task under itself.");
+                return;
+            }
+            if model.get_task(&from).is_none() || (!to.is_empty() && model.get_task(&to).is_none())
+            {
+                return;
+            }
+            let from_inbox = from.len() == 2 && Some(from[0]) == model.inbox;
+            let siblings = model.get_task_list_mut(&from);
+            let index = siblings.get_index_of(&id).unwrap_or(0);
+            let task = siblings.shift_remove(&id).unwrap();
+            update_parent_task_completion(model, &from);
+
+            let mut new_path = to.clone();
+            new_path.push(id);
+            model.get_task_list_mut(&new_path).insert(id, task);
+            // Also marks the moved subtree for filter re-evaluation
+            if let Some(task) = model.get_task_mut(&new_path) {
+                task.record(ChangeKind::Moved);
+            }
+            update_parent_task_completion(model, &new_path);
+            model.emit(Event::TaskChanged(id));
+
+            // While triaging, carry on with the next item in the inbox
+            model.selected = Some(id);
+            if from_inbox {
+                let inbox = &model.tasks[&from[0]].subtasks;
+                if let Some((&next, _)) = inbox.get_index(index).or_else(|| inbox.last()) {
+                    model.selected = Some(next);
+                }
+            }
+        }
+        Msg::ToggleDoneReview => match model.review_stash.take() {
+            Some(view) => model.current_view = view,
+            None => {
+                let review = View {
+                    filter_lists: ["done:today", "done:yesterday"]
+                        .into_iter()
+                        .map(|filter| FilterList::parse(filter).unwrap())
+                        .collect(),
+                    display: DisplayOptions {
+                        show_completed: true,
+                        ..model.current_view.display.clone()
+                    },
+                };
+                model.review_stash = Some(std::mem::replace(&mut model.current_view, review));
+            }
+        },
+        Msg::LoadView(view_name) => {
+            let view = model.saved_views.get(&view_name).cloned();
+            if let Some(view) = view.or_else(|| View::builtin(&view_name)) {
+                model.review_stash = None;
+                model.current_view = view;
+            }
+            if let Overlay::Views = model.overlay {
+                model.overlay = Overlay::None;
+            }
+        }
+        Msg::NewTaskForm => {
+            model.form = Form::new(&TASK_FORM_FIELDS);
+            model.form_target = None;
+            model.overlay = Overlay::TaskForm;
+        }
+        Msg::EditTaskForm => {
+            let path = model.get_path();
+            if let Some(task) = model.get_task(&path) {
+                let mut form = Form::new(&TASK_FORM_FIELDS);
+                // Metadata words get a field of their own
+                let (description, meta) = split_meta(task);
+                form.set_value(FORM_DESCRIPTION, &description);
+                form.set_value(FORM_META, &meta);
+                if let Some(due_time) = task.due_time {
+                    let due_time = model.date_display.local(due_time);
+                    form.set_value(FORM_DUE, &due_time.format("%Y-%m-%d %H:%M").to_string());
+                }
+                if let Some(priority) = task.priority {
+                    form.set_value(FORM_PRIORITY, priority.as_str());
+                }
+                let reminders: Vec<String> = task
+                    .reminders
+                    .iter()
+                    .map(|reminder| reminder.describe(&model.date_display))
+                    .collect();
+                form.set_value(FORM_REMINDERS, &reminders.join(", "));
+                form.set_value(FORM_NOTES, &task.notes);
+                model.form = form;
+                model.form_target = Some(path);
+                model.overlay = Overlay::TaskForm;
+            }
+        }
+        Msg::FormNextField => model.form.focus_next(),
+        Msg::FormPreviousField => model.form.focus_previous(),
+        Msg::SubmitTaskForm => submit_task_form(model),
+        Msg::DeleteTask => {
+            let paths: Vec<_> = counted_paths(model, count)
+                .into_iter()
+                .filter(|path| model.get_task(path).is_some())
+                .collect();
+            if let [path] = &paths[..] {
+                let task = model.get_task(path).unwrap();
+                let descendants = task.descendant_count();
+                if descendants > model.confirm_delete_threshold {
+                    let prompt = format!(
+                        "Delete \"{}\" and its {} subtasks?",
+                        task.description, descendants
+                    );
+                    model.request_confirmation(prompt, Msg::RemoveTask(path.clone()));
+                } else {
+                    update(Msg::RemoveTask(path.clone()), model);
+                }
+            } else if !paths.is_empty() {
+                let descendants: usize = paths
+                    .iter()
+                    .filter_map(|path| model.get_task(path))
+                    .map(Task::descendant_count)
+                    .sum();
+                if descendants > model.confirm_delete_threshold {
+                    let prompt = format!(
+                        "Delete {} tasks and their {} subtasks?",
+                        paths.len(),
+                        descendants
+                    );
+                    model.request_confirmation(prompt, Msg::RemoveTasks(paths));
+                } else {
+                    update(Msg::RemoveTasks(paths), model);
+                }
+            }
+        }
+        Msg::RemoveTask(path) => remove_tasks(model, vec![path]),
+        Msg::RemoveTasks(paths) => remove_tasks(model, paths),
+        Msg::AddTasksBulk(tasks) => {
+            let mut parents: Vec<Vec<Uuid>> = Vec::new();
+            for (parent, task) in tasks {
+                if !parent.is_empty() && model.get_task(&parent).is_none() {
+                    continue;
+                }
+                let id = task.id;
+                let mut path = parent;
+                path.push(id);
+                // New ids are picked up by the next filter pass without
+                // marking anything dirty
+                model.get_task_list_mut(&path).insert(id, task);
+                model.emit(Event::TaskAdded(id));
+                if !parents
+                    .iter()
+                    .any(|known| known[..] == path[..path.len() - 1])
+                {
+                    parents.push(path);
+                }
+            }
+            for path in parents {
+                update_parent_task_completion(model, &path);
+            }
+        }
+        Msg::DuplicateTask(path) => {
+            // A count makes that many copies, all right after the original
+            for _ in 0..count.unwrap_or(1) {
+                let Some(task) = model.get_task(&path) else {
+                    break;
+                };
+                let copy = task.duplicate();
+                let copy_id = copy.id;
+                let siblings = model.get_task_list_mut(&path);
+                let index = siblings.get_index_of(&path[path.len() - 1]).unwrap_or(0);
+                siblings.shift_insert(index + 1, copy_id, copy);
+                model.selected = Some(copy_id);
+                model.emit(Event::TaskAdded(copy_id));
+                update_parent_task_completion(model, &path);
+            }
+        }
+        Msg::OpenUrl => {
+            let path = model.get_path();
+            let url = model
+                .get_task(&path)
+                .and_then(|task| task.urls().next().map(str::to_string));
+            match url {
+                Some(url) => match open_in_system_handler(&url) {
+                    Ok(()) => model.notify(Severity::Success, &format!("Opened {}", url)),
+                    Err(error) => model.notify(
+                        Severity::Error,
+                        &format!("Failed to open {}: {}", url, error),
+                    ),
+                },
+                None => model.notify(Severity::Warning, "The selected task has no URL."),
+            }
+        }
+        Msg::AttachFile => {
+            let input = expand_home(model.input.text().trim());
+            let path = model.get_path();
+            if !input.exists() {
+                model.notify(
+                    Severity::Error,
+                    &format!("No such file: {}", input.display()),
+                );
+            } else if let Some(task) = model.get_task_mut(&path) {
+                task.attachments.push(input);
+                let id = task.id;
+                model.emit(Event::TaskChanged(id));
+                model.input.clear();
+                model.overlay = Overlay::None;
+            }
+        }
+        Msg::CompletePath => {
+            if let Some(completed) = complete_path(model.input.text()) {
+                model.input.set_text(&completed);
+            }
+        }
+        Msg::OpenAttachment => {
+            let path = model.get_path();
+            let attachment = model
+                .get_task(&path)
+                .and_then(|task| task.attachments.first().cloned());
+            match attachment {
+                Some(attachment) => {
+                    let target = attachment.to_string_lossy();
+                    match open_in_system_handler(&target) {
+                        Ok(()) => model.notify(Severity::Success, &format!("Opened {}", target)),
+                        Err(error) => model.notify(
+                            Severity::Error,
+                            &format!("Failed to open {}: {}", target, error),
+                        ),
+                    }
+                }
+                None => model.notify(Severity::Warning, "The selected task has no attachments."),
+            }
+        }
+        Msg::CompleteAllFiltered => {
+            let paths: Vec<Vec<Uuid>> = model
+                .nav
+                .values()
+                .filter(|path| model.get_task(path).is_some_and(|task| !task.completed))
+                .cloned()
+                .collect();
+            if paths.is_empty() {
+                model.notify(Severity::Info, "No incomplete tasks in the current view.");
+            } else {
+                let prompt = format!("Complete all {} visible incomplete tasks?", paths.len());
+                model.request_confirmation(prompt, Msg::CompleteTasks(paths));
+            }
+        }
+        Msg::CompleteTasks(paths) => {
+            for path in &paths {
+                if let Some(task) = model.get_task_mut(path) {
+                    task.set_completed(true);
+                    toggle_subtasks_completion(task);
+                    let id = task.id;
+                    model.emit(Event::TaskCompleted(id));
+                }
+                update_parent_task_completion(model, path);
+            }
+            model.notify(
+                Severity::Success,
+                &format!("Completed {} tasks.", paths.len()),
+            );
+        }
+        Msg::Confirm => {
+            model.overlay = Overlay::None;
+            if let Some((_, msg)) = model.pending_confirmation.take() {
+                update(*msg, model);
+            }
+        }
+        Msg::ScrollDebug(direction) => match direction {
+            Direction::Up => model.debug_scroll = model.debug_scroll.saturating_sub(1),
+            Direction::Down => model.debug_scroll = model.debug_scroll.saturating_add(1),
+        },
+    }
+}
+
+const TASK_FORM_FIELDS: [&str; 6] = [
+    "Description",
+    "Due",
+    "Priority",
+    "Reminders",
+    "Metadata",
+    "Notes",
+];
+const FORM_DESCRIPTION: usize = 0;
+const FORM_DUE: usize = 1;
+const FORM_PRIORITY: usize = 2;
+const FORM_REMINDERS: usize = 3;
+const FORM_META: usize = 4;
+const FORM_NOTES: usize = 5;
+
+/// The description without its `key:value` words, and those words sorted by
+/// key, for the task form.
+fn split_meta(task: &Task) -> (String, String) {
+    if task.meta.is_empty() {
+        return (task.description.clone(), String::new());
+    }
+    let description: Vec<&str> = task
+        .description
+        .split_whitespace()
+        .filter(|word| meta_field(word).is_none())
+        .collect();
+    let mut meta: Vec<String> = task
+        .meta
+        .iter()
+        .map(|(key, value)| format!("{}:{}", key, value))
+        .collect();
+    meta.sort();
+    (description.join(" "), meta.join(" "))
+}
+
+fn submit_task_form(model: &mut Model) {
+    let dates = &model.date_display;
+    let form = &mut model.form;
+    form.clear_errors();
+
+    let description = form.value(FORM_DESCRIPTION).trim().to_string();
+    if description.is_empty() {
+        form.set_error(FORM_DESCRIPTION, "Description can't be empty");
+    }
+    let due_time = parse_due_time(form.value(FORM_DUE), dates).unwrap_or_else(|error| {
+        form.set_error(FORM_DUE, error);
+        None
+    });
+    let priority_input = form.value(FORM_PRIORITY).trim();
+    let priority = if priority_input.is_empty() {
+        None
+    } else {
+        let priority = Priority::parse(priority_input);
+        if priority.is_none() {
+            form.set_error(FORM_PRIORITY, "Expected low, medium or high");
+        }
+        priority
+    };
+    let reminders = parse_reminders(form.value(FORM_REMINDERS), dates).unwrap_or_else(|error| {
+        form.set_error(FORM_REMINDERS, error);
+        Vec::new()
+    });
+    if due_time.is_none()
+        && reminders
+            .iter()
+            .any(|reminder| matches!(reminder, Reminder::BeforeDue { .. }))
+    {
+        form.set_error(FORM_REMINDERS, "Reminders before due need a due date");
+    }
+    let meta = form.value(FORM_META).trim().to_string();
+    if meta
+        .split_whitespace()
+        .any(|word| meta_field(word).is_none())
+    {
+        form.set_error(FORM_META, "Expected key:value words, e.g. client:Acme");
+    }
+    let description = match meta.is_empty() {
+        true => description,
+        false => format!("{} {}", description, meta),
+    };
+    let notes = form.value(FORM_NOTES).to_string();
+
+    if form.has_errors() {
+        return;
+    }
+
+    match model.form_target.take() {
+        Some(path) => {
+            if let Some(task) = model.get_task_mut(&path) {
+                let edited = task.description != description
+                    || task.due_time != due_time
+                    || task.priority != priority
+                    || task.reminders != reminders
+                    || task.notes != notes;
+                if edited {
+                    task.record(ChangeKind::Edited);
+                }
+                task.update_description(&description);
+                task.due_time = due_time;
+                task.priority = priority;
+                task.reminders = reminders;
+                task.notes = notes;
+                let id = task.id;
+                model.emit(Event::TaskChanged(id));
+            }
+        }
+        None => {
+            let mut task = Task::new(&description);
+            task.due_time = due_time;
+            task.priority = priority;
+            task.reminders = reminders;
+            task.notes = notes;
+            model.insert_task(task);
+            select_current(model);
+        }
+    }
+    model.overlay = Overlay::None;
+}
+
+/// Parses a due date typed in the display time zone.
+fn parse_due_time(input: &str, dates: &DateDisplay) -> Result<Option<DateTime<Utc>>, &'static str> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    dates.parse_local(input).map(Some)
+}
+
+/// Parses comma-separated reminders, see [`Reminder::parse`].
+fn parse_reminders(input: &str, dates: &DateDisplay) -> Result<Vec<Reminder>, &'static str> {
+    input
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| Reminder::parse(entry, dates))
+        .collect()
+}
+
+fn collect_due_reminders(
+    tasks: &IndexMap<Uuid, Task>,
+    after: DateTime<Utc>,
+    until: DateTime<Utc>,
+    due: &mut Vec<(Uuid, String, Option<DateTime<Utc>>)>,
+) {
+    for task in tasks.values() {
+        let fired = task.reminders.iter().any(|reminder| {
+            reminder
+                .time(task)
+                .is_some_and(|time| after < time && time <= until)
+        });
+        if fired && !task.completed {
+            due.push((task.id, task.description.clone(), task.due_time));
+        }
+        collect_due_reminders(&task.subtasks, after, until, due);
+    }
+}
+
+/// Shows reminders that came due since the last check.
+fn check_reminders(model: &mut Model) {
+    let now = Utc::now();
+    let Some(checked_at) = model.reminders_checked_at else {
+        model.reminders_checked_at = Some(now);
+        return;
+    };
+    if now - checked_at < chrono::Duration::seconds(1) {
+        return;
+    }
+    model.reminders_checked_at = Some(now);
+
+    let mut due = Vec::new();
+    collect_due_reminders(&model.tasks, checked_at, now, &mut due);
+    for (id, description, due_time) in due {
+        let message = match due_time {
+            Some(due_time) => format!(
+                "Reminder: {} (due {})",
+                description,
+                model.date_display.date_time(due_time, now)
+            ),
+            None => format!("Reminder: {}", description),
+        };
+        model.notify(Severity::Warning, &message);
+        model.emit(Event::ReminderDue(id));
+    }
+}
+
+/// Opens a URL or file with the platform's default handler without waiting for it.
+pub fn open_in_system_handler(target: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Puts `text` on the system clipboard through the platform's copy command:
+/// `pbcopy`, `clip`, or `wl-copy` or `xclip` depending on the display server.
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("pbcopy")
+    } else if cfg!(target_os = "windows") {
+        Command::new("clip")
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard"]);
+        command
+    };
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(text.as_bytes())?;
+    // The copy commands fork to keep serving the selection, so this returns
+    // once the text is read
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("copy command {}", status)));
+    }
+    Ok(())
+}
+
+fn expand_home(input: &str) -> PathBuf {
+    match (input.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// Completes the last path component of `input` to the longest prefix shared
+/// by all matching directory entries, appending a `/` for a unique directory.
+fn complete_path(input: &str) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(index) => (&input[..=index], &input[index + 1..]),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir_part)
+    };
+
+    let candidates: Vec<(String, bool)> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            name.starts_with(prefix).then_some((name, is_dir))
+        })
+        .collect();
+
+    let (first, _) = candidates.first()?;
+    let mut common = first.clone();
+    for (name, _) in &candidates[1..] {
+        let shared = common
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(common.len().min(name.len()), |((index, _), _)| index);
+        common.truncate(shared);
+    }
+    if let [(_, true)] = candidates.as_slice() {
+        common.push('/');
+    }
+    Some(format!("{}{}", dir_part, common))
+}
+
+fn toggle_subtasks_completion(task: &mut Task) {
+    for subtask in task.subtasks.values_mut() {
+        subtask.set_completed(task.completed);
+        toggle_subtasks_completion(subtask);
+    }
+}
+
+/// Removes the tasks at `paths` that are still there, emitting
+/// `TaskRemoved` for each and for each of its subtasks.
+fn remove_tasks(model: &mut Model, paths: Vec<Vec<Uuid>>) {
+    let paths: Vec<_> = paths
+        .into_iter()
+        .filter(|path| model.get_task(path).is_some())
+        .collect();
+    select_after_removal(model, &paths);
+    for path in &paths {
+        // An earlier path may have taken this one's ancestor with it
+        let Some(id) = path.last().filter(|_| model.get_task(path).is_some()) else {
+            continue;
+        };
+        if let Some(task) = model.get_task_list_mut(path).shift_remove(id) {
+            // Subtasks go with their parent, and subscribers hear about each
+            model.emit(Event::TaskRemoved(*id));
+            for (descendant, _) in flatten_tasks(&task.subtasks) {
+                model.emit(Event::TaskRemoved(descendant));
+            }
+        }
+        update_parent_task_completion(model, path);
+    }
+}
+
+/// The paths of the `count` rows from the selection down, leaving out the
+/// subtasks of rows already taken, for actions a count repeats.
+fn counted_paths(model: &Model, count: Option<usize>) -> Vec<Vec<Uuid>> {
+    let Some(start) = model.selected.and_then(|id| model.nav.get_index_of(&id)) else {
+        return vec![model.get_path()];
+    };
+    let mut paths: Vec<Vec<Uuid>> = Vec::new();
+    for (_, path) in model.nav.iter().skip(start).take(count.unwrap_or(1)) {
+        if !paths.iter().any(|taken| path.starts_with(taken)) {
+            paths.push(path.clone());
+        }
+    }
+    paths
+}
+
+/// Moves the selection to the closest visible task outside of the subtrees
+/// at `paths`, preferring the one below them.
+fn select_after_removal(model: &mut Model, paths: &[Vec<Uuid>]) {
+    let Some(index) = paths
+        .iter()
+        .filter_map(|path| path.last().and_then(|id| model.nav.get_index_of(id)))
+        .min()
+    else {
+        return;
+    };
+    let outside = |(_, task_path): &(&Uuid, &Vec<Uuid>)| {
+        !paths.iter().any(|path| task_path.starts_with(path))
+    };
+    let next = model.nav.iter().skip(index).find(outside);
+    let previous = model.nav.iter().take(index).rev().find(outside);
+    model.selected = next.or(previous).map(|(id, _)| *id);
+}
+
+fn select_current(model: &mut Model) {
+    if let Some(selected) = model.selected {
+        let current_index = model.nav.get_index_of(&selected).unwrap_or(0);
+        model.list_state.select(Some(current_index));
+    }
+}
+
+/// Whether the command line runs the built-in command `name`.
+fn name_is(command: &str, name: &str) -> bool {
+    command.split_whitespace().next() == Some(name)
+}
+
+/// Warns about filters of the current view that can't match or add nothing,
+/// with the simpler filters matching the same tasks.
+fn report_lint(model: &mut Model) {
+    let lint = lint::lint(&model.current_view);
+    if lint.findings.is_empty() {
+        return;
+    }
+    let findings: Vec<String> = lint.findings.iter().map(ToString::to_string).collect();
+    model.notify(
+        Severity::Warning,
+        &format!(
+            "{}. Simplified: {}",
+            findings.join("; "),
+            lint.simplified_text()
+        ),
+    );
+}
+
+/// Where the columns overlay starts a description it resizes.
+const DESCRIPTION_WIDTH: u16 = 40;
+const MAX_COLUMN_WIDTH: u16 = 200;
+
+/// Keeps the columns overlay on `column` after it moved in the list.
+fn select_column(model: &mut Model, column: Column) {
+    let index = model
+        .column_choices()
+        .iter()
+        .position(|choice| *choice == column);
+    model.column_state.select(index);
+}
+
+/// `:columns id description due:12` sets the columns of each row, in order
+/// and with optional widths.
+fn set_columns(model: &mut Model, args: &[String]) -> Result<()> {
+    model.columns = columns::parse(args)?;
+    model.notify(Severity::Success, "Updated the columns.");
+    Ok(())
+}
+
+/// Moves a list selection one step, wrapping around at either end.
+fn cycle_selection(state: &mut ListState, len: usize, direction: Direction) {
+    if len > 0 {
+        let next = match (state.selected(), direction) {
+            (None, _) => 0,
+            (Some(index), Direction::Up) => (index + len - 1) % len,
+            (Some(index), Direction::Down) => (index + 1) % len,
+        };
+        state.select(Some(next));
+    }
+}
+
+/// `:done-style [dim] [strike]` picks how finished tasks are de-emphasized,
+/// `:done-style off` goes back to the checkbox alone.
+fn set_completed_style(model: &mut Model, args: &[String]) -> Result<()> {
+    let mut style = CompletedStyle::default();
+    for arg in args {
+        match arg.as_str() {
+            "dim" => style.dim = true,
+            "strike" => style.strike = true,
+            "off" if args.len() == 1 => {}
+            _ => return Err(eyre!("Usage: done-style <dim|strike|dim strike|off>")),
+        }
+    }
+    if args.is_empty() {
+        return Err(eyre!("Usage: done-style <dim|strike|dim strike|off>"));
+    }
+    model.completed_style = style;
+    model.notify(Severity::Success, "Updated how finished tasks are shown.");
+    Ok(())
+}
+
+/// `:text-match fold` makes text criteria ignore case and accents,
+/// `:text-match exact` leaves that to a `/i` suffix. Applies to filters
+/// added from then on.
+fn set_text_match(model: &mut Model, args: &[String]) -> Result<()> {
+    let fold = match args {
+        [mode] if mode == "fold" => true,
+        [mode] if mode == "exact" => false,
+        _ => return Err(eyre!("Usage: text-match <fold|exact>")),
+    };
+    model.fold_text_filters = fold;
+    let message = if fold {
+        "Text filters now ignore case and accents."
+    } else {
+        "Text filters now match exactly unless they end in /i."
+    };
+    model.notify(Severity::Success, message);
+    Ok(())
+}
+
+/// `:animations on` scrolls the list to far jumps and flashes tasks an
+/// undo changed, `:animations off` goes back to plain redraws.
+fn set_animations(model: &mut Model, args: &[String]) -> Result<()> {
+    model.animations = match args {
+        [on] if on == "on" => true,
+        [off] if off == "off" => false,
+        _ => return Err(eyre!("Usage: animations <on|off>")),
+    };
+    model.animation = Animation::default();
+    let message = if model.animations {
+        "Animations on."
+    } else {
+        "Animations off."
+    };
+    model.notify(Severity::Success, message);
+    Ok(())
+}
+
+/// `:journal <path>` appends every completion to a Markdown file from then
+/// on, `:journal off` stops it.
+fn set_journal(model: &mut Model, args: &[String]) -> Result<()> {
+    model.journal = match args {
+        [off] if off == "off" => None,
+        [path] => Some(expand_home(path)),
+        _ => return Err(eyre!("Usage: journal <path|off>")),
+    };
+    let message = match &model.journal {
+        Some(path) => format!("Logging completions to {}.", path.display()),
+        None => "Stopped logging completions.".to_string(),
+    };
+    model.notify(Severity::Success, &message);
+    Ok(())
+}
+
+/// `:rollover [move|flag]` rolls over tasks due on past days now, moving
+/// them by default; `:rollover start <move|flag|off>` sets what happens when
+/// the app starts.
+fn rollover_command(model: &mut Model, args: &[String]) -> Result<()> {
+    let usage = "Usage: rollover [move|flag] | rollover start <move|flag|off>";
+    match args {
+        [] => update(Msg::Rollover(Rollover::Move), model),
+        [kind] => update(
+            Msg::Rollover(Rollover::parse(kind).ok_or_else(|| eyre!(usage))?),
+            model,
+        ),
+        [scope, kind] if scope == "start" => {
+            model.rollover_on_start = match kind.as_str() {
+                "off" => None,
+                kind => Some(Rollover::parse(kind).ok_or_else(|| eyre!(usage))?),
+            };
+            let kind = model.rollover_on_start.map_or("off", |kind| kind.as_str());
+            let message = format!("Rollover on start: {}.", kind);
+            model.notify(Severity::Success, &message);
+        }
+        _ => return Err(eyre!(usage)),
+    }
+    Ok(())
+}
+
+/// `:completion <auto|manual|percent|inherit>` sets the policy of the
+/// selected task, `:completion default <policy>` the one tasks inherit.
+fn set_completion_policy(model: &mut Model, args: &[String]) -> Result<()> {
+    let usage = "Usage: completion [default] <auto|manual|percent|inherit>";
+    match args {
+        [scope, policy] if scope == "default" => {
+            let policy = CompletionPolicy::parse(policy).ok_or_else(|| eyre!(usage))?;
+            model.completion_policy = policy;
+            model.notify(
+                Severity::Success,
+                &format!(
+                    "Parents now complete {} by default.",
+                    describe_policy(policy)
+                ),
+            );
+        }
+        [policy] => {
+            let policy = match policy.as_str() {
+                "inherit" => None,
+                policy => Some(CompletionPolicy::parse(policy).ok_or_else(|| eyre!(usage))?),
+            };
+            let path = model.get_path();
+            let task = model
+                .get_task_mut(&path)
+                .ok_or_else(|| eyre!("Select a task first."))?;
+            task.completion = policy;
+            let first_subtask = task.subtasks.keys().next().copied();
+            let effective = policy.unwrap_or(model.completion_policy);
+            // Switching to auto catches up with the subtasks right away
+            if let Some(id) = first_subtask.filter(|_| effective == CompletionPolicy::Auto) {
+                update_parent_task_completion(model, &[path, vec![id]].concat());
+            }
+            model.notify(
+                Severity::Success,
+                &format!("The task now completes {}.", describe_policy(effective)),
+            );
+        }
+        _ => return Err(eyre!(usage)),
+    }
+    Ok(())
+}
+
+fn describe_policy(policy: CompletionPolicy) -> &'static str {
+    match policy {
+        CompletionPolicy::Auto => "with its subtasks",
+        CompletionPolicy::Manual => "manually",
+        CompletionPolicy::Percent => "manually, showing a percentage",
+    }
+}
+
+/// Completes or reopens the parent of `path` to match its subtasks, and so on
+/// up the tree, stopping at parents whose policy isn't automatic.
+fn update_parent_task_completion(model: &mut Model, path: &[Uuid]) {
+    if path.len() <= 1 {
+        return; // No parent task
+    }
+
+    let parent_path = &path[..path.len() - 1];
+    let default_policy = model.completion_policy;
+    if let Some(parent_task) = model.get_task_mut(parent_path) {
+        if parent_task.subtasks.is_empty()
+            || parent_task.completion.unwrap_or(default_policy) != CompletionPolicy::Auto
+        {
+            return;
+        }
+        let all_subtasks_completed = parent_task.subtasks.values().all(|t| t.completed);
+        let was_completed = parent_task.completed;
+        parent_task.set_completed(all_subtasks_completed);
+        // A parent the cascade flips is completed or reopened like any other
+        // task, for its stamp, the journal and hooks
+        let (id, completed) = (parent_task.id, parent_task.completed);
+        match (was_completed, completed) {
+            (false, true) => model.emit(Event::TaskCompleted(id)),
+            (true, false) => model.emit(Event::TaskReopened(id)),
+            _ => (),
+        }
+        update_parent_task_completion(model, parent_path);
+    }
+}
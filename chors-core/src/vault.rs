@@ -0,0 +1,527 @@
+//! An Obsidian vault (or any folder of Markdown notes) as the task store.
+//!
+//! Every note with checkbox lines becomes a root task named after its path
+//! in the vault, and its `- [ ]`, `- [x]` and `- [-]` (cancelled) lines its
+//! subtasks, nested by indentation:
+//!
+//! ```markdown
+//! # Groceries
+//! - [ ] Weekly shop #errands
+//!     - [x] Milk
+//! ```
+//!
+//! Priorities and start, due and done dates are written after the
+//! description the way the Obsidian Tasks plugin does, by day:
+//!
+//! ```markdown
+//! - [ ] File taxes ⏫ 🛫 2024-03-01 📅 2024-03-15
+//! - [x] Renew passport ✅ 2024-02-20
+//! ```
+//!
+//! Notes, comments, reminders and attachments have no place in a checkbox
+//! line and are lost on the next load; [`unkept`] counts the tasks holding
+//! any, for frontends to warn about.
+//!
+//! Saving edits the checkbox lines in place and leaves everything else in the
+//! notes alone. New tasks go after their previous sibling, or under their
+//! parent; new root tasks become new notes. Notes are never deleted or
+//! renamed, and a note changed outside chors since it was read is skipped
+//! rather than overwritten, until [`Vault::reload_changed`] reads it again.
+
+use crate::{
+    dates::DateDisplay,
+    model::{Cancellation, Priority, Task},
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use indexmap::IndexMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+/// A checkbox line and the task read from it.
+#[derive(Debug, Clone)]
+struct Checkbox {
+    id: Uuid,
+    /// The task's parent when the note was read; the note's task at the top.
+    parent: Uuid,
+    /// Indentation and bullet, e.g. `"    - "`.
+    prefix: String,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    text: String,
+    checkbox: Option<Checkbox>,
+}
+
+#[derive(Debug, Clone)]
+struct Note {
+    /// The root task standing for the note.
+    id: Uuid,
+    path: PathBuf,
+    lines: Vec<Line>,
+    /// The note as last read or written, to notice outside changes.
+    content: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vault {
+    pub dir: PathBuf,
+    /// Dates in the lines are days in this time zone.
+    dates: DateDisplay,
+    notes: Vec<Note>,
+}
+
+impl Vault {
+    /// Reads every `.md` file under `dir`, skipping hidden folders like
+    /// `.obsidian`. Returns the vault and a root task per note with tasks.
+    pub fn load(dir: &Path, dates: &DateDisplay) -> Result<(Self, IndexMap<Uuid, Task>)> {
+        if !dir.is_dir() {
+            return Err(eyre!("{} is not a directory", dir.display()));
+        }
+        let mut paths = Vec::new();
+        collect_notes(dir, &mut paths)?;
+        paths.sort();
+        let mut vault = Vault {
+            dir: dir.to_path_buf(),
+            dates: dates.clone(),
+            notes: Vec::new(),
+        };
+        let mut tasks = IndexMap::new();
+        for path in paths {
+            let content = fs::read_to_string(&path)?;
+            let mut root = Task::new(&note_name(dir, &path));
+            let lines = parse(&content, &mut root, dates);
+            if root.subtasks.is_empty() {
+                continue;
+            }
+            vault.notes.push(Note {
+                id: root.id,
+                path,
+                lines,
+                content,
+            });
+            tasks.insert(root.id, root);
+        }
+        Ok((vault, tasks))
+    }
+
+    /// Writes the checkbox lines of `tasks` back to their notes, returning
+    /// how many notes changed. Notes that can't be written are reported
+    /// together after the others are saved.
+    pub fn save(&mut self, tasks: &IndexMap<Uuid, Task>) -> Result<usize> {
+        let mut written = 0;
+        let mut errors = Vec::new();
+        for root in tasks.values() {
+            if self.notes.iter().all(|note| note.id != root.id) {
+                match self.adopt(root) {
+                    Ok(note) => self.notes.push(note),
+                    Err(error) => {
+                        errors.push(error.to_string());
+                        continue;
+                    }
+                }
+            }
+        }
+        for note in &mut self.notes {
+            // A removed root leaves its note as it was
+            let Some(root) = tasks.get(&note.id) else {
+                continue;
+            };
+            let on_disk = fs::read_to_string(&note.path).unwrap_or_default();
+            if on_disk != note.content {
+                errors.push(format!("{} changed on disk", note.path.display()));
+                continue;
+            }
+            note.lines = render(&note.lines, root, &self.dates);
+            let mut content: Vec<&str> = note.lines.iter().map(|line| line.text.as_str()).collect();
+            if note.content.is_empty() || note.content.ends_with('\n') {
+                content.push("");
+            }
+            let content = content.join("\n");
+            if content == note.content {
+                continue;
+            }
+            if let Err(error) = fs::write(&note.path, &content) {
+                errors.push(format!("{}: {}", note.path.display(), error));
+                continue;
+            }
+            note.content = content;
+            written += 1;
+        }
+        match errors.is_empty() {
+            true => Ok(written),
+            false => Err(eyre!("{}", errors.join("; "))),
+        }
+    }
+
+    /// Reads the notes changed on disk since they were last read or written
+    /// again, dropping what was changed here under them. Returns the root
+    /// task of each with its new subtasks, for the caller to put in place.
+    pub fn reload_changed(&mut self) -> Result<Vec<(Uuid, IndexMap<Uuid, Task>)>> {
+        let mut reloaded = Vec::new();
+        for note in &mut self.notes {
+            let content = match fs::read_to_string(&note.path) {
+                Ok(content) => content,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(error) => return Err(eyre!("{}: {}", note.path.display(), error)),
+            };
+            if content == note.content {
+                continue;
+            }
+            let mut root = Task::new(&note_name(&self.dir, &note.path));
+            root.id = note.id;
+            note.lines = parse(&content, &mut root, &self.dates);
+            note.content = content;
+            reloaded.push((note.id, root.subtasks));
+        }
+        Ok(reloaded)
+    }
+
+    /// The note for a root task added in chors, named after its description.
+    /// An existing note without tasks gets the tasks appended.
+    fn adopt(&self, root: &Task) -> Result<Note> {
+        let name = root.description.trim();
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(eyre!("\"{}\" can't be used as a note name", name));
+        }
+        let path = self.dir.join(format!("{}.md", name));
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let mut lines = parse(&content, &mut Task::new(name), &self.dates);
+        // Checkboxes in an untracked note belong to no task
+        for line in &mut lines {
+            line.checkbox = None;
+        }
+        Ok(Note {
+            id: root.id,
+            path,
+            lines,
+            content,
+        })
+    }
+}
+
+/// How many tasks under `tasks` hold notes, comments, reminders or
+/// attachments, none of which a checkbox line keeps.
+pub fn unkept(tasks: &IndexMap<Uuid, Task>) -> usize {
+    tasks
+        .values()
+        .map(|task| {
+            let lost = !task.notes.is_empty()
+                || !task.comments.is_empty()
+                || !task.reminders.is_empty()
+                || !task.attachments.is_empty();
+            usize::from(lost) + unkept(&task.subtasks)
+        })
+        .sum()
+}
+
+fn collect_notes(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_notes(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "md") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// E.g. `Projects/Garden` for `<vault>/Projects/Garden.md`.
+fn note_name(dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(dir).unwrap_or(path).with_extension("");
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Splits a checkbox line into its prefix, mark and description.
+fn checkbox(text: &str) -> Option<(&str, char, &str)> {
+    let indent = text.len() - text.trim_start().len();
+    let rest = &text[indent..];
+    let bullet = rest
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '+'))?;
+    let rest = rest[bullet.len_utf8()..].strip_prefix(" [")?;
+    let mark = rest.chars().next()?;
+    let description = rest[mark.len_utf8()..].strip_prefix("] ")?;
+    let mark = match mark {
+        ' ' | 'x' | 'X' | '-' => mark,
+        _ => return None,
+    };
+    Some((
+        &text[..indent + bullet.len_utf8() + 1],
+        mark,
+        description.trim_end(),
+    ))
+}
+
+const HIGH: &str = "⏫";
+const MEDIUM: &str = "🔼";
+const LOW: &str = "🔽";
+const START: &str = "🛫";
+const DUE: &str = "📅";
+const DONE: &str = "✅";
+
+/// The fields of a checkbox line after its description.
+#[derive(Debug, Default)]
+struct Fields {
+    priority: Option<Priority>,
+    start: Option<NaiveDate>,
+    due: Option<NaiveDate>,
+    done: Option<NaiveDate>,
+}
+
+/// Splits the Obsidian Tasks fields off the end of a description. Fields it
+/// doesn't know, and whatever follows them, stay in the description.
+fn split_fields(description: &str) -> (&str, Fields) {
+    let mut fields = Fields::default();
+    let mut rest = description.trim_end();
+    loop {
+        let priority = [
+            ("🔺", Priority::High),
+            (HIGH, Priority::High),
+            (MEDIUM, Priority::Medium),
+            (LOW, Priority::Low),
+            ("⏬", Priority::Low),
+        ]
+        .into_iter()
+        .find(|(emoji, _)| rest.ends_with(emoji));
+        if let Some((emoji, priority)) = priority.filter(|_| fields.priority.is_none()) {
+            fields.priority = Some(priority);
+            rest = rest[..rest.len() - emoji.len()].trim_end();
+            continue;
+        }
+        let Some((before, date)) = rest.rsplit_once(' ') else {
+            break;
+        };
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            break;
+        };
+        let before = before.trim_end();
+        let field = [
+            (START, &mut fields.start),
+            (DUE, &mut fields.due),
+            (DONE, &mut fields.done),
+        ]
+        .into_iter()
+        .find(|(emoji, field)| before.ends_with(emoji) && field.is_none());
+        let Some((emoji, field)) = field else {
+            break;
+        };
+        *field = Some(date);
+        rest = before[..before.len() - emoji.len()].trim_end();
+    }
+    (rest, fields)
+}
+
+fn indent_width(prefix: &str) -> usize {
+    let indent = &prefix[..prefix.len() - prefix.trim_start().len()];
+    indent.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+/// Reads the checkbox lines of a note into `root`'s subtasks.
+fn parse(content: &str, root: &mut Task, dates: &DateDisplay) -> Vec<Line> {
+    let mut lines = Vec::new();
+    // Open checkboxes by indentation, each with the path to its task
+    let mut stack: Vec<(usize, Vec<Uuid>)> = Vec::new();
+    for text in content.lines() {
+        let Some((prefix, mark, description)) = checkbox(text) else {
+            // Anything else ends nested lists unless it is indented too
+            if !text.starts_with([' ', '\t']) {
+                stack.clear();
+            }
+            lines.push(Line {
+                text: text.to_string(),
+                checkbox: None,
+            });
+            continue;
+        };
+        let width = indent_width(prefix);
+        while stack.last().is_some_and(|(open, _)| *open >= width) {
+            stack.pop();
+        }
+        let path = stack
+            .last()
+            .map(|(_, path)| path.clone())
+            .unwrap_or_default();
+        let (description, fields) = split_fields(description);
+        let day = |date: Option<NaiveDate>| {
+            date.and_then(|date| dates.from_local(date.and_time(NaiveTime::MIN)))
+        };
+        let mut task = Task::new(description);
+        task.priority = fields.priority;
+        task.start_time = day(fields.start);
+        task.due_time = day(fields.due);
+        match mark {
+            ' ' => {}
+            '-' => {
+                task.completed = true;
+                task.cancelled = Some(Cancellation {
+                    at: Utc::now(),
+                    reason: None,
+                });
+            }
+            _ => {
+                task.completed = true;
+                task.completed_at = day(fields.done);
+            }
+        }
+        let id = task.id;
+        let parent = path.last().copied().unwrap_or(root.id);
+        let mut siblings = &mut root.subtasks;
+        for step in &path {
+            siblings = &mut siblings[step].subtasks;
+        }
+        siblings.insert(id, task);
+        lines.push(Line {
+            text: text.to_string(),
+            checkbox: Some(Checkbox {
+                id,
+                parent,
+                prefix: prefix.to_string(),
+            }),
+        });
+        let mut path = path;
+        path.push(id);
+        stack.push((width, path));
+    }
+    lines
+}
+
+fn checkbox_text(prefix: &str, task: &Task, dates: &DateDisplay) -> String {
+    let mark = match (task.completed, &task.cancelled) {
+        (_, Some(_)) => '-',
+        (true, None) => 'x',
+        (false, None) => ' ',
+    };
+    let mut text = format!("{}[{}] {}", prefix, mark, task.description);
+    if let Some(priority) = task.priority {
+        text.push(' ');
+        text.push_str(match priority {
+            Priority::High => HIGH,
+            Priority::Medium => MEDIUM,
+            Priority::Low => LOW,
+        });
+    }
+    let mut day = |emoji: &str, time: Option<DateTime<Utc>>| {
+        if let Some(time) = time {
+            text.push_str(&format!(" {} {}", emoji, dates.local_date(time)));
+        }
+    };
+    day(START, task.start_time);
+    day(DUE, task.due_time);
+    day(DONE, task.completed_at.filter(|_| mark == 'x'));
+    text
+}
+
+/// The note's lines after `root`'s subtasks changed: checkboxes of tasks
+/// still under the same parent are rewritten, the others dropped, and new
+/// tasks inserted.
+fn render(lines: &[Line], root: &Task, dates: &DateDisplay) -> Vec<Line> {
+    // Every task under the root with its parent, in tree order
+    let mut tasks: Vec<(&Task, Uuid)> = Vec::new();
+    fn walk<'a>(task: &'a Task, out: &mut Vec<(&'a Task, Uuid)>) {
+        for subtask in task.subtasks.values() {
+            out.push((subtask, task.id));
+            walk(subtask, out);
+        }
+    }
+    walk(root, &mut tasks);
+    let parents: HashMap<Uuid, (&Task, Uuid)> = tasks
+        .iter()
+        .map(|(task, parent)| (task.id, (*task, *parent)))
+        .collect();
+
+    let mut out: Vec<Line> = Vec::new();
+    for line in lines {
+        match &line.checkbox {
+            None => out.push(line.clone()),
+            Some(checkbox) => {
+                let Some((task, parent)) = parents.get(&checkbox.id) else {
+                    continue;
+                };
+                if *parent != checkbox.parent {
+                    continue;
+                }
+                out.push(Line {
+                    text: checkbox_text(&checkbox.prefix, task, dates),
+                    checkbox: Some(checkbox.clone()),
+                });
+            }
+        }
+    }
+
+    let position = |out: &[Line], id: Uuid| {
+        out.iter()
+            .position(|line| line.checkbox.as_ref().is_some_and(|c| c.id == id))
+    };
+    for (task, parent) in tasks {
+        if position(&out, task.id).is_some() {
+            continue;
+        }
+        let siblings = match parents.get(&parent) {
+            Some((parent, _)) => &parent.subtasks,
+            None => &root.subtasks,
+        };
+        let index = siblings.get_index_of(&task.id).unwrap_or(0);
+        let previous = siblings
+            .keys()
+            .take(index)
+            .rev()
+            .find_map(|id| position(&out, *id).map(|at| (*id, at)));
+        let (at, prefix) = match (previous, position(&out, parent)) {
+            // After the previous sibling and everything nested under it
+            (Some((_, at)), _) => {
+                let prefix = out[at].checkbox.as_ref().unwrap().prefix.clone();
+                let width = indent_width(&prefix);
+                let end = out[at + 1..]
+                    .iter()
+                    .position(|line| {
+                        line.checkbox
+                            .as_ref()
+                            .is_none_or(|c| indent_width(&c.prefix) <= width)
+                    })
+                    .map_or(out.len(), |offset| at + 1 + offset);
+                (end, prefix)
+            }
+            (None, Some(at)) => {
+                let parent = &out[at].checkbox.as_ref().unwrap().prefix;
+                let indent = &parent[..parent.len() - parent.trim_start().len()];
+                let unit = if indent.contains('\t') { "\t" } else { "    " };
+                (at + 1, format!("{}{}- ", indent, unit))
+            }
+            (None, None) => (out.len(), "- ".to_string()),
+        };
+        out.insert(
+            at,
+            Line {
+                text: checkbox_text(&prefix, task, dates),
+                checkbox: Some(Checkbox {
+                    id: task.id,
+                    parent,
+                    prefix,
+                }),
+            },
+        );
+    }
+    out
+}
@@ -0,0 +1,104 @@
+use crate::{
+    dates::DateDisplay,
+    model::{Task, View},
+};
+use indexmap::IndexMap;
+use rayon::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::Instant,
+};
+use uuid::Uuid;
+
+/// Trees with more tasks than this are filtered on a worker thread when the
+/// view changes, so that redraws (and typing) aren't blocked meanwhile.
+pub const BACKGROUND_FILTER_THRESHOLD: usize = 10_000;
+
+/// With at least this many root tasks, the root subtrees are evaluated in
+/// parallel. Fewer roots leave too little to split up.
+pub const PARALLEL_FILTER_ROOTS: usize = 8;
+
+/// Evaluates `view` for every task in `tasks`, spreading root subtrees over
+/// the rayon pool when there are enough of them.
+pub fn evaluate(
+    tasks: &IndexMap<Uuid, Task>,
+    view: &View,
+    dates: &DateDisplay,
+) -> HashMap<Uuid, bool> {
+    fn subtree(
+        task: &Task,
+        view: &View,
+        dates: &DateDisplay,
+        ancestors: &mut Vec<Uuid>,
+        out: &mut HashMap<Uuid, bool>,
+    ) {
+        out.insert(task.id, view.matches_at(task, ancestors, dates));
+        ancestors.push(task.id);
+        for subtask in task.subtasks.values() {
+            subtree(subtask, view, dates, ancestors, out);
+        }
+        ancestors.pop();
+    }
+
+    let mut matches = HashMap::new();
+    if tasks.len() < PARALLEL_FILTER_ROOTS {
+        for task in tasks.values() {
+            subtree(task, view, dates, &mut Vec::new(), &mut matches);
+        }
+        return matches;
+    }
+    let parts: Vec<HashMap<Uuid, bool>> = tasks
+        .par_values()
+        .map(|task| {
+            let mut part = HashMap::new();
+            subtree(task, view, dates, &mut Vec::new(), &mut part);
+            part
+        })
+        .collect();
+    // Merged in insertion order of the roots
+    for part in parts {
+        matches.extend(part);
+    }
+    matches
+}
+
+/// Filter evaluation running on a worker thread. `generation` identifies the
+/// request; results of superseded generations are discarded.
+#[derive(Debug, Clone)]
+pub struct FilterJob {
+    pub generation: u64,
+    pub started: Instant,
+    result: Arc<Mutex<Option<HashMap<Uuid, bool>>>>,
+}
+
+impl FilterJob {
+    pub fn spawn(
+        generation: u64,
+        tasks: IndexMap<Uuid, Task>,
+        view: View,
+        dates: DateDisplay,
+    ) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&result);
+        thread::spawn(move || {
+            *slot.lock().unwrap() = Some(evaluate(&tasks, &view, &dates));
+        });
+        Self {
+            generation,
+            started: Instant::now(),
+            result,
+        }
+    }
+
+    /// Takes the result if the worker has finished.
+    pub fn try_take(&self) -> Option<HashMap<Uuid, bool>> {
+        self.result.lock().unwrap().take()
+    }
+
+    pub fn spinner(&self) -> char {
+        const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        FRAMES[(self.started.elapsed().as_millis() / 80) as usize % FRAMES.len()]
+    }
+}
@@ -0,0 +1,72 @@
+//! Scrolling to far jumps and flashing tasks an undo changed.
+
+use chors_core::{
+    animation::{Animation, FLASH_DURATION, SCROLL_DURATION},
+    model::{Model, Msg, Task},
+    update::update,
+};
+use chrono::Utc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn scrolls_ease_out_and_end_at_the_target() {
+    let start = Instant::now();
+    let mut animation = Animation::default();
+    animation.scroll(0, 90, start);
+    assert!(animation.is_running());
+    assert_eq!(animation.scroll_offset(start), Some(0));
+    let halfway = animation
+        .scroll_offset(start + SCROLL_DURATION / 2)
+        .unwrap();
+    assert!(halfway > 45 && halfway < 90);
+    assert_eq!(animation.scroll_offset(start + SCROLL_DURATION), None);
+
+    assert_eq!(animation.tick(start + Duration::from_millis(100)), None);
+    assert!(animation.is_running());
+    assert_eq!(animation.tick(start + SCROLL_DURATION), Some(90));
+    assert!(!animation.is_running());
+
+    animation.scroll(90, 10, start);
+    assert!(
+        animation
+            .scroll_offset(start + SCROLL_DURATION / 2)
+            .unwrap()
+            < 50
+    );
+}
+
+#[test]
+fn flashes_fade_after_a_moment() {
+    let start = Instant::now();
+    let task = Task::new("Water plants");
+    let mut animation = Animation::default();
+    animation.flash([task.id], start);
+    assert!(animation.is_flashing(task.id, start));
+    assert!(!animation.is_flashing(Task::new("Other").id, start));
+    assert!(!animation.is_flashing(task.id, start + FLASH_DURATION));
+    animation.tick(start + FLASH_DURATION);
+    assert!(!animation.is_running());
+}
+
+#[test]
+fn undoing_a_rollover_flashes_its_tasks() {
+    let mut model = Model::new();
+    let mut task = Task::new("File taxes");
+    task.due_time = Some(Utc::now() - chrono::Duration::days(2));
+    let id = task.id;
+    model.tasks.insert(id, task);
+
+    update(Msg::RunCommand("rollover".to_string()), &mut model);
+    update(Msg::UndoRollover, &mut model);
+    assert!(!model.animation.is_running());
+
+    update(Msg::RunCommand("animations on".to_string()), &mut model);
+    assert!(model.animations);
+    update(Msg::RunCommand("rollover".to_string()), &mut model);
+    update(Msg::UndoRollover, &mut model);
+    assert!(model.animation.is_flashing(id, Instant::now()));
+
+    update(Msg::RunCommand("animations off".to_string()), &mut model);
+    assert!(!model.animations);
+    assert!(!model.animation.is_running());
+}
@@ -0,0 +1,47 @@
+//! Stamping who added and completed tasks, and filtering by it.
+
+use chors_core::{
+    events::Event,
+    model::{Filter, Model, Msg, Task},
+    update::update,
+};
+
+#[test]
+fn adding_and_completing_are_attributed() {
+    let mut model = Model::new();
+    model.author = Some("alice".to_string());
+    model.input.set_text("Buy milk");
+    update(Msg::AddTask, &mut model);
+    let id = model.selected.unwrap();
+    let path = model.path_of(id).unwrap();
+    assert_eq!(
+        model.get_task(&path).unwrap().author.as_deref(),
+        Some("alice")
+    );
+
+    model.author = Some("bob".to_string());
+    update(Msg::CompleteTasks(vec![path.clone()]), &mut model);
+    let task = model.get_task(&path).unwrap();
+    assert_eq!(task.author.as_deref(), Some("alice"));
+    assert_eq!(task.completed_by.as_deref(), Some("bob"));
+    assert!(Filter::parse("by:bob").unwrap().matches(task));
+    assert!(Filter::parse("by:alice").unwrap().matches(task));
+    assert!(!Filter::parse("by:carol").unwrap().matches(task));
+
+    model.get_task_mut(&path).unwrap().set_completed(false);
+    model.emit(Event::TaskReopened(id));
+    assert_eq!(model.get_task(&path).unwrap().completed_by, None);
+}
+
+#[test]
+fn nothing_is_stamped_without_an_author() {
+    let mut model = Model::new();
+    let task = Task::new("Water plants");
+    let path = vec![task.id];
+    model.tasks.insert(task.id, task);
+    update(Msg::CompleteTasks(vec![path.clone()]), &mut model);
+    let task = model.get_task(&path).unwrap();
+    assert_eq!((&task.author, &task.completed_by), (&None, &None));
+    assert_eq!(Filter::parse("by:alice").unwrap().to_string(), "by:alice");
+    assert!(Filter::parse("by:").is_err());
+}
@@ -0,0 +1,255 @@
+//! Syncing with a CalDAV collection, against an in-memory server.
+
+use chors_core::{
+    caldav::{self, Server, Status, Todo},
+    dates::DateDisplay,
+    model::{ChangeKind, Model, Priority, Task},
+};
+use chrono::{TimeZone, Utc};
+use color_eyre::Result;
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+struct Collection {
+    items: BTreeMap<String, String>,
+}
+
+impl Server for Collection {
+    fn list(&mut self) -> Result<Vec<Todo>> {
+        let dates = DateDisplay::default();
+        Ok(self
+            .items
+            .iter()
+            .filter_map(|(href, ics)| caldav::parse_todo(ics, href, &dates))
+            .collect())
+    }
+
+    fn put(&mut self, href: &str, ics: &str) -> Result<()> {
+        self.items.insert(href.to_string(), ics.to_string());
+        Ok(())
+    }
+
+    fn delete(&mut self, href: &str) -> Result<()> {
+        self.items.remove(href);
+        Ok(())
+    }
+}
+
+impl Collection {
+    fn todo(&mut self, href: &str) -> Todo {
+        let mut todos = self.list().unwrap();
+        let at = todos.iter().position(|todo| todo.href == href).unwrap();
+        todos.remove(at)
+    }
+}
+
+const PHONE: &str = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:phone-1\r\nSUMMARY:Call the bank\\, again\r\nDUE;VALUE=DATE:20240308\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+fn description(model: &Model, id: uuid::Uuid) -> Option<String> {
+    let path = model.path_of(id)?;
+    model.get_task(&path).map(|task| task.description.clone())
+}
+
+#[test]
+fn tasks_go_both_ways() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 10, 0, 0).unwrap();
+    let mut model = Model::new();
+    let mut trip = Task::new("Plan trip #travel");
+    let flights = Task::new("Book flights");
+    let flights_id = flights.id;
+    trip.subtasks.insert(flights.id, flights);
+    let trip_id = trip.id;
+    model.tasks.insert(trip.id, trip);
+    let mut server = Collection::default();
+    server.put("phone-1.ics", PHONE).unwrap();
+
+    let report = caldav::sync(&mut model, &mut server, now).unwrap();
+    assert_eq!((report.pulled, report.pushed, report.deleted), (1, 2, 0));
+    let pushed = server.todo(&format!("{}.ics", flights_id));
+    assert_eq!(pushed.fields.parent, Some(trip_id.to_string()));
+    let (&phone_id, _) = model
+        .caldav
+        .items
+        .iter()
+        .find(|(_, synced)| synced.uid == "phone-1")
+        .unwrap();
+    assert_eq!(
+        description(&model, phone_id).as_deref(),
+        Some("Call the bank, again")
+    );
+
+    // Nothing changed, nothing to do
+    let report = caldav::sync(&mut model, &mut server, now).unwrap();
+    assert_eq!(report, caldav::Report::default());
+
+    // Completed on the phone, renamed here
+    let done = server.items["phone-1.ics"].replace("END:VTODO", "STATUS:COMPLETED\r\nEND:VTODO");
+    server.put("phone-1.ics", &done).unwrap();
+    let path = model.path_of(trip_id).unwrap();
+    model
+        .get_task_mut(&path)
+        .unwrap()
+        .update_description("Plan the trip #travel");
+    let report = caldav::sync(&mut model, &mut server, now).unwrap();
+    assert_eq!((report.pulled, report.pushed), (1, 1));
+    let path = model.path_of(phone_id).unwrap();
+    assert!(model.get_task(&path).unwrap().completed);
+    let trip = server.todo(&format!("{}.ics", trip_id));
+    assert_eq!(trip.fields.summary, "Plan the trip #travel");
+
+    // Deleted here, then deleted there
+    let path = model.path_of(flights_id).unwrap();
+    model.get_task_list_mut(&path).shift_remove(&flights_id);
+    server.delete("phone-1.ics").unwrap();
+    let report = caldav::sync(&mut model, &mut server, now).unwrap();
+    assert_eq!(report.deleted, 2);
+    assert_eq!(server.items.len(), 1);
+    assert_eq!(description(&model, phone_id), None);
+    assert_eq!(model.caldav.items.len(), 1);
+}
+
+#[test]
+fn tasks_modified_here_outlive_a_delete_there() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 10, 0, 0).unwrap();
+    let mut model = Model::new();
+    let (garden, bills) = (Task::new("Weed the garden"), Task::new("Pay bills"));
+    let (garden_id, bills_id) = (garden.id, bills.id);
+    model.tasks.insert(garden.id, garden);
+    model.tasks.insert(bills.id, bills);
+    let mut server = Collection::default();
+    caldav::sync(&mut model, &mut server, now).unwrap();
+
+    // Only a field the server doesn't hold changed, but later than the sync
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let path = model.path_of(garden_id).unwrap();
+    let garden = model.get_task_mut(&path).unwrap();
+    garden.priority = Some(Priority::High);
+    garden.record(ChangeKind::Edited);
+    server.items.clear();
+
+    let report = caldav::sync(&mut model, &mut server, now).unwrap();
+    assert_eq!((report.pushed, report.deleted), (1, 1));
+    assert_eq!(
+        description(&model, garden_id).as_deref(),
+        Some("Weed the garden")
+    );
+    assert_eq!(description(&model, bills_id), None);
+    assert!(server.items.contains_key(&format!("{}.ics", garden_id)));
+}
+
+#[test]
+fn requests_stay_on_the_collection_host() {
+    let url = "https://dav.example.com:8443/calendars/me/tasks/";
+    assert_eq!(
+        caldav::resolve(url, "a.ics").unwrap(),
+        "https://dav.example.com:8443/calendars/me/tasks/a.ics"
+    );
+    assert_eq!(
+        caldav::resolve(url, "/calendars/me/tasks/a.ics").unwrap(),
+        "https://dav.example.com:8443/calendars/me/tasks/a.ics"
+    );
+    assert!(caldav::resolve(url, "https://dav.example.com:8443/calendars/me/tasks/a.ics").is_ok());
+    assert!(caldav::resolve(url, "https://evil.example.com/a.ics").is_err());
+    assert!(caldav::resolve(url, "https://dav.example.com/a.ics").is_err());
+    assert!(caldav::resolve(url, "http://dav.example.com:8443/a.ics").is_err());
+}
+
+#[test]
+fn calendar_objects_round_trip() {
+    let mut task = Task::new("Pay rent; then, relax\nfor real");
+    task.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 8, 9, 30, 0).unwrap());
+    task.set_completed(true);
+    let fields = caldav::Fields {
+        summary: task.description.clone(),
+        notes:
+            "A very long note that goes well past the seventy-five bytes a content line may have"
+                .to_string(),
+        status: Status::Completed,
+        completed: task.completed_at,
+        due: task.due_time,
+        start: None,
+        parent: Some("parent-uid".to_string()),
+    };
+    let ics = caldav::to_ics("uid-1", &fields, &task, Utc::now());
+    assert!(ics.lines().all(|line| line.len() <= 76));
+    let todo = caldav::parse_todo(&ics, "uid-1.ics", &DateDisplay::default()).unwrap();
+    assert_eq!(todo.uid, "uid-1");
+    assert_eq!(
+        todo.fields.completed.map(|at| at.timestamp()),
+        fields.completed.map(|at| at.timestamp())
+    );
+    assert_eq!(
+        caldav::Fields {
+            completed: fields.completed,
+            ..todo.fields
+        },
+        fields
+    );
+}
+
+#[test]
+fn reads_multistatus_replies() {
+    let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/tasks/a.ics</d:href>
+    <d:propstat><d:prop>
+      <cal:calendar-data>BEGIN:VCALENDAR&#13;
+BEGIN:VTODO&#13;
+UID:a&#13;
+SUMMARY:Fish &amp; chips&#13;
+END:VTODO&#13;
+END:VCALENDAR&#13;
+</cal:calendar-data>
+    </d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let todos = caldav::parse_multistatus(xml, &DateDisplay::default());
+    assert_eq!(todos.len(), 1);
+    assert_eq!(todos[0].href, "/dav/tasks/a.ics");
+    assert_eq!(todos[0].fields.summary, "Fish & chips");
+}
+
+#[test]
+fn config_needs_every_key() {
+    let config = caldav::parse_config(
+        "# Nextcloud\nurl = https://cloud.example.com/remote.php/dav/calendars/me/tasks\nusername = me\npassword = p=w\n",
+    )
+    .unwrap();
+    assert!(config.url.ends_with("/tasks/"));
+    assert_eq!(config.password, "p=w");
+    assert!(!format!("{:?}", config).contains("p=w"));
+    assert!(caldav::parse_config("url = https://example.com/").is_err());
+
+    let plain = "url = http://dav.example.com/tasks/\nusername = me\npassword = secret\n";
+    assert!(caldav::parse_config(plain).is_err());
+    let local = plain.replace("dav.example.com", "localhost:5232");
+    assert!(caldav::parse_config(&local).is_ok());
+}
+
+#[test]
+fn feed_has_the_open_due_tasks() {
+    let mut model = Model::new();
+    model.date_display.zone = Some(chors_core::dates::parse_zone("UTC").unwrap());
+    let mut rent = Task::new("Pay rent");
+    rent.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 8, 9, 30, 0).unwrap());
+    let mut bins = Task::new("Bins, recycling");
+    bins.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 9, 0, 0, 0).unwrap());
+    let mut done = Task::new("Renew passport");
+    done.due_time = rent.due_time;
+    done.set_completed(true);
+    for task in [rent, bins, done, Task::new("Someday")] {
+        model.tasks.insert(task.id, task);
+    }
+
+    let events = caldav::feed(&model, false, Utc::now());
+    assert_eq!(events.matches("BEGIN:VEVENT").count(), 2);
+    assert!(events.contains("DTSTART:20240308T093000Z\r\n"));
+    assert!(events.contains("DTSTART;VALUE=DATE:20240309\r\n"));
+    assert!(events.contains("SUMMARY:Bins\\, recycling\r\n"));
+    assert!(!events.contains("Renew passport"));
+
+    let todos = caldav::feed(&model, true, Utc::now());
+    assert_eq!(todos.matches("BEGIN:VTODO").count(), 2);
+    assert!(todos.contains("DUE:20240309T000000Z\r\n"));
+}
@@ -0,0 +1,40 @@
+//! Parsing the `:columns` layout and reading it from the data file.
+
+use chors_core::{
+    columns::{self, Column, ColumnSpec},
+    model::Model,
+};
+
+fn args(input: &str) -> Vec<String> {
+    input.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn columns_keep_their_order_and_widths() {
+    let specs = columns::parse(&args("id description due:12")).unwrap();
+    assert_eq!(
+        specs,
+        [
+            ColumnSpec::new(Column::Id),
+            ColumnSpec::new(Column::Description),
+            ColumnSpec {
+                column: Column::Due,
+                width: Some(12)
+            },
+        ]
+    );
+    assert_eq!(specs[0].width(), Some(4));
+    assert_eq!(specs[1].width(), None);
+    assert!(columns::parse(&args("due")).is_err());
+    assert!(columns::parse(&args("description size")).is_err());
+    assert!(columns::parse(&args("description due:0")).is_err());
+    assert!(columns::parse(&args("description age age")).is_err());
+}
+
+#[test]
+fn older_data_files_get_the_default_layout() {
+    let mut json = serde_json::to_value(Model::new()).unwrap();
+    json.as_object_mut().unwrap().remove("columns");
+    let model: Model = serde_json::from_value(json).unwrap();
+    assert_eq!(model.columns, columns::default_columns());
+}
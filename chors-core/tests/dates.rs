@@ -0,0 +1,313 @@
+//! Time zone handling of the date presentation layer, pinned to explicit
+//! zones so the results don't depend on the machine running the tests, and
+//! the completion and due date filters, which are relative to now, the
+//! former skipping cancelled tasks, creation times, scheduling from the planning board, the rows of the timeline and
+//! the days of the activity heatmap.
+
+use chors_core::{
+    dates::{self, DateDisplay, DueBucket},
+    model::{ActivityMetric, Filter, FilterList, Model, Msg, Task},
+    update::update,
+};
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+fn display(zone: &str) -> DateDisplay {
+    DateDisplay {
+        zone: Some(dates::parse_zone(zone).unwrap()),
+        ..DateDisplay::default()
+    }
+}
+
+#[test]
+fn local_dates_follow_the_zone() {
+    // 23:30 UTC is already the next day east of Greenwich
+    let time = Utc.with_ymd_and_hms(2024, 3, 10, 23, 30, 0).unwrap();
+    assert_eq!(
+        display("UTC").local_date(time),
+        NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()
+    );
+    assert_eq!(
+        display("Europe/Vilnius").local_date(time),
+        NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()
+    );
+}
+
+#[test]
+fn wall_clock_input_round_trips() {
+    let dates = display("America/New_York");
+    let naive = NaiveDate::from_ymd_opt(2024, 7, 1)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap();
+    let stored = dates.from_local(naive).unwrap();
+    assert_eq!(stored, Utc.with_ymd_and_hms(2024, 7, 1, 13, 0, 0).unwrap());
+    assert_eq!(dates.local(stored).naive_local(), naive);
+
+    // Skipped by the spring DST change
+    let missing = NaiveDate::from_ymd_opt(2024, 3, 10)
+        .unwrap()
+        .and_hms_opt(2, 30, 0)
+        .unwrap();
+    assert_eq!(dates.from_local(missing), None);
+}
+
+#[test]
+fn buckets_use_the_local_day_and_week_start() {
+    // Friday 2024-03-08, 22:00 UTC; already Saturday in Vilnius
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 22, 0, 0).unwrap();
+    let due = |day, hour| Some(Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap());
+
+    let utc = display("UTC");
+    assert_eq!(utc.bucket(due(8, 21), now), DueBucket::Overdue);
+    assert_eq!(utc.bucket(due(8, 23), now), DueBucket::Today);
+    assert_eq!(utc.bucket(due(10, 12), now), DueBucket::ThisWeek);
+    assert_eq!(utc.bucket(due(11, 12), now), DueBucket::Later);
+    assert_eq!(utc.bucket(None, now), DueBucket::NoDueDate);
+
+    let vilnius = display("Europe/Vilnius");
+    assert_eq!(vilnius.bucket(due(9, 12), now), DueBucket::Today);
+
+    let sunday_first = DateDisplay {
+        week_start: Weekday::Sun,
+        ..display("UTC")
+    };
+    assert_eq!(sunday_first.bucket(due(9, 12), now), DueBucket::ThisWeek);
+    assert_eq!(sunday_first.bucket(due(10, 12), now), DueBucket::Later);
+}
+
+#[test]
+fn offsets_in_older_files_are_read_as_utc() {
+    let mut task = serde_json::to_value(Task::new("Old")).unwrap();
+    task["due_time"] = "2024-03-08T10:00:00+02:00".into();
+    let task: Task = serde_json::from_value(task).unwrap();
+    assert_eq!(
+        task.due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 8, 8, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn invalid_formats_fall_back_instead_of_panicking() {
+    let dates = DateDisplay {
+        date_format: "%Q".to_string(),
+        ..display("UTC")
+    };
+    let time = Utc.with_ymd_and_hms(2024, 3, 8, 10, 0, 0).unwrap();
+    assert_eq!(dates.date_time(time, time), "2024-03-08 10:00");
+}
+
+#[test]
+fn done_filters_follow_completion_time() {
+    let today = FilterList::parse("done:today").unwrap();
+    let yesterday = FilterList::parse("done:yesterday").unwrap();
+    let week = FilterList::parse("done:7d").unwrap();
+
+    let mut task = Task::new("Standup notes");
+    assert!(!today.matches(&task));
+    task.set_completed(true);
+    assert!(today.matches(&task) && week.matches(&task) && !yesterday.matches(&task));
+
+    task.completed_at = Some(Utc::now() - chrono::Duration::days(1));
+    assert!(yesterday.matches(&task) && !today.matches(&task));
+    task.set_completed(true);
+    assert!(
+        yesterday.matches(&task),
+        "recompleting keeps the original time"
+    );
+
+    task.set_completed(false);
+    assert_eq!(task.completed_at, None);
+    assert!(FilterList::parse("done:0d").is_err());
+}
+
+#[test]
+fn done_filters_count_days_in_the_display_time_zone() {
+    let today = FilterList::parse("done:today").unwrap();
+    let yesterday = FilterList::parse("done:yesterday").unwrap();
+    for zone in ["Pacific/Kiritimati", "Pacific/Pago_Pago"] {
+        let dates = display(zone);
+        let midnight = dates
+            .from_local(dates.local_date(Utc::now()).and_time(NaiveTime::MIN))
+            .unwrap();
+        let mut task = Task::new("Standup notes");
+        task.completed = true;
+        task.completed_at = Some(midnight);
+        assert!(today.matches_at(&task, &[], &dates), "{}", zone);
+        task.completed_at = Some(midnight - chrono::Duration::seconds(1));
+        assert!(!today.matches_at(&task, &[], &dates), "{}", zone);
+        assert!(yesterday.matches_at(&task, &[], &dates), "{}", zone);
+    }
+}
+
+#[test]
+fn creation_time_comes_from_the_id() {
+    let mut task = Task::new("Book flights");
+    let created = task.created_at().unwrap();
+    assert!((Utc::now() - created).num_seconds() < 5);
+    assert!(FilterList::parse("created:today").unwrap().matches(&task));
+    assert!(!FilterList::parse("created:yesterday")
+        .unwrap()
+        .matches(&task));
+
+    let week_ago = (Utc::now() - chrono::Duration::days(7)).timestamp();
+    task.id = uuid::Uuid::new_v7(uuid::Timestamp::from_unix(
+        uuid::NoContext,
+        week_ago as u64,
+        0,
+    ));
+    assert!(!FilterList::parse("created:7d").unwrap().matches(&task));
+    assert!(FilterList::parse("created:8d").unwrap().matches(&task));
+    assert_eq!(
+        FilterList::parse("created:8d").unwrap().filters[0].to_string(),
+        "created:8d"
+    );
+
+    task.id = uuid::Uuid::nil();
+    assert_eq!(task.created_at(), None);
+    assert!(FilterList::parse("created:soon").is_err());
+}
+
+#[test]
+fn cancelled_tasks_are_not_done() {
+    let mut task = Task::new("Repaint the fence");
+    task.cancel(Some("selling it".to_string()));
+    assert!(task.completed && !task.is_done());
+    for (filter, matches) in [
+        ("done:today", false),
+        ("completed:true", false),
+        ("completed:false", false),
+        ("cancelled", true),
+    ] {
+        assert_eq!(
+            FilterList::parse(filter).unwrap().matches(&task),
+            matches,
+            "{}",
+            filter
+        );
+    }
+
+    task.set_completed(false);
+    assert!(
+        task.cancelled.is_none() && FilterList::parse("completed:false").unwrap().matches(&task)
+    );
+}
+
+#[test]
+fn planning_moves_the_day_and_keeps_the_time() {
+    let mut model = Model::new();
+    model.date_display = display("Europe/Vilnius");
+    let mut dated = Task::new("Dentist");
+    dated.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 8, 8, 30, 0).unwrap());
+    let undated = Task::new("Taxes");
+    let (dated_id, undated_id) = (dated.id, undated.id);
+    model.tasks.insert(dated_id, dated);
+    model.tasks.insert(undated_id, undated);
+
+    let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+    assert_eq!(
+        model.planning_backlog(now),
+        [vec![dated_id], vec![undated_id]],
+        "overdue and undated tasks are in the backlog"
+    );
+
+    let day = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+    for id in [dated_id, undated_id] {
+        update(
+            Msg::ScheduleTask {
+                path: vec![id],
+                day: Some(day),
+            },
+            &mut model,
+        );
+    }
+    // 10:30 and end of day local time, two hours ahead of UTC
+    assert_eq!(
+        model.tasks[&dated_id].due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 12, 8, 30, 0).unwrap())
+    );
+    assert_eq!(
+        model.tasks[&undated_id].due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 12, 21, 59, 0).unwrap())
+    );
+    assert!(model.planning_backlog(now).is_empty());
+}
+
+#[test]
+fn timeline_lists_dated_tasks_in_the_view() {
+    let mut model = Model::new();
+    let time = Utc.with_ymd_and_hms(2024, 3, 8, 8, 30, 0).unwrap();
+    let mut project = Task::new("Move house");
+    project.start_time = Some(time);
+    let mut packed = Task::new("Pack");
+    packed.due_time = Some(time);
+    packed.completed = true;
+    let undated = Task::new("Call movers");
+    let (project_id, packed_id) = (project.id, packed.id);
+    project.subtasks.insert(packed_id, packed);
+    project.subtasks.insert(undated.id, undated);
+    model.tasks.insert(project_id, project);
+
+    assert_eq!(
+        model.timeline_rows(),
+        [vec![project_id], vec![project_id, packed_id]]
+    );
+    update(Msg::ToggleShowCompleted, &mut model);
+    assert_eq!(model.timeline_rows(), [vec![project_id]]);
+}
+
+#[test]
+fn activity_counts_local_days_and_skips_cancelled_tasks() {
+    let mut model = Model::new();
+    model.date_display = display("Europe/Vilnius");
+    // 23:30 UTC is already the next day in Vilnius
+    let late = Utc.with_ymd_and_hms(2024, 3, 10, 23, 30, 0).unwrap();
+    let mut done = Task::new("Done");
+    done.completed = true;
+    done.completed_at = Some(late);
+    let mut cancelled = Task::new("Cancelled");
+    cancelled.cancel(None);
+    let done_id = done.id;
+    model.tasks.insert(done_id, done);
+    model.tasks.insert(cancelled.id, cancelled);
+
+    let completed = model.activity(ActivityMetric::Completed);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(
+        completed[&NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()],
+        [vec![done_id]]
+    );
+
+    let today = model.date_display.local_date(Utc::now());
+    assert_eq!(model.activity(ActivityMetric::Created)[&today].len(), 2);
+}
+
+#[test]
+fn due_filters_follow_the_bucket() {
+    let mut task = Task::new("Pay rent");
+    assert!(FilterList::parse("due:none").unwrap().matches(&task));
+    task.due_time = Some(Utc::now() - chrono::Duration::hours(1));
+    assert!(FilterList::parse("due:overdue").unwrap().matches(&task));
+    assert!(!FilterList::parse("due:today").unwrap().matches(&task));
+    assert!(FilterList::parse("due:soon").is_err());
+
+    // The week ends where the display's week does
+    let week = FilterList::parse("due:week").unwrap();
+    let mut dates = display("Pacific/Kiritimati");
+    let today = dates.local_date(Utc::now());
+    let tomorrow = today + chrono::Duration::days(1);
+    task.due_time = dates.from_local(tomorrow.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+    dates.week_start = today.weekday();
+    assert!(week.matches_at(&task, &[], &dates));
+    dates.week_start = tomorrow.weekday();
+    assert!(!week.matches_at(&task, &[], &dates));
+
+    for text in [
+        "due:week",
+        "done:yesterday",
+        "done:7d",
+        "waiting:ana",
+        "#home",
+    ] {
+        assert_eq!(Filter::parse(text).unwrap().to_string(), text);
+    }
+}
@@ -0,0 +1,51 @@
+//! Exports follow the navigation order of the last frame, so these tests lay
+//! it out by hand instead of rendering.
+
+use chors_core::{
+    export::{self, Format},
+    model::{Model, Task},
+};
+
+fn model() -> Model {
+    let mut model = Model::new();
+    let mut project = Task::new("Launch <beta>");
+    let mut done = Task::new("Write docs");
+    done.completed = true;
+    let mut dropped = Task::new("Print flyers");
+    dropped.cancel(None);
+    let hidden = Task::new("Not on screen");
+    let (project_id, done_id, dropped_id) = (project.id, done.id, dropped.id);
+    project.subtasks.insert(done_id, done);
+    project.subtasks.insert(dropped_id, dropped);
+    project.subtasks.insert(hidden.id, hidden);
+    model.tasks.insert(project_id, project);
+    model.nav = [
+        (project_id, vec![project_id]),
+        (done_id, vec![project_id, done_id]),
+        (dropped_id, vec![project_id, dropped_id]),
+    ]
+    .into_iter()
+    .collect();
+    model
+}
+
+#[test]
+fn markdown_keeps_the_visible_rows_and_nesting() {
+    let (document, count) = export::render(&model(), Format::Markdown);
+    assert_eq!(count, 3);
+    assert_eq!(
+        document,
+        "# default\n\n- [ ] Launch <beta>\n  - [x] Write docs\n  - [x] ~~Print flyers~~\n"
+    );
+}
+
+#[test]
+fn html_is_standalone_and_escaped() {
+    let (document, count) = export::render(&model(), Format::Html);
+    assert_eq!(count, 3);
+    assert!(document.starts_with("<!DOCTYPE html>"));
+    assert!(document.contains("<style>"));
+    assert!(document.contains("☐ Launch &lt;beta&gt;"));
+    assert!(document.contains("<li style=\"margin-left: 2em\"><span class=\"done\">☑ Write docs"));
+    assert!(!document.contains("Not on screen"));
+}
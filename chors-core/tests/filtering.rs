@@ -0,0 +1,67 @@
+//! Filtering large trees: on a worker thread, in place, or split across
+//! root subtrees.
+
+use chors_core::{
+    dates::DateDisplay,
+    generate,
+    model::{flatten_tasks, FilterList, Model, Task, View},
+    worker::{self, BACKGROUND_FILTER_THRESHOLD, PARALLEL_FILTER_ROOTS},
+};
+use chrono::{Duration, Utc};
+
+#[test]
+fn filter_now_evaluates_large_trees_in_place() {
+    let mut model = Model::new();
+    model.tasks = generate::balanced_tree(BACKGROUND_FILTER_THRESHOLD + 1, 8, 6);
+    let view = View {
+        filter_lists: vec![FilterList::parse("completed:false").unwrap()],
+        display: Default::default(),
+    };
+    model.filter_now(&view);
+    assert!(model.filter_job.is_none());
+    assert_eq!(model.match_cache.len(), model.flattened().len());
+
+    // The first draw finds the view unchanged and keeps the results
+    model.refresh_match_cache(&view);
+    assert!(model.filter_job.is_none());
+}
+
+#[test]
+fn parallel_evaluation_matches_task_by_task() {
+    let tasks = generate::balanced_tree(2_000, PARALLEL_FILTER_ROOTS * 4, 4);
+    assert!(tasks.len() >= PARALLEL_FILTER_ROOTS);
+    let view = View {
+        filter_lists: vec![FilterList::parse("#work completed:false").unwrap()],
+        display: Default::default(),
+    };
+    let matches = worker::evaluate(&tasks, &view, &DateDisplay::default());
+    let mut model = Model::new();
+    model.tasks = tasks;
+    let flat = flatten_tasks(&model.tasks);
+    assert_eq!(matches.len(), flat.len());
+    for (id, path) in flat {
+        let task = model.get_task(&path).unwrap();
+        assert_eq!(matches[&id], view.matches(task));
+    }
+}
+
+#[test]
+fn clock_criteria_are_evaluated_again_each_minute() {
+    let mut model = Model::new();
+    let mut task = Task::new("Renew the parking permit");
+    task.due_time = Some(Utc::now() + Duration::hours(1));
+    let id = task.id;
+    model.tasks.insert(id, task);
+    let view = View {
+        filter_lists: vec![FilterList::parse("due:overdue").unwrap()],
+        display: Default::default(),
+    };
+    model.refresh_match_cache(&view);
+    assert_eq!(model.match_cache.get(&id), Some(&false));
+
+    // The due time passing changes nothing about the task itself
+    model.tasks[&id].due_time = Some(Utc::now() - Duration::minutes(1));
+    model.match_cache_minute = model.match_cache_minute.map(|minute| minute - 1);
+    model.refresh_match_cache(&view);
+    assert_eq!(model.match_cache.get(&id), Some(&true));
+}
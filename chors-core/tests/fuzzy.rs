@@ -0,0 +1,44 @@
+//! Ranking of the jump overlay's fuzzy matches.
+
+use chors_core::{
+    fuzzy::score,
+    model::{Model, Task},
+};
+
+#[test]
+fn characters_must_appear_in_order() {
+    assert!(score("pmk", "Buy pumpkin").is_some());
+    assert!(score("kmp", "Buy pumpkin").is_none());
+    assert!(score("PUMP", "buy pumpkin").is_some());
+    assert_eq!(score("", "anything"), Some(-8));
+}
+
+#[test]
+fn word_starts_and_runs_rank_first() {
+    let word_starts = score("gb", "Get bread").unwrap();
+    let scattered = score("gb", "Big bag").unwrap();
+    assert!(word_starts > scattered);
+    let run = score("bread", "Bread").unwrap();
+    let spread = score("bread", "Buy red bean pudding").unwrap();
+    assert!(run > spread);
+}
+
+#[test]
+fn results_cover_the_whole_tree_best_first() {
+    let mut model = Model::new();
+    let mut trip = Task::new("Plan trip");
+    let tickets = Task::new("Book train tickets");
+    let tickets_id = tickets.id;
+    trip.subtasks.insert(tickets_id, tickets);
+    let tax = Task::new("File taxes");
+    let (trip_id, tax_id) = (trip.id, tax.id);
+    model.tasks.insert(tax_id, tax);
+    model.tasks.insert(trip_id, trip);
+
+    assert_eq!(model.jump_results("tick"), [vec![trip_id, tickets_id]]);
+    // All match at a word start, so the shortest description wins
+    assert_eq!(
+        model.jump_results("t"),
+        [vec![trip_id], vec![tax_id], vec![trip_id, tickets_id]]
+    );
+}
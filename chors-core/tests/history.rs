@@ -0,0 +1,48 @@
+//! What ends up in a task's history.
+
+use chors_core::{
+    model::{ChangeKind, Model, Msg, Task},
+    update::update,
+};
+
+fn kinds(task: &Task) -> Vec<ChangeKind> {
+    task.history.iter().map(|change| change.kind).collect()
+}
+
+#[test]
+fn completion_changes_are_recorded_once() {
+    let mut task = Task::new("Water plants");
+    task.set_completed(true);
+    task.set_completed(true);
+    task.set_completed(false);
+    task.cancel(None);
+    assert_eq!(
+        kinds(&task),
+        [
+            ChangeKind::Completed,
+            ChangeKind::Reopened,
+            ChangeKind::Cancelled
+        ]
+    );
+    assert!(task.duplicate().history.is_empty());
+}
+
+#[test]
+fn moves_are_recorded_on_the_moved_task() {
+    let mut model = Model::new();
+    let (project, chore) = (Task::new("Project"), Task::new("Chore"));
+    let (project_id, chore_id) = (project.id, chore.id);
+    model.tasks.insert(project_id, project);
+    model.tasks.insert(chore_id, chore);
+
+    update(
+        Msg::MoveTask {
+            from: vec![chore_id],
+            to: vec![project_id],
+        },
+        &mut model,
+    );
+    let moved = model.get_task(&[project_id, chore_id]).unwrap();
+    assert_eq!(kinds(moved), [ChangeKind::Moved]);
+    assert!(model.tasks[&project_id].history.is_empty());
+}
@@ -0,0 +1,84 @@
+//! Walking back and forth through the tasks left by jumps.
+
+use chors_core::{
+    model::{Model, Msg, Task},
+    update::update,
+};
+use uuid::Uuid;
+
+fn model_with(descriptions: &[&str]) -> (Model, Vec<Uuid>) {
+    let mut model = Model::new();
+    let ids = descriptions
+        .iter()
+        .map(|description| {
+            let task = Task::new(description);
+            let id = task.id;
+            model.tasks.insert(id, task);
+            id
+        })
+        .collect();
+    (model, ids)
+}
+
+#[test]
+fn back_and_forward_retrace_jumps() {
+    let (mut model, ids) = model_with(&["Water plants", "Call plumber", "Renew passport"]);
+    model.selected = Some(ids[0]);
+    update(Msg::JumpToTask(vec![ids[1]]), &mut model);
+    update(Msg::JumpToTask(vec![ids[2]]), &mut model);
+
+    update(Msg::JumpBack, &mut model);
+    assert_eq!(model.selected, Some(ids[1]));
+    update(Msg::JumpBack, &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+    update(Msg::JumpBack, &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+
+    update(Msg::JumpForward, &mut model);
+    update(Msg::JumpForward, &mut model);
+    assert_eq!(model.selected, Some(ids[2]));
+    update(Msg::JumpForward, &mut model);
+    assert_eq!(model.selected, Some(ids[2]));
+}
+
+#[test]
+fn deleted_tasks_are_skipped() {
+    let (mut model, ids) = model_with(&["Water plants", "Call plumber", "Renew passport"]);
+    model.selected = Some(ids[0]);
+    update(Msg::JumpToTask(vec![ids[1]]), &mut model);
+    update(Msg::JumpToTask(vec![ids[2]]), &mut model);
+    model.tasks.shift_remove(&ids[1]);
+
+    update(Msg::JumpBack, &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+}
+
+#[test]
+fn marks_jump_back_to_their_task() {
+    let (mut model, ids) = model_with(&["Water plants", "Call plumber"]);
+    model.selected = Some(ids[1]);
+    update(Msg::SetMark('a'), &mut model);
+    model.selected = Some(ids[0]);
+
+    update(Msg::JumpToMark('a'), &mut model);
+    assert_eq!(model.selected, Some(ids[1]));
+    // Jumping to a mark counts as a jump
+    update(Msg::JumpBack, &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+
+    update(Msg::JumpToMark('b'), &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+}
+
+#[test]
+fn marks_of_deleted_tasks_are_dropped() {
+    let (mut model, ids) = model_with(&["Water plants", "Call plumber"]);
+    model.selected = Some(ids[1]);
+    update(Msg::SetMark('a'), &mut model);
+    model.tasks.shift_remove(&ids[1]);
+    model.selected = Some(ids[0]);
+
+    update(Msg::JumpToMark('a'), &mut model);
+    assert_eq!(model.selected, Some(ids[0]));
+    assert!(model.marks.is_empty());
+}
@@ -0,0 +1,68 @@
+//! Spotting filters that can't match or add nothing, and simplifying them.
+
+use chors_core::{
+    lint::{self, Finding},
+    model::{Filter, FilterList, Model, Msg, Overlay, View},
+    update::update,
+};
+
+fn view(lists: &[&str]) -> View {
+    View {
+        filter_lists: lists
+            .iter()
+            .map(|list| FilterList::parse(list).unwrap())
+            .collect(),
+        display: Default::default(),
+    }
+}
+
+#[test]
+fn contradictions_drop_the_list() {
+    let lint = lint::lint(&view(&["completed:true waiting #home", "#work"]));
+    assert_eq!(
+        lint.findings,
+        [Finding::Contradiction {
+            list: 0,
+            a: Filter::Completed(true),
+            b: Filter::Waiting(None),
+        }]
+    );
+    assert_eq!(lint.simplified_text(), "#work");
+    assert_eq!(lint::lint(&view(&["due:today due:later"])).simplified, None);
+    assert_eq!(lint::lint(&view(&["#home not #home"])).simplified, None);
+}
+
+#[test]
+fn redundant_criteria_and_lists_are_removed() {
+    let lint = lint::lint(&view(&[
+        "#home #home waiting completed:false",
+        "#home @phone",
+        "#home",
+    ]));
+    assert_eq!(lint.findings.len(), 4);
+    assert_eq!(lint.findings[0].to_string(), "Filter 1 repeats #home");
+    assert_eq!(
+        lint.findings[1].to_string(),
+        "Filter 1: waiting already implies completed:false"
+    );
+    assert_eq!(lint.simplified_text(), "#home");
+
+    let lint = lint::lint(&view(&["#home", ""]));
+    assert_eq!(lint.findings, [Finding::AlwaysTrue { list: 1 }]);
+    assert_eq!(lint.simplified_text(), "(matches every task)");
+    assert!(lint::lint(&view(&["#home", "@phone"])).findings.is_empty());
+}
+
+#[test]
+fn adding_a_filter_reports_the_simplified_form() {
+    let mut model = Model::new();
+    model.current_view = view(&["#home"]);
+    update(Msg::SetOverlay(Overlay::AddingFilterCriterion), &mut model);
+    model.input.set_text("#home @phone");
+    update(Msg::AddFilterCriterion, &mut model);
+    let message = model.messages.log().last().unwrap();
+    assert_eq!(
+        message.text,
+        "Filter 2 is covered by filter 1. Simplified: #home"
+    );
+}
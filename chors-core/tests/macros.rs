@@ -0,0 +1,48 @@
+//! Expanding named criteria from the filters file.
+
+use chors_core::{
+    macros::FilterMacros,
+    model::{Filter, FilterList},
+};
+
+fn macros() -> FilterMacros {
+    FilterMacros::parse(
+        "
+        # Shared across views
+        actionable = completed:false due:today
+        errands = @town actionable
+        ",
+    )
+    .unwrap()
+}
+
+#[test]
+fn macros_expand_in_place_and_nest() {
+    let list = FilterList::parse_with("#home errands", &macros()).unwrap();
+    assert_eq!(
+        list.filters,
+        [
+            Filter::Tag("#home".to_string()),
+            Filter::Context("@town".to_string()),
+            Filter::Completed(false),
+            Filter::Due(chors_core::dates::DueBucket::Today),
+        ]
+    );
+}
+
+#[test]
+fn broken_definitions_are_reported() {
+    let error = FilterMacros::parse("# comment\nerrands = @town due:soon").unwrap_err();
+    assert_eq!(
+        format!("{:#}", error),
+        "line 2: In `errands`: Invalid due date range (expected overdue, today, week, later, none)"
+    );
+
+    let error = FilterMacros::parse("a = #x b\nb = a").unwrap_err();
+    assert_eq!(
+        format!("{:#}", error),
+        "line 1: In `a`: In `b`: Macro `a` refers to itself"
+    );
+    assert!(FilterMacros::parse("two words = #x").is_err());
+    assert!(FilterMacros::parse("waiting = #blocked").is_err());
+}
@@ -0,0 +1,42 @@
+//! Custom `key:value` metadata on tasks and filtering by it.
+
+use chors_core::model::{meta_field, Filter, FilterList, Task};
+
+#[test]
+fn key_value_words_become_metadata() {
+    let task = Task::new("Invoice client:Acme rate:120 at 10:30, see https://acme.test");
+    assert_eq!(task.meta.len(), 2);
+    assert_eq!(task.meta["client"], "Acme");
+    assert_eq!(task.meta["rate"], "120");
+    assert_eq!(meta_field("Re:"), None);
+    assert_eq!(meta_field("10:30"), None);
+
+    let mut task = task;
+    task.update_description("Invoice client:Globex");
+    assert_eq!(task.meta.len(), 1);
+    assert_eq!(task.meta["client"], "Globex");
+}
+
+#[test]
+fn meta_criteria_compare_values() {
+    let acme = Task::new("Invoice client:Acme");
+    let globex = Task::new("Invoice client:Globex");
+    let filters = FilterList::parse(r#"meta.client = "Acme""#).unwrap();
+    assert_eq!(filters.filters.len(), 1);
+    assert!(filters.filters[0].matches(&acme));
+    assert!(!filters.filters[0].matches(&globex));
+    assert_eq!(filters.filters[0].to_string(), "meta.client=Acme");
+
+    let any = Filter::parse("meta.client").unwrap();
+    assert!(any.matches(&acme) && any.matches(&globex));
+    assert!(!any.matches(&Task::new("Invoice")));
+    assert_eq!(
+        FilterList::parse("meta.client=Acme #work")
+            .unwrap()
+            .filters
+            .len(),
+        2
+    );
+    assert!(Filter::parse("meta.").is_err());
+    assert!(Filter::parse("meta.client=").is_err());
+}
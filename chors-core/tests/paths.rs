@@ -0,0 +1,33 @@
+//! Messages holding paths whose tasks or ancestors are gone are ignored.
+
+use chors_core::{
+    model::{Model, Msg, Task},
+    update::update,
+};
+
+#[test]
+fn paths_through_missing_tasks_are_skipped() {
+    let mut model = Model::new();
+    let task = Task::new("Water plants");
+    let id = task.id;
+    model.tasks.insert(id, task);
+    let bogus = Task::new("Gone").id;
+
+    assert!(model.get_task(&[bogus, bogus]).is_none());
+    assert!(model.get_task_mut(&[bogus, id]).is_none());
+    update(
+        Msg::AddTasksBulk(vec![(vec![bogus, bogus], Task::new("Orphan"))]),
+        &mut model,
+    );
+    update(Msg::DuplicateTask(vec![bogus, id]), &mut model);
+    update(
+        Msg::MoveTask {
+            from: vec![bogus, id],
+            to: Vec::new(),
+        },
+        &mut model,
+    );
+    update(Msg::RemoveTask(vec![bogus, id]), &mut model);
+    assert_eq!(model.tasks.len(), 1);
+    assert!(model.get_task(&[id]).is_some());
+}
@@ -0,0 +1,313 @@
+//! Property tests for tree operations and filter parsing on randomly
+//! generated task trees.
+
+use chors_core::{
+    diff::diff,
+    events::Event,
+    model::{flatten_tasks, CompletionPolicy, Filter, FilterList, Model, Msg, Task},
+    update::update,
+};
+use indexmap::IndexMap;
+use proptest::prelude::*;
+use uuid::Uuid;
+
+/// Shape of a generated task: description words, completion and children.
+#[derive(Debug, Clone)]
+struct Spec {
+    words: Vec<&'static str>,
+    completed: bool,
+    children: Vec<Spec>,
+}
+
+const WORDS: [&str; 8] = ["buy", "call", "fix", "#a", "#b", "@x", "@y", "later"];
+
+fn spec() -> impl Strategy<Value = Spec> {
+    let leaf = (
+        prop::collection::vec(prop::sample::select(&WORDS[..]), 1..4),
+        any::<bool>(),
+    )
+        .prop_map(|(words, completed)| Spec {
+            words,
+            completed,
+            children: Vec::new(),
+        });
+    leaf.prop_recursive(4, 40, 4, |inner| {
+        (
+            prop::collection::vec(prop::sample::select(&WORDS[..]), 1..4),
+            any::<bool>(),
+            prop::collection::vec(inner, 1..4),
+        )
+            .prop_map(|(words, completed, children)| Spec {
+                words,
+                completed,
+                children,
+            })
+    })
+}
+
+fn build(specs: &[Spec]) -> IndexMap<Uuid, Task> {
+    specs
+        .iter()
+        .map(|spec| {
+            let mut task = Task::new(&spec.words.join(" "));
+            task.completed = spec.completed;
+            task.subtasks = build(&spec.children);
+            (task.id, task)
+        })
+        .collect()
+}
+
+/// Makes every parent's completion agree with its subtasks, the state the
+/// app maintains.
+fn normalize(tasks: &mut IndexMap<Uuid, Task>) {
+    for task in tasks.values_mut() {
+        if !task.subtasks.is_empty() {
+            normalize(&mut task.subtasks);
+            task.completed = task.subtasks.values().all(|subtask| subtask.completed);
+        }
+    }
+}
+
+fn consistent(tasks: &IndexMap<Uuid, Task>) -> bool {
+    tasks.values().all(|task| {
+        consistent(&task.subtasks)
+            && (task.subtasks.is_empty()
+                || task.completed == task.subtasks.values().all(|subtask| subtask.completed))
+    })
+}
+
+fn model_with(tasks: IndexMap<Uuid, Task>) -> Model {
+    let mut model = Model::new();
+    model.tasks = tasks;
+    // The view builds `nav` while rendering; without one, expose every task.
+    model.nav = flatten_tasks(&model.tasks).into_iter().collect();
+    model
+}
+
+/// Structure and content of a tree, independent of `HashSet` ordering.
+fn fingerprint(tasks: &IndexMap<Uuid, Task>) -> Vec<(Vec<Uuid>, String, bool)> {
+    flatten_tasks(tasks)
+        .into_iter()
+        .map(|(_, path)| {
+            let mut current = tasks;
+            for id in &path[..path.len() - 1] {
+                current = &current[id].subtasks;
+            }
+            let task = &current[path.last().unwrap()];
+            (path, task.description.clone(), task.completed)
+        })
+        .collect()
+}
+
+fn tree() -> impl Strategy<Value = IndexMap<Uuid, Task>> {
+    prop::collection::vec(spec(), 1..5).prop_map(|specs| build(&specs))
+}
+
+proptest! {
+    #[test]
+    fn flatten_visits_every_task_once(tasks in tree()) {
+        let flat = flatten_tasks(&tasks);
+        let count: usize = tasks.values().map(|task| task.descendant_count() + 1).sum();
+        prop_assert_eq!(flat.len(), count);
+        let mut ids: Vec<Uuid> = flat.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        ids.dedup();
+        prop_assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn path_of_finds_tasks_before_and_after_a_move(
+        tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+        target in any::<prop::sample::Index>(),
+    ) {
+        let flat = flatten_tasks(&tasks);
+        let mut model = model_with(tasks);
+        for (id, path) in &flat {
+            prop_assert_eq!(model.path_of(*id), Some(path.clone()));
+        }
+
+        let from = flat[pick.index(flat.len())].1.clone();
+        let to = flat[target.index(flat.len())].1.clone();
+        prop_assume!(!to.starts_with(&from));
+        let id = *from.last().unwrap();
+        update(Msg::MoveTask { from, to: to.clone() }, &mut model);
+        let mut moved = to;
+        moved.push(id);
+        prop_assert_eq!(model.path_of(id), Some(moved));
+        prop_assert_eq!(model.path_of(Uuid::nil()), None);
+    }
+
+    #[test]
+    fn diff_reports_only_what_a_move_touched(
+        tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+        target in any::<prop::sample::Index>(),
+    ) {
+        let before = tasks.clone();
+        prop_assert!(diff(&before, &tasks).is_empty());
+        let flat = flatten_tasks(&tasks);
+        let mut model = model_with(tasks);
+        let from = flat[pick.index(flat.len())].1.clone();
+        let to = flat[target.index(flat.len())].1.clone();
+        prop_assume!(!to.starts_with(&from) && to != from[..from.len() - 1]);
+        let id = *from.last().unwrap();
+        // Besides the moved task only parents can change, through completion
+        let mut parents: Vec<Uuid> = flat
+            .iter()
+            .filter_map(|(_, path)| path.len().checked_sub(2).map(|parent| path[parent]))
+            .collect();
+        parents.extend(to.last());
+        update(Msg::MoveTask { from, to }, &mut model);
+
+        let changes = diff(&before, &model.tasks);
+        prop_assert!(changes.added.is_empty() && changes.removed.is_empty());
+        prop_assert!(changes.changed.contains(&id));
+        for changed in &changes.changed {
+            prop_assert!(*changed == id || parents.contains(changed));
+        }
+    }
+
+    #[test]
+    fn bulk_insert_under_a_parent_keeps_completion_consistent(
+        mut tasks in tree(),
+        batch in tree(),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        normalize(&mut tasks);
+        let mut batch = batch;
+        normalize(&mut batch);
+        let flat = flatten_tasks(&tasks);
+        let parent = flat[pick.index(flat.len())].1.clone();
+        let mut model = model_with(tasks);
+        let roots: Vec<Uuid> = batch.keys().copied().collect();
+        let msg = Msg::AddTasksBulk(batch.into_values().map(|task| (parent.clone(), task)).collect());
+        update(msg, &mut model);
+
+        let siblings = &model.get_task(&parent).unwrap().subtasks;
+        prop_assert!(siblings.keys().rev().take(roots.len()).eq(roots.iter().rev()));
+        prop_assert!(consistent(&model.tasks));
+        // Parents the batch reopens are reported on top of the added tasks
+        let added = model
+            .take_events()
+            .into_iter()
+            .filter(|event| matches!(event, Event::TaskAdded(_)))
+            .count();
+        prop_assert_eq!(added, roots.len());
+    }
+
+    #[test]
+    fn remove_then_insert_round_trips(tasks in tree(), pick in any::<prop::sample::Index>()) {
+        let before = fingerprint(&tasks);
+        let mut model = model_with(tasks);
+        let path = before[pick.index(before.len())].0.clone();
+        let id = *path.last().unwrap();
+
+        let siblings = model.get_task_list_mut(&path);
+        let index = siblings.get_index_of(&id).unwrap();
+        let task = siblings.shift_remove(&id).unwrap();
+        siblings.shift_insert(index, id, task);
+
+        prop_assert_eq!(fingerprint(&model.tasks), before);
+    }
+
+    #[test]
+    fn toggling_keeps_parent_completion_consistent(
+        mut tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        normalize(&mut tasks);
+        let mut model = model_with(tasks);
+        let (id, _) = model.nav.get_index(pick.index(model.nav.len())).unwrap();
+        model.selected = Some(*id);
+
+        update(Msg::ToggleTaskCompletion, &mut model);
+        prop_assert!(consistent(&model.tasks));
+    }
+
+    #[test]
+    fn manual_completion_leaves_the_rest_of_the_tree_alone(
+        tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        let mut model = model_with(tasks);
+        model.completion_policy = CompletionPolicy::Manual;
+        let (id, path) = model.nav.get_index(pick.index(model.nav.len())).unwrap();
+        let (id, path) = (*id, path.clone());
+        model.selected = Some(id);
+        let outside = |model: &Model| -> Vec<_> {
+            fingerprint(&model.tasks)
+                .into_iter()
+                .filter(|(other, _, _)| !other.starts_with(&path))
+                .collect()
+        };
+        let before = outside(&model);
+
+        update(Msg::ToggleTaskCompletion, &mut model);
+        prop_assert_eq!(outside(&model), before);
+    }
+
+    #[test]
+    fn removing_keeps_parent_completion_consistent(
+        mut tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        normalize(&mut tasks);
+        let mut model = model_with(tasks);
+        let (_, path) = model.nav.get_index(pick.index(model.nav.len())).unwrap();
+        let path = path.clone();
+        let siblings_left = model.get_task_list_mut(&path).len() > 1;
+
+        update(Msg::RemoveTask(path.clone()), &mut model);
+        prop_assert!(model.get_task(&path).is_none());
+        // Removing an only child leaves a parent without subtasks, whose
+        // completion is its own again; anything else must stay consistent.
+        if siblings_left || path.len() == 1 {
+            prop_assert!(consistent(&model.tasks));
+        }
+    }
+
+    #[test]
+    fn duplicate_copies_the_subtree_with_fresh_ids(
+        tasks in tree(),
+        pick in any::<prop::sample::Index>(),
+    ) {
+        let mut model = model_with(tasks);
+        let (_, path) = model.nav.get_index(pick.index(model.nav.len())).unwrap();
+        let path = path.clone();
+        let original = model.get_task(&path).unwrap().descendant_count();
+        let before = flatten_tasks(&model.tasks).len();
+
+        update(Msg::DuplicateTask(path.clone()), &mut model);
+        let mut copy_path = path;
+        *copy_path.last_mut().unwrap() = model.selected.unwrap();
+        prop_assert_eq!(model.get_task(&copy_path).unwrap().descendant_count(), original);
+
+        let after = flatten_tasks(&model.tasks);
+        prop_assert_eq!(after.len(), before + original + 1);
+        let mut ids: Vec<Uuid> = after.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        ids.dedup();
+        prop_assert_eq!(ids.len(), after.len());
+    }
+
+    #[test]
+    fn filter_parse_errors_point_into_the_input(input in "[a-z:#@ ]{0,24}") {
+        if let Err(error) = FilterList::parse(&input) {
+            prop_assert!(error.offset <= input.len());
+            prop_assert!(input.is_char_boundary(error.offset));
+        }
+    }
+
+    #[test]
+    fn filter_shorthands_are_equivalent(name in "[a-z]{1,8}") {
+        prop_assert_eq!(
+            Filter::parse(&format!("tag:{}", name)).unwrap(),
+            Filter::parse(&format!("#{}", name)).unwrap()
+        );
+        prop_assert_eq!(
+            Filter::parse(&format!("context:{}", name)).unwrap(),
+            Filter::parse(&format!("@{}", name)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Rolling over open tasks due on past days, and undoing it.
+
+use chors_core::{
+    dates::{self, DateDisplay},
+    model::{Model, Msg, Overlay, Task},
+    rollover::{self, Rollover},
+    update::update,
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// A model in UTC with a task due two days before `now` at 09:30, one due
+/// later today and one done, in that order.
+fn model(now: DateTime<Utc>) -> Model {
+    let mut model = Model::new();
+    model.date_display = DateDisplay {
+        zone: Some(dates::parse_zone("UTC").unwrap()),
+        ..DateDisplay::default()
+    };
+    for (description, due, completed) in [
+        (
+            "File taxes",
+            now - chrono::Duration::hours(48) - chrono::Duration::minutes(30),
+            false,
+        ),
+        ("Call dentist", now + chrono::Duration::hours(1), false),
+        ("Pay rent", now - chrono::Duration::days(3), true),
+    ] {
+        let mut task = Task::new(description);
+        task.due_time = Some(due);
+        task.completed = completed;
+        model.tasks.insert(task.id, task);
+    }
+    model
+}
+
+fn task<'a>(model: &'a Model, description: &str) -> &'a Task {
+    let mut tasks = model.tasks.values();
+    tasks
+        .find(|task| task.description.starts_with(description))
+        .unwrap()
+}
+
+#[test]
+fn moving_keeps_the_time_of_day() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 10, 0, 0).unwrap();
+    let mut model = model(now);
+    let report = rollover::run(&mut model, Rollover::Move, now);
+    assert_eq!(report.tasks.len(), 1);
+    assert_eq!(
+        task(&model, "File taxes").due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 8, 9, 30, 0).unwrap())
+    );
+    assert!(task(&model, "Pay rent").due_time < Some(now));
+
+    rollover::undo(&mut model, &report);
+    assert_eq!(
+        task(&model, "File taxes").due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 6, 9, 30, 0).unwrap())
+    );
+}
+
+#[test]
+fn flagging_tags_once_and_undoes() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 10, 0, 0).unwrap();
+    let mut model = model(now);
+    let report = rollover::run(&mut model, Rollover::Flag, now);
+    assert_eq!(
+        task(&model, "File taxes").description,
+        "File taxes #overdue"
+    );
+    assert!(task(&model, "File taxes").tags.contains("#overdue"));
+    assert!(rollover::run(&mut model, Rollover::Flag, now)
+        .tasks
+        .is_empty());
+
+    rollover::undo(&mut model, &report);
+    assert_eq!(task(&model, "File taxes").description, "File taxes");
+    assert!(task(&model, "File taxes").tags.is_empty());
+}
+
+#[test]
+fn the_overlay_offers_to_undo() {
+    let mut model = model(Utc::now());
+    update(Msg::RunCommand("rollover flag".to_string()), &mut model);
+    assert!(matches!(model.overlay, Overlay::Rollover));
+    update(Msg::UndoRollover, &mut model);
+    assert!(matches!(model.overlay, Overlay::None));
+    assert_eq!(model.last_rollover, None);
+    assert_eq!(task(&model, "File taxes").description, "File taxes");
+
+    update(
+        Msg::RunCommand("rollover start flag".to_string()),
+        &mut model,
+    );
+    assert_eq!(model.rollover_on_start, Some(Rollover::Flag));
+}
@@ -0,0 +1,79 @@
+//! Reopening a saved model puts the list back where it was left.
+
+use chors_core::{
+    model::{Model, Task},
+    persistence,
+};
+use std::{env, fs};
+use uuid::Uuid;
+
+#[test]
+fn selection_scroll_and_zoom_survive_a_restart() {
+    let mut model = Model::new();
+    let mut project = Task::new("Project");
+    let step = Task::new("Step");
+    let (project_id, step_id) = (project.id, step.id);
+    project.subtasks.insert(step_id, step);
+    model.tasks.insert(project_id, project);
+    model.selected = Some(step_id);
+    model.list_state.select(Some(3));
+    model.list_state.set_offset(2);
+    model.zoom = vec![project_id];
+    model.marks.insert('a', step_id);
+
+    let file = env::temp_dir().join(format!("chors-session-{}.json", Uuid::now_v7()));
+    let file = file.to_str().unwrap();
+    persistence::save(file, &model, None).unwrap();
+    let restored = persistence::load(file).unwrap();
+    assert_eq!(restored.selected, Some(step_id));
+    assert_eq!(restored.list_state, model.list_state);
+    assert_eq!(restored.zoom, [project_id]);
+    assert_eq!(restored.marks.get(&'a'), Some(&step_id));
+
+    // A zoom into a task removed in the meantime is dropped
+    model.tasks.shift_remove(&project_id);
+    persistence::save(file, &model, None).unwrap();
+    assert!(persistence::load(file).unwrap().zoom.is_empty());
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn a_failed_save_leaves_the_original_and_no_temp_file() {
+    let dir = env::temp_dir().join(format!("chors-save-{}", Uuid::now_v7()));
+    fs::create_dir(&dir).unwrap();
+    let file = dir.join("tasks.json");
+    let file = file.to_str().unwrap();
+    let mut model = Model::new();
+    let task = Task::new("Kept");
+    model.tasks.insert(task.id, task);
+    persistence::save(file, &model, None).unwrap();
+
+    let missing = dir.join("missing").join("tasks.json");
+    assert!(persistence::save(missing.to_str().unwrap(), &model, None).is_err());
+    let entries: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, ["tasks.json"]);
+    assert_eq!(persistence::load(file).unwrap().tasks.len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn plaintext_files_opened_with_a_passphrase_save_encrypted() {
+    let mut model = Model::new();
+    let task = Task::new("Quarterly report");
+    model.tasks.insert(task.id, task);
+    let file = env::temp_dir().join(format!("chors-encrypt-{}.json", Uuid::now_v7()));
+    let file = file.to_str().unwrap();
+    persistence::save(file, &model, None).unwrap();
+
+    let opened = persistence::load_with_progress(file, Some("hunter2"), |_, _| {}).unwrap();
+    assert_eq!(opened.tasks.len(), 1);
+    persistence::save(file, &opened, Some("hunter2")).unwrap();
+    assert!(persistence::is_encrypted(file).unwrap());
+    let reloaded = persistence::load_with_progress(file, Some("hunter2"), |_, _| {}).unwrap();
+    assert_eq!(reloaded.tasks.len(), 1);
+    assert!(persistence::load(file).is_err());
+    fs::remove_file(file).unwrap();
+}
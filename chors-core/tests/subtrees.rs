@@ -0,0 +1,77 @@
+//! `under:` criteria, which match by a task's place in the tree, and `not`.
+
+use chors_core::{
+    model::{Filter, FilterList, Model, Task},
+    resolve,
+};
+
+/// "Project X" with a step, and a task outside it.
+fn model() -> (Model, [uuid::Uuid; 3]) {
+    let mut model = Model::new();
+    let mut project = Task::new("Project X");
+    let step = Task::new("Draft plan");
+    let other = Task::new("Water plants");
+    let ids = [project.id, step.id, other.id];
+    project.subtasks.insert(step.id, step);
+    model.tasks.insert(ids[0], project);
+    model.tasks.insert(ids[2], other);
+    (model, ids)
+}
+
+fn matching(model: &Model, list: &FilterList) -> Vec<String> {
+    let view = chors_core::View {
+        filter_lists: vec![list.clone()],
+        display: Default::default(),
+    };
+    chors_core::worker::evaluate(&model.tasks, &view, &model.date_display)
+        .into_iter()
+        .filter(|(_, matches)| *matches)
+        .map(|(id, _)| {
+            let path = resolve::resolve(
+                &chors_core::model::flatten_tasks(&model.tasks),
+                &id.to_string(),
+            )
+            .unwrap();
+            model.get_task(&path).unwrap().description.clone()
+        })
+        .collect()
+}
+
+#[test]
+fn under_matches_the_subtree() {
+    let (model, ids) = model();
+    let list = model.parse_filter("under:\"project x\"").unwrap();
+    assert_eq!(
+        list.filters,
+        [Filter::Under {
+            target: "project x".to_string(),
+            id: Some(ids[0]),
+        }]
+    );
+    let mut found = matching(&model, &list);
+    found.sort();
+    assert_eq!(found, ["Draft plan", "Project X"]);
+
+    let short_id = &ids[0].simple().to_string()[24..];
+    let list = model
+        .parse_filter(&format!("not under:{}", short_id))
+        .unwrap();
+    assert_eq!(matching(&model, &list), ["Water plants"]);
+    assert_eq!(
+        list.filters[0].to_string(),
+        format!("not under:{}", short_id)
+    );
+}
+
+#[test]
+fn unknown_targets_and_dangling_not_are_errors() {
+    let (model, _) = model();
+    let error = model.parse_filter("#home under:nothing").unwrap_err();
+    assert_eq!((error.offset, error.len), (12, 7));
+    assert!(FilterList::parse("#home not").is_err());
+    assert!(FilterList::parse("under:\"Project X").is_err());
+    assert_eq!(
+        FilterList::parse("not #home").unwrap().filters,
+        [Filter::Not(Box::new(Filter::Tag("#home".to_string())))]
+    );
+}
@@ -0,0 +1,49 @@
+//! The end-of-day summary of today's and tomorrow's tasks.
+
+use chors_core::{
+    dates::{self, DateDisplay},
+    model::{Model, Msg, Overlay, Task},
+    summary,
+    update::update,
+};
+use chrono::{TimeZone, Utc};
+
+#[test]
+fn summarizes_today_and_tomorrow() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 18, 0, 0).unwrap();
+    let mut model = Model::new();
+    model.date_display = DateDisplay {
+        zone: Some(dates::parse_zone("UTC").unwrap()),
+        ..DateDisplay::default()
+    };
+    let mut done = Task::new("Write report");
+    done.completed = true;
+    done.completed_at = Some(now - chrono::Duration::hours(2));
+    let mut stale = Task::new("Old chore");
+    stale.completed = true;
+    stale.completed_at = Some(now - chrono::Duration::days(2));
+    let mut late = Task::new("Call dentist");
+    late.due_time = Some(now + chrono::Duration::hours(20));
+    let mut early = Task::new("Standup");
+    early.due_time = Some(now + chrono::Duration::hours(15));
+    let mut later = Task::new("Pay rent");
+    later.due_time = Some(now + chrono::Duration::days(3));
+    for task in [done, stale, late, early, later] {
+        model.tasks.insert(task.id, task);
+    }
+
+    let summary = summary::summarize(&model, now);
+    assert_eq!(summary.completed, ["Write report"]);
+    assert_eq!(summary.due_tomorrow, ["Standup", "Call dentist"]);
+    // The tasks were created just now, not on the summarized day
+    assert!(summary.added.is_empty());
+    assert_eq!(
+        summary.markdown(&model.date_display),
+        "# Summary of 2024-03-08\n\n\
+         ## Completed (1)\n\n- Write report\n\n\
+         ## Due tomorrow (2)\n\n- Standup\n- Call dentist\n"
+    );
+
+    update(Msg::RunCommand("summary".to_string()), &mut model);
+    assert!(matches!(model.overlay, Overlay::Summary));
+}
@@ -0,0 +1,65 @@
+//! Renaming, merging and deleting tags across the tree.
+
+use chors_core::model::{Filter, FilterList, Model, Task, View};
+
+fn model_with(descriptions: &[&str]) -> Model {
+    let mut model = Model::new();
+    let mut parent = Task::new("Groceries #errand");
+    for description in descriptions {
+        let task = Task::new(description);
+        parent.subtasks.insert(task.id, task);
+    }
+    model.tasks.insert(parent.id, parent);
+    model
+}
+
+fn descriptions(model: &Model) -> Vec<String> {
+    let parent = model.tasks.values().next().unwrap();
+    std::iter::once(parent)
+        .chain(parent.subtasks.values())
+        .map(|task| task.description.clone())
+        .collect()
+}
+
+#[test]
+fn renaming_rewrites_descriptions_and_views() {
+    let mut model = model_with(&["Buy #errand milk", "Call mom"]);
+    model.current_view = View {
+        filter_lists: vec![FilterList::parse("#errand").unwrap()],
+        ..View::default()
+    };
+
+    assert_eq!(model.rewrite_tag("#errand", Some("#shopping")), 2);
+    assert_eq!(
+        descriptions(&model),
+        ["Groceries #shopping", "Buy #shopping milk", "Call mom"]
+    );
+    let child = model
+        .tasks
+        .values()
+        .next()
+        .unwrap()
+        .subtasks
+        .values()
+        .next()
+        .unwrap();
+    assert!(child.tags.contains("#shopping") && !child.tags.contains("#errand"));
+    assert_eq!(
+        model.current_view.filter_lists[0].filters,
+        [Filter::Tag("#shopping".to_string())]
+    );
+}
+
+#[test]
+fn merging_keeps_one_copy_and_deleting_removes_the_word() {
+    let mut model = model_with(&["Milk #errand #shopping", "Eggs  #errand"]);
+
+    model.rewrite_tag("#errand", Some("#shopping"));
+    assert_eq!(
+        descriptions(&model),
+        ["Groceries #shopping", "Milk #shopping", "Eggs  #shopping"]
+    );
+
+    assert_eq!(model.rewrite_tag("#shopping", None), 3);
+    assert_eq!(descriptions(&model), ["Groceries", "Milk", "Eggs"]);
+}
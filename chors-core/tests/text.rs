@@ -0,0 +1,32 @@
+//! Text criteria, exact or ignoring case and accents.
+
+use chors_core::{
+    model::{Filter, FilterList, Model, Msg, Task},
+    update::update,
+};
+
+#[test]
+fn folding_ignores_case_and_accents() {
+    let task = Task::new("Réserver le Café");
+    let matches = |input: &str| FilterList::parse(input).unwrap().matches(&task);
+    assert!(matches("text:Café"));
+    assert!(!matches("text:cafe"));
+    assert!(matches("text:cafe/i"));
+    assert!(matches("text:RESERVER/i"));
+    assert_eq!(
+        Filter::parse("text:CAFÉ/i").unwrap().to_string(),
+        "text:cafe/i"
+    );
+    assert!(FilterList::parse("text:").is_err());
+}
+
+#[test]
+fn the_global_setting_folds_every_text_criterion() {
+    let mut model = Model::new();
+    let task = Task::new("Quarterly Report");
+    assert!(!model.parse_filter("text:report").unwrap().matches(&task));
+
+    update(Msg::RunCommand("text-match fold".to_string()), &mut model);
+    assert!(model.fold_text_filters);
+    assert!(model.parse_filter("text:report").unwrap().matches(&task));
+}
@@ -0,0 +1,166 @@
+//! Reading tasks from the checkboxes of Markdown notes and writing them back.
+
+use chors_core::{
+    dates::{self, DateDisplay},
+    model::{Comment, Priority, Task},
+    vault::{self, Vault},
+};
+use chrono::{TimeZone, Utc};
+use std::{fs, path::PathBuf};
+
+fn utc() -> DateDisplay {
+    DateDisplay {
+        zone: Some(dates::parse_zone("UTC").unwrap()),
+        ..DateDisplay::default()
+    }
+}
+
+fn vault_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("chors-vault-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("Projects")).unwrap();
+    fs::create_dir_all(dir.join(".obsidian")).unwrap();
+    fs::write(
+        dir.join("Projects").join("Garden.md"),
+        "# Garden\n\nSome notes.\n\n- [ ] Plant tomatoes #spring\n\t- [x] Buy seeds\n- [ ] Build a fence\n\nMore notes.\n",
+    )
+    .unwrap();
+    fs::write(dir.join("Ideas.md"), "No tasks here.\n").unwrap();
+    fs::write(dir.join(".obsidian").join("tasks.md"), "- [ ] Hidden\n").unwrap();
+    dir
+}
+
+#[test]
+fn notes_with_checkboxes_become_root_tasks() {
+    let dir = vault_dir("load");
+    let (_, tasks) = Vault::load(&dir, &utc()).unwrap();
+    assert_eq!(tasks.len(), 1);
+    let garden = &tasks[0];
+    assert_eq!(garden.description, "Projects/Garden");
+    let plant = &garden.subtasks[0];
+    assert_eq!(plant.description, "Plant tomatoes #spring");
+    assert!(plant.tags.contains("#spring"));
+    assert!(plant.subtasks[0].completed);
+    assert_eq!(garden.subtasks[1].description, "Build a fence");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saving_edits_checkboxes_in_place() {
+    let dir = vault_dir("save");
+    let (mut vault, mut tasks) = Vault::load(&dir, &utc()).unwrap();
+    let garden = &mut tasks[0];
+    garden.subtasks[0].set_completed(true);
+    let water = Task::new("Water daily");
+    garden.subtasks[0].subtasks.insert(water.id, water);
+    garden.subtasks.shift_remove_index(1);
+    let shed = Task::new("Paint the shed");
+    garden.subtasks.insert(shed.id, shed);
+    let mut chores = Task::new("Chores");
+    let dishes = Task::new("Dishes");
+    chores.subtasks.insert(dishes.id, dishes);
+    tasks.insert(chores.id, chores);
+
+    assert_eq!(vault.save(&tasks).unwrap(), 2);
+    let done = tasks[0].subtasks[0].completed_at.unwrap().date_naive();
+    assert_eq!(
+        fs::read_to_string(dir.join("Projects").join("Garden.md")).unwrap(),
+        format!("# Garden\n\nSome notes.\n\n- [x] Plant tomatoes #spring ✅ {}\n\t- [x] Buy seeds\n\t- [ ] Water daily\n- [ ] Paint the shed\n\nMore notes.\n", done)
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("Chores.md")).unwrap(),
+        "- [ ] Dishes\n"
+    );
+    assert_eq!(vault.save(&tasks).unwrap(), 0);
+
+    // Notes edited elsewhere are left alone
+    fs::write(dir.join("Chores.md"), "- [ ] Laundry\n").unwrap();
+    tasks[1].subtasks[0].set_completed(true);
+    assert!(vault.save(&tasks).is_err());
+    assert_eq!(
+        fs::read_to_string(dir.join("Chores.md")).unwrap(),
+        "- [ ] Laundry\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn dates_and_priorities_are_kept_in_the_line() {
+    let dir = vault_dir("fields");
+    fs::write(
+        dir.join("Taxes.md"),
+        "- [ ] File taxes ⏫ 🛫 2024-03-01 📅 2024-03-15\n- [x] Renew passport ✅ 2024-02-20\n- [ ] Pay rent 🔁 every month 📅 2024-04-01\n",
+    )
+    .unwrap();
+    let (mut vault, mut tasks) = Vault::load(&dir, &utc()).unwrap();
+    let taxes = &mut tasks[1];
+    assert_eq!(taxes.description, "Taxes");
+    let file = &taxes.subtasks[0];
+    assert_eq!(file.description, "File taxes");
+    assert_eq!(file.priority, Some(Priority::High));
+    assert_eq!(
+        file.start_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        file.due_time,
+        Some(Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        taxes.subtasks[1].completed_at,
+        Some(Utc.with_ymd_and_hms(2024, 2, 20, 0, 0, 0).unwrap())
+    );
+    assert_eq!(taxes.subtasks[2].description, "Pay rent 🔁 every month");
+
+    // Unchanged tasks are written back as they were
+    assert_eq!(vault.save(&tasks).unwrap(), 0);
+    let file = &mut tasks[1].subtasks[0];
+    file.priority = Some(Priority::Low);
+    file.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 20, 9, 0, 0).unwrap());
+    assert_eq!(vault.save(&tasks).unwrap(), 1);
+    assert!(fs::read_to_string(dir.join("Taxes.md"))
+        .unwrap()
+        .starts_with("- [ ] File taxes 🔽 🛫 2024-03-01 📅 2024-03-20\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn notes_changed_elsewhere_can_be_read_again() {
+    let dir = vault_dir("reload");
+    let (mut vault, mut tasks) = Vault::load(&dir, &utc()).unwrap();
+    let garden = dir.join("Projects").join("Garden.md");
+    fs::write(&garden, "- [ ] Mow the lawn\n").unwrap();
+    tasks[0].subtasks[1].set_completed(true);
+    assert!(vault.save(&tasks).is_err());
+
+    let reloaded = vault.reload_changed().unwrap();
+    assert_eq!(reloaded.len(), 1);
+    let (id, subtasks) = reloaded.into_iter().next().unwrap();
+    assert_eq!(id, tasks[0].id);
+    tasks[0].subtasks = subtasks;
+    assert_eq!(tasks[0].subtasks[0].description, "Mow the lawn");
+    tasks[0].subtasks[0].set_completed(true);
+    assert_eq!(vault.save(&tasks).unwrap(), 1);
+    let done = tasks[0].subtasks[0].completed_at.unwrap().date_naive();
+    assert_eq!(
+        fs::read_to_string(&garden).unwrap(),
+        format!("- [x] Mow the lawn ✅ {}\n", done)
+    );
+    assert!(vault.reload_changed().unwrap().is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fields_a_line_cant_hold_are_counted() {
+    let dir = vault_dir("unkept");
+    let (_, mut tasks) = Vault::load(&dir, &utc()).unwrap();
+    assert_eq!(vault::unkept(&tasks), 0);
+    tasks[0].subtasks[0].notes = "Rows of four".to_string();
+    tasks[0].subtasks[0].subtasks[0].comments.push(Comment {
+        at: Utc::now(),
+        author: None,
+        text: "Heirloom ones".to_string(),
+    });
+    assert_eq!(vault::unkept(&tasks), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,129 @@
+//! User-defined shorthands for command lines, read from
+//! `$XDG_CONFIG_HOME/chors/aliases` (or the platform equivalent):
+//!
+//! ```text
+//! # Blank lines and comments are ignored
+//! alias today = list --filter "due:today completed:false"
+//! shopping = print --filter #groceries --width 40
+//! ```
+//!
+//! `chors today` then runs as `chors list --filter "due:today completed:false"`,
+//! with any further arguments appended. Built-in subcommands always win over
+//! an alias of the same name.
+
+use crate::cli;
+use color_eyre::{eyre::bail, Result};
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+pub type Aliases = HashMap<String, Vec<String>>;
+
+pub fn default_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chors").join("aliases"))
+}
+
+/// Reads the aliases at `path`; a missing file means no aliases.
+pub fn load(path: &PathBuf) -> Result<Aliases> {
+    match fs::read_to_string(path) {
+        Ok(source) => {
+            parse(&source).map_err(|error| error.wrap_err(format!("in {}", path.to_string_lossy())))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Aliases::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn parse(source: &str) -> Result<Aliases> {
+    let mut aliases = Aliases::new();
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("alias ").unwrap_or(line);
+        let Some((name, expansion)) = line.split_once('=') else {
+            bail!("line {}: expected `name = command`", number + 1);
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            bail!("line {}: alias names are a single word", number + 1);
+        }
+        let words =
+            split(expansion).map_err(|error| error.wrap_err(format!("line {}", number + 1)))?;
+        if words.is_empty() {
+            bail!("line {}: `{}` expands to nothing", number + 1, name);
+        }
+        aliases.insert(name.to_string(), words);
+    }
+    Ok(aliases)
+}
+
+/// Splits a command line into words the way a shell would for plain and
+/// quoted words, without any expansion.
+fn split(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some(open), ch) if ch == open => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (Some(_), ch) => word.get_or_insert_with(String::new).push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                word.get_or_insert_with(String::new);
+            }
+            (None, ch) if ch.is_whitespace() => words.extend(word.take()),
+            (None, ch) => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    if quote.is_some() {
+        bail!("unterminated quote");
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Replaces an alias in the subcommand position of `args` (including the
+/// program name) with its expansion.
+pub fn expand(args: Vec<String>, aliases: &Aliases) -> Vec<String> {
+    let command = cli::build_cli();
+    // Options before the subcommand whose values shouldn't be mistaken for it
+    let takes_value: Vec<String> = command
+        .get_arguments()
+        .filter(|arg| arg.get_action().takes_values())
+        .flat_map(|arg| {
+            let short = arg.get_short().map(|short| format!("-{}", short));
+            let long = arg.get_long().map(|long| format!("--{}", long));
+            short.into_iter().chain(long)
+        })
+        .collect();
+
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        if takes_value.contains(arg) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            break;
+        }
+    }
+    let Some(name) = args.get(index) else {
+        return args;
+    };
+    let builtin = name == "help" || command.get_subcommands().any(|sub| sub.get_name() == name);
+    match aliases.get(name) {
+        Some(expansion) if !builtin => {
+            let mut expanded = args[..index].to_vec();
+            expanded.extend(expansion.iter().cloned());
+            expanded.extend(args[index + 1..].iter().cloned());
+            expanded
+        }
+        _ => args,
+    }
+}
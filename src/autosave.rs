@@ -0,0 +1,139 @@
+use chors_core::{
+    events::{Event, Subscriber},
+    model::{Model, Msg},
+    persistence,
+    status::Severity,
+    update::update,
+    vault::{self, Vault},
+};
+use std::time::{Duration, Instant};
+
+/// How long to wait after the first unsaved change before writing, so bursts
+/// of edits (or a slow passphrase derivation) cost a single save.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// Where the tasks are written.
+enum Target {
+    File {
+        path: String,
+        passphrase: Option<String>,
+    },
+    Vault(Vault),
+}
+
+/// Writes the data file shortly after the tasks change, so a crash or a
+/// closed terminal loses at most the last couple of seconds of work.
+pub struct Autosave {
+    target: Target,
+    pending_since: Option<Instant>,
+    /// Tasks last warned about for holding what the vault can't keep.
+    unkept: usize,
+}
+
+impl Autosave {
+    pub fn new(file_path: &str, passphrase: Option<&str>) -> Self {
+        Self {
+            target: Target::File {
+                path: file_path.to_string(),
+                passphrase: passphrase.map(str::to_string),
+            },
+            pending_since: None,
+            unkept: 0,
+        }
+    }
+
+    /// Saves the tasks back into the notes of `vault` instead.
+    pub fn vault(vault: Vault) -> Self {
+        Self {
+            target: Target::Vault(vault),
+            pending_since: None,
+            unkept: 0,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending_since.is_some()
+    }
+
+    /// Saves if changes have been pending for long enough.
+    pub fn tick(&mut self, model: &mut Model) {
+        if self
+            .pending_since
+            .is_some_and(|since| since.elapsed() >= AUTOSAVE_DELAY)
+        {
+            self.save(model);
+        }
+    }
+
+    /// Saves right away. A failure is put in front of the user with an
+    /// overlay they have to dismiss, rather than a status message that
+    /// scrolls away.
+    pub fn save(&mut self, model: &mut Model) -> bool {
+        self.pending_since = None;
+        let result = match &mut self.target {
+            Target::File { path, passphrase } => {
+                persistence::save(path, model, passphrase.as_deref())
+                    .map_err(|err| format!("Saving {} failed: {}", path, err))
+            }
+            Target::Vault(vault) => vault.save(&model.tasks).map(|_| ()).map_err(|err| {
+                format!(
+                    "Saving to the vault {} failed: {}",
+                    vault.dir.display(),
+                    err
+                )
+            }),
+        };
+        if let (Ok(()), Target::Vault(_)) = (&result, &self.target) {
+            let unkept = vault::unkept(&model.tasks);
+            if unkept > self.unkept {
+                model.notify(
+                    Severity::Warning,
+                    &format!(
+                        "{} tasks have notes, comments, reminders or attachments, which the vault notes can't keep.",
+                        unkept
+                    ),
+                );
+            }
+            self.unkept = unkept;
+        }
+        match result {
+            Ok(()) => {
+                model.save_error = None;
+                true
+            }
+            Err(message) => {
+                update(Msg::SaveFailed(message), model);
+                false
+            }
+        }
+    }
+
+    /// Reads the vault notes changed on disk again, replacing the tasks of
+    /// each, so saving them works again.
+    pub fn reload(&mut self, model: &mut Model) {
+        let Target::Vault(vault) = &mut self.target else {
+            return;
+        };
+        match vault.reload_changed() {
+            Ok(notes) => {
+                let message = format!("Reloaded {} notes changed on disk.", notes.len());
+                for (id, subtasks) in notes {
+                    if let Some(root) = model.get_task_mut(&[id]) {
+                        root.subtasks = subtasks;
+                        model.emit(Event::TaskChanged(id));
+                    }
+                }
+                model.notify(Severity::Success, &message);
+            }
+            Err(error) => model.notify(Severity::Error, &format!("Reloading failed: {}", error)),
+        }
+    }
+}
+
+impl Subscriber for Autosave {
+    fn notify(&mut self, event: &Event, _model: &Model) {
+        if event.is_persistent() && self.pending_since.is_none() {
+            self.pending_since = Some(Instant::now());
+        }
+    }
+}
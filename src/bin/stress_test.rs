@@ -0,0 +1,77 @@
+use chors_core::{
+    generate,
+    model::{FilterList, Model, Msg, View},
+    update::update,
+};
+use clap::{value_parser, Arg, Command};
+use std::time::Instant;
+
+fn timed<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    println!("{:<28} {:>10.2?}", label, start.elapsed());
+    result
+}
+
+fn main() {
+    let matches = Command::new("stress_test")
+        .about("Times core operations on a generated task tree")
+        .arg(
+            Arg::new("tasks")
+                .long("tasks")
+                .default_value("50000")
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("fanout")
+                .long("fanout")
+                .default_value("8")
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .default_value("6")
+                .value_parser(value_parser!(usize)),
+        )
+        .get_matches();
+    let tasks = *matches.get_one::<usize>("tasks").unwrap();
+    let fanout = *matches.get_one::<usize>("fanout").unwrap();
+    let depth = *matches.get_one::<usize>("depth").unwrap();
+
+    println!("{} tasks, fanout {}, depth {}", tasks, fanout, depth);
+    let mut model = Model::new();
+    model.tasks = timed("generate", || generate::balanced_tree(tasks, fanout, depth));
+
+    timed("flatten", || model.flattened().len());
+
+    let batch = model
+        .tasks
+        .values()
+        .map(|task| (Vec::new(), task.clone()))
+        .collect();
+    let mut imported = Model::new();
+    timed("bulk insert", || {
+        update(Msg::AddTasksBulk(batch), &mut imported)
+    });
+
+    let view = View {
+        filter_lists: vec![
+            FilterList::parse("#work @office completed:false").unwrap(),
+            FilterList::parse("#home").unwrap(),
+        ],
+        display: Default::default(),
+    };
+    timed("filter (cold)", || model.refresh_match_cache(&view));
+    let path = model.flattened()[0].1.clone();
+    model.get_task_mut(&path);
+    timed("filter (one dirty subtree)", || {
+        model.refresh_match_cache(&view)
+    });
+
+    let data = timed("serialize", || serde_json::to_string(&model).unwrap());
+    println!("{:<28} {:>10} bytes", "serialized size", data.len());
+    timed("deserialize", || {
+        serde_json::from_str::<Model>(&data).unwrap()
+    });
+}
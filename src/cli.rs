@@ -1,4 +1,4 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 
 pub fn build_cli() -> Command {
     Command::new("Chors - Task Manager.")
@@ -9,6 +9,162 @@ pub fn build_cli() -> Command {
                 .short('f')
                 .long("file")
                 .value_name("FILE")
+                .global(true)
                 .help("Sets a custom file for persistence"),
         )
+        .arg(
+            Arg::new("vault")
+                .long("vault")
+                .value_name("DIR")
+                .global(true)
+                .conflicts_with_all(["file", "encrypt"])
+                .help("Uses the checkboxes in the Markdown notes under DIR (e.g. an Obsidian vault) as the tasks"),
+        )
+        .arg(
+            Arg::new("encrypt")
+                .long("encrypt")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Encrypts the data file with a passphrase (encrypted files always ask for it)",
+                ),
+        )
+        .arg(
+            Arg::new("read-only")
+                .long("read-only")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("encrypt")
+                .help("Opens the data file for browsing; nothing is changed or saved"),
+        )
+        .arg(
+            Arg::new("profile-startup")
+                .long("profile-startup")
+                .action(ArgAction::SetTrue)
+                .help("Prints how long loading, the initial filter and the first draw took on exit"),
+        )
+        .arg(
+            Arg::new("tz")
+                .long("tz")
+                .global(true)
+                .value_name("ZONE")
+                .help("Shows dates in ZONE (e.g. Europe/Vilnius) instead of the system time zone"),
+        )
+        .arg(
+            Arg::new("author")
+                .long("author")
+                .global(true)
+                .value_name("NAME")
+                .help("Stamps NAME onto the tasks you add and complete (defaults to $USER)"),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("Draws without colors, as when NO_COLOR is set"),
+        )
+        .arg(
+            Arg::new("accessible")
+                .long("accessible")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("Plain ASCII with spelled-out markers, for screen readers and dumb terminals"),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("LOG")
+                .help("Appends every action of this session to LOG, for `chors replay`"),
+        )
+        .subcommand(
+            Command::new("replay")
+                .about("Rebuilds the model from a log written with --record")
+                .arg(Arg::new("log").required(true).help("Log file to replay"))
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Saves the rebuilt model to FILE"),
+                ),
+        )
+        .subcommand(
+            Command::new("add")
+                .about("Adds a task, or with `-` one task per line from stdin (`>` for subtasks)")
+                .arg(
+                    Arg::new("description")
+                        .required(true)
+                        .num_args(1..)
+                        .help("Task description, or - to read from stdin"),
+                ),
+        )
+        .subcommand(
+            Command::new("capture")
+                .about("Prompts for a single task and exits, for binding to a global hotkey"),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("Lists matching tasks with their short ids; exits 0 if any matched, 1 if none, 2 on a bad filter")
+                .arg(
+                    Arg::new("filter")
+                        .long("filter")
+                        .value_name("FILTER")
+                        .help("Only tasks matching FILTER (e.g. \"due:today\")"),
+                )
+                .arg(
+                    Arg::new("count")
+                        .long("count")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("quiet")
+                        .help("Prints the number of matching tasks instead"),
+                )
+                .arg(
+                    Arg::new("quiet")
+                        .short('q')
+                        .long("quiet")
+                        .action(ArgAction::SetTrue)
+                        .help("Prints nothing; only the exit code tells"),
+                )
+                .arg(
+                    Arg::new("watch")
+                        .long("watch")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("quiet")
+                        .help("Lists again whenever the data file changes, e.g. in a tmux pane"),
+                ),
+        )
+        .subcommand(
+            Command::new("print")
+                .about("Prints the task tree as plain text with checkboxes, e.g. to pipe to `lp`")
+                .arg(
+                    Arg::new("filter").long("filter").value_name("FILTER").help(
+                        "Only tasks matching FILTER (e.g. \"#groceries\"), with their parents",
+                    ),
+                )
+                .arg(
+                    Arg::new("width")
+                        .long("width")
+                        .value_name("COLUMNS")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Wraps descriptions to COLUMNS"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Serves the due tasks as a read-only calendar at /calendar.ics")
+                .arg(
+                    Arg::new("addr")
+                        .long("addr")
+                        .value_name("ADDRESS")
+                        .default_value("127.0.0.1:8080")
+                        .help("Listens on ADDRESS; use 0.0.0.0:8080 to reach it from a phone"),
+                ),
+        )
+        .subcommand(
+            Command::new("done").about("Marks a task as completed").arg(
+                Arg::new("id")
+                    .required(true)
+                    .help("Short id of the task (as shown in the task list)"),
+            ),
+        )
 }
@@ -0,0 +1,351 @@
+use crate::replay;
+use chors_core::{
+    model::{flatten_tasks, FilterList, Model, Msg, Task},
+    persistence, resolve,
+    update::update,
+};
+use chrono::Local;
+use color_eyre::{eyre::bail, Result};
+use indexmap::IndexMap;
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Read, Write},
+    thread,
+    time::Duration,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use uuid::Uuid;
+
+/// `chors done <id>`: marks the task with the given short id as completed.
+pub fn done(model: &mut Model, short_id: &str) -> Result<()> {
+    let path = resolve::resolve(model.flattened(), short_id)?;
+    update(Msg::CompleteTasks(vec![path.clone()]), model);
+    if let Some(task) = model.get_task(&path) {
+        println!("Completed: {}", task.description);
+    }
+    Ok(())
+}
+
+/// `chors add <description>` adds a single task; `chors add -` reads one task
+/// per line from stdin, with a `>` prefix per level for subtasks.
+pub fn add(model: &mut Model, words: &[String]) -> Result<()> {
+    let tasks = if words == ["-"] {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        parse_outline(&input)?
+    } else {
+        vec![Task::new(&words.join(" "))]
+    };
+    let count: usize = tasks.iter().map(|task| task.descendant_count() + 1).sum();
+    let inbox = vec![model.inbox_id()];
+    let tasks = tasks
+        .into_iter()
+        .map(|task| (inbox.clone(), task))
+        .collect();
+    update(Msg::AddTasksBulk(tasks), model);
+    println!("Added {} task{}.", count, if count == 1 { "" } else { "s" });
+    Ok(())
+}
+
+/// Builds task trees from lines like `Plan trip`, `> Book flights`.
+fn parse_outline(input: &str) -> Result<Vec<Task>> {
+    let mut roots = IndexMap::new();
+    for (number, line) in input.lines().enumerate() {
+        let depth = line.chars().take_while(|&c| c == '>').count();
+        let description = line[depth..].trim();
+        if description.is_empty() {
+            continue;
+        }
+        let mut siblings: &mut IndexMap<Uuid, Task> = &mut roots;
+        for _ in 0..depth {
+            siblings = match siblings.last_mut() {
+                Some((_, parent)) => &mut parent.subtasks,
+                None => bail!("line {}: subtask without a parent task", number + 1),
+            };
+        }
+        let task = Task::new(description);
+        siblings.insert(task.id, task);
+    }
+    Ok(roots.into_values().collect())
+}
+
+/// `chors replay <log>`: rebuilds the model from a recorded log and prints
+/// the resulting task tree, optionally saving it.
+pub fn replay(log: &str, output: Option<&String>) -> Result<()> {
+    let replay = replay::replay(log)?;
+    let model = replay.model;
+    println!(
+        "Replayed {} messages from {} sessions.",
+        replay.messages, replay.sessions
+    );
+    if replay.skipped > 0 {
+        println!(
+            "Skipped {} messages that open, write or run things.",
+            replay.skipped
+        );
+    }
+    for (_, path) in flatten_tasks(&model.tasks) {
+        if let Some(task) = model.get_task(&path) {
+            let status = if task.completed { "[x]" } else { "[ ]" };
+            println!(
+                "{}{} {}",
+                "  ".repeat(path.len() - 1),
+                status,
+                task.description
+            );
+        }
+    }
+    if let Some(output) = output {
+        persistence::save(output, &model, None)?;
+    }
+    Ok(())
+}
+
+/// Exit codes of the query commands, so scripts and prompts can branch on
+/// whether anything matched.
+pub const EXIT_MATCHED: i32 = 0;
+pub const EXIT_NO_MATCHES: i32 = 1;
+pub const EXIT_PARSE_ERROR: i32 = 2;
+
+/// What `chors list` writes for the tasks it found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListOutput {
+    Tasks,
+    Count,
+    Quiet,
+}
+
+/// Parses a `--filter` argument, reporting errors on stderr since the query
+/// commands answer with an exit code rather than an error.
+fn parse_filter(model: &Model, filter: Option<&str>) -> Result<Option<FilterList>, i32> {
+    match filter.map(|filter| model.parse_filter(filter)) {
+        Some(Ok(filter)) => Ok(Some(filter)),
+        Some(Err(error)) => {
+            eprintln!("chors: invalid filter: {}", error);
+            Err(EXIT_PARSE_ERROR)
+        }
+        None => Ok(None),
+    }
+}
+
+fn exit_code(matched: usize) -> i32 {
+    match matched {
+        0 => EXIT_NO_MATCHES,
+        _ => EXIT_MATCHED,
+    }
+}
+
+/// `chors list`: the tasks matching a filter, one per line with their short
+/// ids. Returns the exit code.
+pub fn list(model: &mut Model, filter: Option<&str>, output: ListOutput) -> i32 {
+    let filter = match parse_filter(model, filter) {
+        Ok(filter) => filter,
+        Err(code) => return code,
+    };
+    let lines = list_lines(model, filter.as_ref());
+    match output {
+        ListOutput::Tasks => lines.iter().for_each(|line| println!("{}", line)),
+        ListOutput::Count => println!("{}", lines.len()),
+        ListOutput::Quiet => {}
+    }
+    exit_code(lines.len())
+}
+
+/// The lines `chors list` prints for the tasks matching `filter`.
+pub fn list_lines(model: &mut Model, filter: Option<&FilterList>) -> Vec<String> {
+    let short_ids = resolve::short_ids(model.flattened());
+    flatten_tasks(&model.tasks)
+        .into_iter()
+        .filter_map(|(_, path)| {
+            let task = model.get_task(&path)?;
+            filter
+                .is_none_or(|filter| {
+                    filter.matches_at(task, &path[..path.len() - 1], &model.date_display)
+                })
+                .then_some(task)
+        })
+        .map(|task| {
+            format!(
+                "{} {} {}",
+                short_ids[&task.id],
+                checkbox(task),
+                task.description
+            )
+        })
+        .collect()
+}
+
+/// How often `chors list --watch` checks the data file for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `chors list --watch`: lists again whenever the data file is written, on
+/// a cleared screen, until interrupted. In the accessible profile the
+/// screen is never cleared; only the lines that went away (`-`) and came in
+/// (`+`) are written, so a screen reader announces just the change.
+///
+/// Polls the modification time rather than subscribing to file system
+/// events, so it behaves the same on every platform and on network drives.
+pub fn watch_list(
+    mut model: Model,
+    passphrase: Option<&str>,
+    filter: Option<&str>,
+    output: ListOutput,
+) -> Result<i32> {
+    let Some(file_path) = model.data_file.clone() else {
+        bail!("--watch requires a data file");
+    };
+    let parsed = match parse_filter(&model, filter) {
+        Ok(parsed) => parsed,
+        Err(code) => return Ok(code),
+    };
+    let modified = || {
+        fs::metadata(&file_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let mut seen = modified();
+    let mut shown: Option<Vec<String>> = None;
+    loop {
+        let lines = list_lines(&mut model, parsed.as_ref());
+        match (&shown, model.accessible, output) {
+            (Some(shown), true, ListOutput::Tasks) => {
+                for line in shown.iter().filter(|line| !lines.contains(line)) {
+                    println!("- {}", line);
+                }
+                for line in lines.iter().filter(|line| !shown.contains(line)) {
+                    println!("+ {}", line);
+                }
+            }
+            (Some(shown), true, ListOutput::Count) if shown.len() == lines.len() => {}
+            (_, accessible, _) => {
+                if !accessible {
+                    // Clear the screen and move to the top left
+                    print!("\x1b[2J\x1b[H");
+                    println!(
+                        "{} (updated {})\n",
+                        filter.unwrap_or("all tasks"),
+                        Local::now().format("%H:%M:%S")
+                    );
+                }
+                match output {
+                    ListOutput::Tasks => lines.iter().for_each(|line| println!("{}", line)),
+                    ListOutput::Count => println!("{}", lines.len()),
+                    ListOutput::Quiet => {}
+                }
+            }
+        }
+        shown = Some(lines);
+        io::stdout().flush()?;
+
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            let current = modified();
+            if current == seen {
+                continue;
+            }
+            // A save may still be in progress; try again on the next tick
+            if let Ok(reloaded) = persistence::load_with_progress(&file_path, passphrase, |_, _| {})
+            {
+                seen = current;
+                let (dates, accessible) = (model.date_display.clone(), model.accessible);
+                model = reloaded;
+                model.data_file = Some(file_path.clone());
+                model.date_display = dates;
+                model.accessible = accessible;
+                break;
+            }
+        }
+    }
+}
+
+/// `chors print`: writes the task tree as plain text with checkboxes, for
+/// printing or piping to `lp`. Returns the exit code.
+pub fn print(model: &Model, filter: Option<&str>, width: Option<usize>) -> i32 {
+    let filter = match parse_filter(model, filter) {
+        Ok(filter) => filter,
+        Err(code) => return code,
+    };
+    let (report, matched) = report(model, filter.as_ref(), width);
+    print!("{}", report);
+    exit_code(matched)
+}
+
+fn checkbox(task: &Task) -> &'static str {
+    match (task.completed, &task.cancelled) {
+        (_, Some(_)) => "[-]",
+        (true, None) => "[x]",
+        (false, None) => "[ ]",
+    }
+}
+
+/// The tree as indented `[ ]`/`[x]`/`[-]` lines, and how many tasks
+/// matched. With a filter, matching tasks are kept along with their
+/// ancestors, so every line keeps its place in the outline. Descriptions
+/// wrap to `width` columns under their own indentation.
+pub fn report(model: &Model, filter: Option<&FilterList>, width: Option<usize>) -> (String, usize) {
+    let paths = flatten_tasks(&model.tasks);
+    let matched: Vec<&Vec<Uuid>> = paths
+        .iter()
+        .map(|(_, path)| path)
+        .filter(|path| {
+            model.get_task(path).is_some_and(|task| {
+                filter.is_none_or(|filter| {
+                    filter.matches_at(task, &path[..path.len() - 1], &model.date_display)
+                })
+            })
+        })
+        .collect();
+    let kept: HashSet<Uuid> = matched
+        .iter()
+        .flat_map(|path| path.iter().copied())
+        .collect();
+
+    let mut out = String::new();
+    for (id, path) in &paths {
+        if !kept.contains(id) {
+            continue;
+        }
+        let Some(task) = model.get_task(path) else {
+            continue;
+        };
+        let status = checkbox(task);
+        let indent = "  ".repeat(path.len() - 1);
+        let hang = " ".repeat(indent.len() + status.len() + 1);
+        let text_width = width.map(|width| width.saturating_sub(hang.len()).max(1));
+        for (index, line) in wrap(&task.description, text_width).iter().enumerate() {
+            match index {
+                0 => out.push_str(&format!("{}{} {}\n", indent, status, line)),
+                _ => out.push_str(&format!("{}{}\n", hang, line)),
+            }
+        }
+    }
+    (out, matched.len())
+}
+
+/// Greedy word wrap; words longer than the width are split.
+fn wrap(text: &str, width: Option<usize>) -> Vec<String> {
+    let Some(width) = width else {
+        return vec![text.to_string()];
+    };
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().unwrap();
+        if !line.is_empty() && line.width() + 1 + word.width() <= width {
+            line.push(' ');
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(String::new());
+        }
+        for ch in word.chars() {
+            let line = lines.last_mut().unwrap();
+            if !line.is_empty() && line.width() + ch.width().unwrap_or(0) > width {
+                lines.push(String::new());
+            }
+            lines.last_mut().unwrap().push(ch);
+        }
+    }
+    lines
+}
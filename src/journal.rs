@@ -0,0 +1,71 @@
+//! The done-log: a line appended to [`Model::journal`] for every task
+//! completed, readable by other tools (e.g. an Obsidian daily note).
+//!
+//! ```text
+//! - [x] 2024-03-08 14:05 Write the report #work @office
+//! ```
+
+use chors_core::{
+    events::{Event, Subscriber},
+    model::{flatten_tasks, Model, Task},
+};
+use chrono::{DateTime, Utc};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// Appends completions to the journal file, if one is set. Writing can fail
+/// while the model can't be changed, so failures wait in [`Journal::error`]
+/// for the caller to report.
+#[derive(Default)]
+pub struct Journal {
+    pub error: Option<String>,
+}
+
+/// The journal line for `task` completed `at`. Times are in the display time
+/// zone but always in the same format, so the log stays easy to parse; tags
+/// and contexts are part of the description.
+pub fn entry(model: &Model, task: &Task, at: DateTime<Utc>) -> String {
+    let at = model.date_display.local(at);
+    format!(
+        "- [x] {} {}\n",
+        at.format("%Y-%m-%d %H:%M"),
+        task.description
+    )
+}
+
+fn append(path: &Path, entry: &str) -> io::Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(entry.as_bytes())
+}
+
+impl Subscriber for Journal {
+    fn notify(&mut self, event: &Event, model: &Model) {
+        let (Event::TaskCompleted(id), Some(journal)) = (event, &model.journal) else {
+            return;
+        };
+        let task = flatten_tasks(&model.tasks)
+            .into_iter()
+            .find(|(task_id, _)| task_id == id)
+            .and_then(|(_, path)| model.get_task(&path));
+        let Some(task) = task else {
+            return;
+        };
+        let at = task.completed_at.unwrap_or_else(Utc::now);
+        if let Err(error) = append(journal, &entry(model, task, at)) {
+            self.error = Some(format!(
+                "Writing to the journal {} failed: {}",
+                journal.display(),
+                error
+            ));
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Which message each key press sends, by overlay and mode.
+
+use chors_core::{
+    export,
+    model::{Direction, Mode, Model, Msg, Overlay, View},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Percentage points `+` and `-` move a task's progress by.
+const PROGRESS_STEP: i32 = 10;
+
+/// The message for `event` in the model's current overlay and mode.
+pub fn key_event_to_msg(model: &Model, event: KeyEvent) -> Msg {
+    let key = event.code;
+    match model.overlay {
+        Overlay::None => match model.mode {
+            Mode::List if model.sidebar_focused => match key {
+                KeyCode::Char('k') => Msg::NavigateSidebar(Direction::Up),
+                KeyCode::Char('j') => Msg::NavigateSidebar(Direction::Down),
+                KeyCode::Enter => Msg::SelectSidebarEntry,
+                KeyCode::Char('S') => Msg::ToggleSidebar,
+                KeyCode::Tab | KeyCode::Esc => Msg::FocusSidebar(false),
+                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
+                _ => Msg::NoOp,
+            },
+            Mode::List => match key {
+                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
+                KeyCode::Char('a') => Msg::SetOverlay(Overlay::AddingTask),
+                KeyCode::Char('A') => Msg::SetOverlay(Overlay::AddingSubtask),
+                // Most terminals send Ctrl-I as Tab, so Ctrl-N goes forward
+                // everywhere and Ctrl-I only where it is reported apart
+                KeyCode::Char('n' | 'i') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Msg::JumpForward
+                }
+                KeyCode::Char('n') => Msg::NewTaskForm,
+                KeyCode::Char('e') => Msg::EditTaskForm,
+                KeyCode::Char('v') => Msg::SetOverlay(Overlay::View),
+                KeyCode::Char('f') => Msg::SetOverlay(Overlay::AddingFilterCriterion),
+                KeyCode::Char('c') => Msg::ToggleTaskCompletion,
+                KeyCode::Char('x') => Msg::SetOverlay(Overlay::CancellingTask),
+                KeyCode::Char('h') => Msg::RunCommand("history".to_string()),
+                KeyCode::Char('r') => Msg::RunCommand("comments".to_string()),
+                KeyCode::Char('#') => Msg::RunCommand("tags".to_string()),
+                KeyCode::Char('F') => Msg::RunCommand("focus".to_string()),
+                KeyCode::Char('V') => Msg::RunCommand("views".to_string()),
+                KeyCode::Char('d') => Msg::DeleteTask,
+                KeyCode::Char('D') => Msg::DuplicateTask(model.get_path()),
+                KeyCode::Char('o') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Msg::JumpBack
+                }
+                KeyCode::Char('o') => Msg::OpenUrl,
+                KeyCode::Char('P') => Msg::SetOverlay(Overlay::AddingAttachment),
+                KeyCode::Char('O') => Msg::OpenAttachment,
+                KeyCode::Char('X') => Msg::CompleteAllFiltered,
+                KeyCode::Char('k') => Msg::NavigateTasks(Direction::Up),
+                KeyCode::Char('j') => Msg::NavigateTasks(Direction::Down),
+                KeyCode::Char('p') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Msg::SetOverlay(Overlay::Jump)
+                }
+                KeyCode::Char('p') => Msg::SetOverlay(Overlay::Debug),
+                KeyCode::Char('g') => Msg::SetOverlay(Overlay::Navigation),
+                KeyCode::Char('G') => Msg::JumpToEnd,
+                KeyCode::Char('N') => Msg::ToggleRelativeLineNumbers,
+                KeyCode::Char('I') => Msg::ToggleShortIds,
+                KeyCode::Char('S') => Msg::ToggleSidebar,
+                KeyCode::Tab => Msg::FocusSidebar(true),
+                KeyCode::Char('s') => Msg::CycleSortKey,
+                KeyCode::Char('B') => Msg::CycleGroupBy,
+                KeyCode::Char('H') => Msg::ToggleShowCompleted,
+                KeyCode::Char('T') => Msg::ToggleDoneReview,
+                KeyCode::Char('W')
+                    if Some(&model.current_view) == View::builtin("waiting").as_ref() =>
+                {
+                    Msg::LoadView(model.selected_view.clone())
+                }
+                KeyCode::Char('W') => Msg::LoadView("waiting".to_string()),
+                KeyCode::Char('C') => Msg::SwitchMode(Mode::Calendar),
+                KeyCode::Char('w') => Msg::SwitchMode(Mode::Planning),
+                KeyCode::Char('t') => Msg::SwitchMode(Mode::Timeline),
+                KeyCode::Char('Y') => Msg::SwitchMode(Mode::Stats),
+                KeyCode::Char('E') => Msg::ExportView(export::Format::Markdown),
+                KeyCode::Char('L') => Msg::SetOverlay(Overlay::SwitchingList),
+                KeyCode::Char('?') => Msg::SetOverlay(Overlay::Help),
+                KeyCode::Char(':') => Msg::SetOverlay(Overlay::Command),
+                KeyCode::Char('i') => Msg::JumpToInbox,
+                KeyCode::Char('m') => Msg::SetOverlay(Overlay::MovingTask),
+                KeyCode::Char('M') => Msg::SetOverlay(Overlay::SettingMark),
+                KeyCode::Char('*') => Msg::SetOverlay(Overlay::FilterSuggestions),
+                KeyCode::Char('\'') => Msg::SetOverlay(Overlay::Marks),
+                KeyCode::Char('z') => Msg::Zoom(model.get_path()),
+                KeyCode::Char('+') => Msg::AdjustProgress(PROGRESS_STEP),
+                KeyCode::Char('-') => Msg::AdjustProgress(-PROGRESS_STEP),
+                KeyCode::Backspace => {
+                    Msg::Zoom(model.zoom[..model.zoom.len().saturating_sub(1)].to_vec())
+                }
+                KeyCode::Esc => Msg::Zoom(Vec::new()),
+                // Plain digits are counts, so bound views load with Alt
+                KeyCode::Char(c)
+                    if event.modifiers.contains(KeyModifiers::ALT)
+                        && model.view_keys.contains_key(&c) =>
+                {
+                    Msg::LoadView(model.view_keys[&c].clone())
+                }
+                KeyCode::Char(c)
+                    if c.is_ascii_digit() && (c != '0' || model.pending_count.is_some()) =>
+                {
+                    Msg::PushCount(c.to_digit(10).unwrap() as usize)
+                }
+                KeyCode::Char(c) => match model.scripts.bound_to(c) {
+                    Some(name) => Msg::RunCommand(name.to_string()),
+                    None => Msg::NoOp,
+                },
+                _ => Msg::NoOp,
+            },
+            Mode::Calendar => match key {
+                KeyCode::Char('C') => Msg::SwitchMode(Mode::List),
+                _ => Msg::NoOp,
+            },
+            Mode::Planning => match key {
+                KeyCode::Char('j') | KeyCode::Down => Msg::NavigatePlanning(Direction::Down),
+                KeyCode::Char('k') | KeyCode::Up => Msg::NavigatePlanning(Direction::Up),
+                KeyCode::Char(c @ '1'..='7') => {
+                    let now = chrono::Utc::now();
+                    let backlog = model.planning_backlog(now);
+                    let offset = c.to_digit(10).unwrap() - 1;
+                    let day =
+                        model.date_display.local_date(now) + chrono::Duration::days(offset.into());
+                    match model
+                        .planning_state
+                        .selected()
+                        .and_then(|index| backlog.get(index))
+                    {
+                        Some(path) => Msg::ScheduleTask {
+                            path: path.clone(),
+                            day: Some(day),
+                        },
+                        None => Msg::NoOp,
+                    }
+                }
+                KeyCode::Char('w') | KeyCode::Esc => Msg::SwitchMode(Mode::List),
+                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
+                _ => Msg::NoOp,
+            },
+            Mode::Timeline => match key {
+                KeyCode::Char('j') | KeyCode::Down => Msg::NavigateTasks(Direction::Down),
+                KeyCode::Char('k') | KeyCode::Up => Msg::NavigateTasks(Direction::Up),
+                KeyCode::Char('c') => Msg::ToggleTaskCompletion,
+                KeyCode::Char('x') => Msg::SetOverlay(Overlay::CancellingTask),
+                KeyCode::Char('e') => Msg::EditTaskForm,
+                KeyCode::Char('z') => Msg::CycleTimelineScale,
+                KeyCode::Char('t') | KeyCode::Esc => Msg::SwitchMode(Mode::List),
+                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
+                _ => Msg::NoOp,
+            },
+            Mode::Stats => match key {
+                KeyCode::Char('h') | KeyCode::Left => Msg::MoveActivityDay(-7),
+                KeyCode::Char('l') | KeyCode::Right => Msg::MoveActivityDay(7),
+                KeyCode::Char('k') | KeyCode::Up => Msg::MoveActivityDay(-1),
+                KeyCode::Char('j') | KeyCode::Down => Msg::MoveActivityDay(1),
+                KeyCode::Tab => Msg::ToggleActivityMetric,
+                KeyCode::Char('Y') | KeyCode::Esc => Msg::SwitchMode(Mode::List),
+                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
+                _ => Msg::NoOp,
+            },
+            Mode::Quit => Msg::Quit,
+        },
+        Overlay::AddingTask | Overlay::AddingSubtask | Overlay::AddingFilterCriterion => {
+            match key {
+                KeyCode::Enter => {
+                    if let Overlay::AddingTask = model.overlay {
+                        Msg::AddTask
+                    } else if let Overlay::AddingSubtask = model.overlay {
+                        Msg::AddSubtask
+                    } else {
+                        Msg::AddFilterCriterion
+                    }
+                }
+                KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+                _ => input_key_to_msg(event),
+            }
+        }
+        Overlay::Tags => match key {
+            KeyCode::Char('j') | KeyCode::Down => Msg::NavigateTags(Direction::Down),
+            KeyCode::Char('k') | KeyCode::Up => Msg::NavigateTags(Direction::Up),
+            KeyCode::Char('r') => Msg::SetOverlay(Overlay::RenamingTag),
+            KeyCode::Char('m') => Msg::SetOverlay(Overlay::MergingTag),
+            KeyCode::Char('d') => Msg::DeleteTag,
+            KeyCode::Esc | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::Columns => {
+            let selected = model
+                .column_state
+                .selected()
+                .and_then(|index| model.column_choices().get(index).copied());
+            match (key, selected) {
+                (KeyCode::Char('j') | KeyCode::Down, _) => Msg::NavigateColumns(Direction::Down),
+                (KeyCode::Char('k') | KeyCode::Up, _) => Msg::NavigateColumns(Direction::Up),
+                (KeyCode::Char(' ') | KeyCode::Enter, Some(column)) => Msg::ToggleColumn(column),
+                (KeyCode::Char('J'), Some(column)) => Msg::MoveColumn(column, Direction::Down),
+                (KeyCode::Char('K'), Some(column)) => Msg::MoveColumn(column, Direction::Up),
+                (KeyCode::Char('+'), Some(column)) => Msg::ResizeColumn(column, 1),
+                (KeyCode::Char('-'), Some(column)) => Msg::ResizeColumn(column, -1),
+                (KeyCode::Esc | KeyCode::Char('q'), _) => Msg::SetOverlay(Overlay::None),
+                _ => Msg::NoOp,
+            }
+        }
+        Overlay::FocusPicker => match key {
+            KeyCode::Char('j') | KeyCode::Down => Msg::NavigateFocusChoices(Direction::Down),
+            KeyCode::Char('k') | KeyCode::Up => Msg::NavigateFocusChoices(Direction::Up),
+            KeyCode::Enter => {
+                let choices = model.focus_choices();
+                match model
+                    .focus_state
+                    .selected()
+                    .and_then(|index| choices.get(index))
+                {
+                    Some(choice) => Msg::SetFocusContext(choice.clone()),
+                    None => Msg::NoOp,
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::RenamingTag | Overlay::MergingTag => match key {
+            KeyCode::Enter => Msg::SubmitTagRename,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::Tags),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::CancellingTask => match key {
+            KeyCode::Enter => Msg::CancelTask,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::MovingTask => match key {
+            KeyCode::Enter => Msg::SubmitMove,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::AddingAttachment => match key {
+            KeyCode::Enter => Msg::AttachFile,
+            KeyCode::Tab => Msg::CompletePath,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::View => match key {
+            KeyCode::Enter => Msg::SaveCurrentView(model.input.text().to_string()),
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Command => match key {
+            KeyCode::Enter => Msg::RunCommand(model.input.text().to_string()),
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Jump => match key {
+            KeyCode::Enter => {
+                let results = model.jump_results(model.input.text());
+                match model
+                    .jump_state
+                    .selected()
+                    .and_then(|index| results.get(index))
+                {
+                    Some(path) => Msg::JumpToTask(path.clone()),
+                    None => Msg::SetOverlay(Overlay::None),
+                }
+            }
+            KeyCode::Up => Msg::NavigateJump(Direction::Up),
+            KeyCode::Down => Msg::NavigateJump(Direction::Down),
+            KeyCode::Char('p') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                Msg::NavigateJump(Direction::Up)
+            }
+            KeyCode::Char('n') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                Msg::NavigateJump(Direction::Down)
+            }
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Views => match key {
+            KeyCode::Char('j') | KeyCode::Down => Msg::NavigateViews(Direction::Down),
+            KeyCode::Char('k') | KeyCode::Up => Msg::NavigateViews(Direction::Up),
+            KeyCode::Enter => match model.selected_saved_view() {
+                Some(name) => Msg::LoadView(name.to_string()),
+                None => Msg::SetOverlay(Overlay::None),
+            },
+            KeyCode::Char(c @ '1'..='9') => Msg::BindViewKey(c),
+            KeyCode::Esc | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::FilterSuggestions => match key {
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c.to_digit(10).unwrap() as usize - 1;
+                match model.filter_suggestions().into_iter().nth(index) {
+                    Some(filter) => Msg::ApplyFilter(filter),
+                    None => Msg::NoOp,
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('*') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::SettingMark => match key {
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() => Msg::SetMark(c),
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::Marks => match key {
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() => Msg::JumpToMark(c),
+            KeyCode::Esc | KeyCode::Char('\'') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::SwitchingList => match key {
+            KeyCode::Enter => Msg::SwitchList(model.input.text().to_string()),
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Debug => match key {
+            KeyCode::Char('p') => Msg::SetOverlay(Overlay::None),
+            KeyCode::Char('j') => Msg::ScrollDebug(Direction::Down),
+            KeyCode::Char('k') => Msg::ScrollDebug(Direction::Up),
+            _ => Msg::NoOp,
+        },
+        Overlay::Messages | Overlay::History => match key {
+            KeyCode::Char('j') => Msg::ScrollDebug(Direction::Down),
+            KeyCode::Char('k') => Msg::ScrollDebug(Direction::Up),
+            KeyCode::Esc | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::Comments => match key {
+            KeyCode::Enter => Msg::AddComment,
+            KeyCode::Up => Msg::ScrollDebug(Direction::Up),
+            KeyCode::Down => Msg::ScrollDebug(Direction::Down),
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Rollover => match key {
+            KeyCode::Char('j') => Msg::ScrollDebug(Direction::Down),
+            KeyCode::Char('k') => Msg::ScrollDebug(Direction::Up),
+            KeyCode::Char('u') => Msg::UndoRollover,
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::Summary => match key {
+            KeyCode::Char('j') => Msg::ScrollDebug(Direction::Down),
+            KeyCode::Char('k') => Msg::ScrollDebug(Direction::Up),
+            KeyCode::Char('y') => Msg::CopySummary,
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::Navigation => match key {
+            KeyCode::Char('g') => Msg::HandleNavigation,
+            KeyCode::Char('e') | KeyCode::Char('G') => Msg::JumpToEnd,
+            KeyCode::Char(c) if c.is_ascii_digit() => Msg::PushChar(c),
+            KeyCode::Backspace => Msg::PopChar,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::TaskForm => match key {
+            KeyCode::Enter => Msg::SubmitTaskForm,
+            KeyCode::Tab => Msg::FormNextField,
+            KeyCode::BackTab => Msg::FormPreviousField,
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => input_key_to_msg(event),
+        },
+        Overlay::Confirm => match key {
+            KeyCode::Char('y') | KeyCode::Enter => Msg::Confirm,
+            KeyCode::Char('n') | KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+        Overlay::SaveFailed => match key {
+            KeyCode::Enter | KeyCode::Esc => Msg::DismissSaveError,
+            KeyCode::Char('r') => Msg::ReloadChangedNotes,
+            KeyCode::Char('Q') => Msg::Quit,
+            _ => Msg::NoOp,
+        },
+        Overlay::SlowStartup => Msg::SetOverlay(Overlay::None),
+        Overlay::Welcome => match key {
+            KeyCode::Char('?') => Msg::SetOverlay(Overlay::Help),
+            _ => Msg::SetOverlay(Overlay::None),
+        },
+        Overlay::Help => match key {
+            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
+            _ => Msg::NoOp,
+        },
+    }
+}
+
+/// Readline-style line editing shared by all text input overlays.
+pub fn input_key_to_msg(event: KeyEvent) -> Msg {
+    if event.modifiers.contains(KeyModifiers::CONTROL) {
+        return match event.code {
+            KeyCode::Char('a') => Msg::CursorHome,
+            KeyCode::Char('e') => Msg::CursorEnd,
+            KeyCode::Char('b') => Msg::CursorLeft,
+            KeyCode::Char('f') => Msg::CursorRight,
+            KeyCode::Char('u') => Msg::KillToStart,
+            KeyCode::Char('k') => Msg::KillToEnd,
+            KeyCode::Char('y') => Msg::Yank,
+            KeyCode::Char('t') => Msg::TransposeChars,
+            _ => Msg::NoOp,
+        };
+    }
+    if event.modifiers.contains(KeyModifiers::ALT) {
+        return match event.code {
+            KeyCode::Char('d') => Msg::KillWordForward,
+            _ => Msg::NoOp,
+        };
+    }
+    match event.code {
+        KeyCode::Char(c) => Msg::PushChar(c),
+        KeyCode::Backspace => Msg::PopChar,
+        KeyCode::Left => Msg::CursorLeft,
+        KeyCode::Right => Msg::CursorRight,
+        KeyCode::Up => Msg::HistoryPrevious,
+        KeyCode::Down => Msg::HistoryNext,
+        KeyCode::Home => Msg::CursorHome,
+        KeyCode::End => Msg::CursorEnd,
+        _ => Msg::NoOp,
+    }
+}
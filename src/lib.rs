@@ -0,0 +1,13 @@
+//! The terminal frontend of chors, built on [`chors_core`].
+
+pub mod aliases;
+pub mod autosave;
+pub mod cli;
+pub mod commands;
+pub mod errors;
+pub mod journal;
+pub mod keys;
+pub mod replay;
+pub mod serve;
+pub mod view;
+pub mod webhooks;
@@ -1,108 +1,267 @@
-mod cli;
-mod errors;
-mod model;
-mod update;
-mod view;
-
-use crate::{
+use chors::{
+    aliases,
+    autosave::Autosave,
+    cli, commands,
     errors::install_hooks,
-    model::{Direction, Mode, Model, Msg},
+    journal::Journal,
+    keys,
+    replay::Recorder,
+    serve, view,
+    webhooks::{self, Webhooks},
+};
+use chors_core::{
+    caldav, dates,
+    events::{self, Subscriber},
+    macros,
+    model::{ColorSupport, Mode, Model, Msg, Overlay, StartupProfile, Task, SLOW_FILTER_THRESHOLD},
+    persistence, scripting,
+    status::Severity,
     update::update,
+    vault::Vault,
 };
-use color_eyre::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
-use model::Overlay;
+use color_eyre::{eyre::bail, Result};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use futures::StreamExt;
 use ratatui::Terminal;
-use std::{fs, path::Path};
+use std::{
+    fs, mem,
+    net::TcpListener,
+    path::Path,
+    process,
+    time::{Duration, Instant},
+};
 
-fn run_app<B: ratatui::backend::Backend>(
+/// How often the event loop wakes up without input while something is
+/// running on a timer, to drive it and redraw progress from background work.
+const TICK_RATE: Duration = Duration::from_millis(100);
+/// How often it wakes up when nothing is, for reminders and the clock.
+const IDLE_TICK_RATE: Duration = Duration::from_secs(1);
+/// Quiet ticks still redraw this often, for relative dates, the current day
+/// and other displays that follow the clock.
+const CLOCK_REDRAW: Duration = Duration::from_secs(1);
+/// Frames are drawn at most this often; changes arriving faster (held keys,
+/// pastes) are drawn together.
+const MAX_FPS: u32 = 60;
+
+async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     model: &mut Model,
+    mut autosave: Option<Autosave>,
+    mut recorder: Option<Recorder>,
+    webhooks: &mut Webhooks,
 ) -> Result<()> {
+    let mut events = EventStream::new();
+    let mut journal = Journal::default();
+    let frame_time = Duration::from_secs(1) / MAX_FPS;
+    let mut next_tick = Instant::now() + TICK_RATE;
+    // Drawing renders the whole screen, so messages that change nothing on
+    // it (unbound keys, most ticks) skip the frame altogether
+    let mut redraw = true;
+    let mut last_draw = Instant::now() - frame_time;
     loop {
-        terminal.draw(|f| view::ui(f, model))?;
+        if redraw && last_draw.elapsed() >= frame_time {
+            let started = Instant::now();
+            terminal.draw(|f| view::ui(f, model))?;
+            model.frame_stats.record(started.elapsed());
+            if let Some(profile) = &mut model.startup_profile {
+                profile.first_draw.get_or_insert(started.elapsed());
+            }
+            last_draw = started;
+            redraw = false;
+            // Drawing starts the scroll to a far jump
+            if model.animation.is_running() {
+                next_tick = next_tick.min(started + TICK_RATE);
+            }
+        }
+        let next_frame = last_draw + frame_time;
 
-        if event::poll(std::time::Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let msg = key_event_to_msg(model, key.code);
-                    update(msg, model);
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    let msg = keys::key_event_to_msg(model, key);
+                    redraw |= !matches!(msg, Msg::NoOp);
+                    let reload = matches!(msg, Msg::ReloadChangedNotes);
+                    apply(msg, model, autosave.as_mut(), recorder.as_mut(), &mut journal, webhooks);
+                    if let (true, Some(autosave)) = (reload, autosave.as_mut()) {
+                        autosave.reload(model);
+                    }
+                    // Save before leaving so a failure can still be shown,
+                    // unless it was just acknowledged with Q
                     if let Mode::Quit = model.mode {
-                        return Ok(());
+                        if model.caldav_config.is_some() && !model.read_only {
+                            apply(
+                                Msg::Sync,
+                                model,
+                                autosave.as_mut(),
+                                recorder.as_mut(),
+                                &mut journal,
+                                webhooks,
+                            );
+                        }
+                        if model.save_error.is_some()
+                            || autosave.as_mut().is_none_or(|autosave| autosave.save(model))
+                        {
+                            return Ok(());
+                        }
+                    }
+                    // Pick up timers the key started without waiting out an
+                    // idle tick
+                    if is_animating(model, autosave.as_ref()) {
+                        next_tick = next_tick.min(Instant::now() + TICK_RATE);
                     }
                 }
+                Some(Ok(Event::Resize(..))) => redraw = true,
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.into()),
+                None => return Ok(()),
+            },
+            _ = tokio::time::sleep_until(next_frame.into()), if redraw => {}
+            _ = tokio::time::sleep_until(next_tick.into()) => {
+                let status = model.messages.revision();
+                let overlay = mem::discriminant(&model.overlay);
+                let animating = model.animation.is_running();
+                apply(
+                    Msg::Tick,
+                    model,
+                    autosave.as_mut(),
+                    recorder.as_mut(),
+                    &mut journal,
+                    webhooks,
+                );
+                if let Some(autosave) = autosave.as_mut() {
+                    autosave.tick(model);
+                }
+                // A failed autosave brings up its overlay; a running filter
+                // job animates its spinner, list animations their frames
+                redraw |= model.messages.revision() != status
+                    || mem::discriminant(&model.overlay) != overlay
+                    || model.filter_job.is_some()
+                    || animating
+                    || last_draw.elapsed() >= CLOCK_REDRAW;
+                next_tick = Instant::now()
+                    + match is_animating(model, autosave.as_ref()) {
+                        true => TICK_RATE,
+                        false => IDLE_TICK_RATE,
+                    };
             }
         }
     }
 }
 
-fn key_event_to_msg(model: &Model, key: KeyCode) -> Msg {
-    match model.overlay {
-        Overlay::None => match model.mode {
-            Mode::List => match key {
-                KeyCode::Char('q') => Msg::SwitchMode(Mode::Quit),
-                KeyCode::Char('a') => Msg::SetOverlay(Overlay::AddingTask),
-                KeyCode::Char('A') => Msg::SetOverlay(Overlay::AddingSubtask),
-                KeyCode::Char('v') => Msg::SetOverlay(Overlay::View),
-                KeyCode::Char('f') => Msg::SetOverlay(Overlay::AddingFilterCriterion),
-                KeyCode::Char('c') => Msg::ToggleTaskCompletion,
-                KeyCode::Char('k') => Msg::NavigateTasks(Direction::Up),
-                KeyCode::Char('j') => Msg::NavigateTasks(Direction::Down),
-                KeyCode::Char('p') => Msg::SetOverlay(Overlay::Debug),
-                KeyCode::Char('g') => Msg::SetOverlay(Overlay::Navigation),
-                KeyCode::Char('C') => Msg::SwitchMode(Mode::Calendar),
-                KeyCode::Char('?') => Msg::SetOverlay(Overlay::Help),
-                _ => Msg::NoOp,
-            },
-            Mode::Calendar => match key {
-                KeyCode::Char('C') => Msg::SwitchMode(Mode::List),
-                _ => Msg::NoOp,
-            },
-            Mode::Quit => Msg::Quit,
-        },
-        Overlay::AddingTask | Overlay::AddingSubtask | Overlay::AddingFilterCriterion => {
-            match key {
+/// Whether anything is waiting on a timer shorter than the idle tick: a
+/// status message about to expire, a pending autosave or a filter job.
+fn is_animating(model: &Model, autosave: Option<&Autosave>) -> bool {
+    model.messages.expiring()
+        || model.filter_job.is_some()
+        || autosave.is_some_and(Autosave::is_pending)
+        || model.animation.is_running()
+}
+
+/// Runs `msg` through `update`, logs it when recording and hands the
+/// resulting events to subscribers.
+fn apply(
+    msg: Msg,
+    model: &mut Model,
+    autosave: Option<&mut Autosave>,
+    recorder: Option<&mut Recorder>,
+    journal: &mut Journal,
+    webhooks: &mut Webhooks,
+) {
+    match recorder {
+        Some(recorder) => {
+            update(msg.clone(), model);
+            if let Err(err) = recorder.record(&msg, &model.events) {
+                model.notify(Severity::Error, &format!("Recording failed: {}", err));
+            }
+        }
+        None => update(msg, model),
+    }
+    let mut subscribers: Vec<&mut dyn Subscriber> = vec![&mut *journal, &mut *webhooks];
+    if let Some(autosave) = autosave {
+        subscribers.push(autosave);
+    }
+    events::dispatch(model, &mut subscribers);
+    if let Some(error) = journal.error.take() {
+        model.notify(Severity::Error, &error);
+    }
+    for error in webhooks.failures() {
+        model.notify(Severity::Error, &error);
+    }
+}
+
+/// `chors capture`: a single input prompt whose text is added as a task.
+async fn capture(model: &mut Model) -> Result<()> {
+    let mut terminal = view::init()?;
+    update(Msg::SetOverlay(Overlay::AddingTask), model);
+    let mut events = EventStream::new();
+    let result = loop {
+        if let Err(err) = terminal.draw(|f| view::render_capture(f, model)) {
+            break Err(err.into());
+        }
+        match events.next().await {
+            Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => match key.code {
                 KeyCode::Enter => {
-                    if let Overlay::AddingTask = model.overlay {
-                        Msg::AddTask
-                    } else if let Overlay::AddingSubtask = model.overlay {
-                        Msg::AddSubtask
-                    } else {
-                        Msg::AddFilterCriterion
+                    let description = model.input.text().trim().to_string();
+                    if !description.is_empty() {
+                        model.task_history.push(&description);
+                        model.capture(Task::new(&description));
                     }
+                    break Ok(());
                 }
-                KeyCode::Esc => Msg::SetOverlay(Overlay::None),
-                KeyCode::Char(c) => Msg::PushChar(c),
-                KeyCode::Backspace => Msg::PopChar,
-                _ => Msg::NoOp,
-            }
+                KeyCode::Esc => break Ok(()),
+                _ => update(keys::input_key_to_msg(key), model),
+            },
+            Some(Ok(_)) => {}
+            Some(Err(err)) => break Err(err.into()),
+            None => break Ok(()),
+        }
+    };
+    update(Msg::SetOverlay(Overlay::None), model);
+    view::restore()?;
+    result
+}
+
+/// Loads the data file, printing a progress line to stderr for large files
+/// since this happens before the TUI is up.
+fn load_with_progress(file_path: &str, passphrase: Option<&str>) -> Result<Model> {
+    let mut last_percent = None;
+    let model = persistence::load_with_progress(file_path, passphrase, |read, total| {
+        if total < persistence::PROGRESS_THRESHOLD {
+            return;
+        }
+        let percent = read * 100 / total;
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            eprint!("\rLoading {}... {}%", file_path, percent);
+        }
+    })?;
+    if last_percent.is_some() {
+        eprintln!();
+    }
+    Ok(model)
+}
+
+fn print_startup_profile(profile: &StartupProfile) {
+    eprintln!("Startup profile for {} tasks:", profile.tasks);
+    eprintln!("{:<16} {:>10.2?}", "deserialize", profile.deserialize);
+    eprintln!("{:<16} {:>10.2?}", "filter", profile.filter);
+    match profile.first_draw {
+        Some(first_draw) => eprintln!("{:<16} {:>10.2?}", "first draw", first_draw),
+        None => eprintln!("{:<16} {:>10}", "first draw", "-"),
+    }
+}
+
+/// Asks for the data file passphrase on the terminal. Files that aren't
+/// encrypted yet get a confirmation prompt so a typo doesn't lock them out.
+fn prompt_passphrase(file_path: &str, existing: bool) -> Result<String> {
+    let passphrase = rpassword::prompt_password(format!("Passphrase for {}: ", file_path))?;
+    if !existing {
+        let confirmation = rpassword::prompt_password("Confirm passphrase: ")?;
+        if confirmation != passphrase {
+            bail!("passphrases do not match");
         }
-        Overlay::View => match key {
-            KeyCode::Enter => Msg::SaveCurrentView(model.input.clone()),
-            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
-            KeyCode::Char(c) => Msg::PushChar(c),
-            KeyCode::Backspace => Msg::PopChar,
-            _ => Msg::NoOp,
-        },
-        Overlay::Debug => match key {
-            KeyCode::Char('p') => Msg::SetOverlay(Overlay::None),
-            KeyCode::Char('j') => Msg::ScrollDebug(Direction::Down),
-            KeyCode::Char('k') => Msg::ScrollDebug(Direction::Up),
-            _ => Msg::NoOp,
-        },
-        Overlay::Navigation => match key {
-            KeyCode::Char('g') => Msg::HandleNavigation,
-            KeyCode::Char('e') => Msg::JumpToEnd,
-            KeyCode::Char(c) if c.is_ascii_digit() => Msg::PushChar(c),
-            KeyCode::Backspace => Msg::PopChar,
-            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
-            _ => Msg::NoOp,
-        },
-        Overlay::Help => match key {
-            KeyCode::Esc => Msg::SetOverlay(Overlay::None),
-            _ => Msg::NoOp,
-        },
     }
+    Ok(passphrase)
 }
 
 // TODO: add a calendar and time to tasks
@@ -111,38 +270,264 @@ fn key_event_to_msg(model: &Model, key: KeyCode) -> Msg {
 // TODO: improve ui visibility (colors, etc. inspiration dooit)
 // TODO: add a web ui with iced so I can use this on the phone...
 // TODO: add the ability to host from a server
-fn main() -> Result<()> {
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
     install_hooks()?;
 
-    let matches = cli::build_cli().get_matches();
-    let file_path = matches.get_one::<String>("file");
+    let aliases = match aliases::default_file() {
+        Some(path) => aliases::load(&path)?,
+        None => Default::default(),
+    };
+    let args = aliases::expand(std::env::args().collect(), &aliases);
+    let matches = cli::build_cli().get_matches_from(args);
 
-    let mut terminal = view::init()?;
+    // Replaying works on the log alone and must not touch the data file
+    if let Some(("replay", sub_matches)) = matches.subcommand() {
+        return commands::replay(
+            sub_matches.get_one::<String>("log").unwrap(),
+            sub_matches.get_one::<String>("output"),
+        );
+    }
+    let default_file = persistence::default_data_file();
+    let vault_dir = matches.get_one::<String>("vault");
+    let file_path = match vault_dir {
+        Some(_) => None,
+        None => matches.get_one::<String>("file").or(default_file.as_ref()),
+    };
+    let first_run = matches.get_one::<String>("file").is_none()
+        && file_path.is_some_and(|file_path| !Path::new(file_path).exists());
+    let read_only = matches.get_flag("read-only");
+    if read_only {
+        match file_path {
+            Some(file_path) if Path::new(file_path).exists() => (),
+            Some(file_path) => bail!("{} does not exist, there is nothing to browse", file_path),
+            None if vault_dir.is_some() => (),
+            None => bail!("--read-only requires a data file (-f)"),
+        }
+        if let Some((name @ ("done" | "add" | "capture"), _)) = matches.subcommand() {
+            bail!(
+                "`{}` changes the data file and cannot run with --read-only",
+                name
+            );
+        }
+    }
+
+    // Unlock the data file before entering the alternate screen
+    let mut encrypting = false;
+    let passphrase = match file_path {
+        Some(file_path) => {
+            let encrypted = persistence::is_encrypted(file_path)?;
+            if encrypted || matches.get_flag("encrypt") {
+                encrypting = !encrypted && Path::new(file_path).exists();
+                Some(prompt_passphrase(file_path, encrypted)?)
+            } else {
+                None
+            }
+        }
+        None if matches.get_flag("encrypt") => bail!("--encrypt requires a data file (-f)"),
+        None => None,
+    };
+
+    let zone = matches
+        .get_one::<String>("tz")
+        .map(|zone| dates::parse_zone(zone))
+        .transpose()?;
 
     // Load application state
-    let mut model = if let Some(file_path) = file_path {
-        if Path::new(file_path).exists() {
-            let data = fs::read_to_string(file_path)?;
-            let mut model: Model = serde_json::from_str(&data)?;
-            model.mode = Mode::List;
+    let loading = Instant::now();
+    let mut vault = None;
+    let mut model = match (file_path, vault_dir) {
+        (Some(file_path), _) => load_with_progress(file_path, passphrase.as_deref())?,
+        (None, Some(dir)) => {
+            let dates = dates::DateDisplay {
+                zone,
+                ..Default::default()
+            };
+            let (loaded, tasks) = Vault::load(Path::new(dir), &dates)?;
+            vault = Some(loaded);
+            let mut model = Model::new();
+            model.tasks = tasks;
             model
-        } else {
-            Model::new()
         }
+        (None, None) => Model::new(),
+    };
+    let deserialize = loading.elapsed();
+    model.data_file = file_path.cloned();
+    model.read_only = read_only;
+    model.author = matches
+        .get_one::<String>("author")
+        .cloned()
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .filter(|author| !author.trim().is_empty());
+    if let Some(path) = macros::default_file() {
+        model.filter_macros = macros::load(&path)?;
+    }
+    if let Some(path) = caldav::default_file() {
+        model.caldav_config = caldav::load(&path)?;
+    }
+    let mut webhooks = Webhooks::new(match webhooks::default_file() {
+        Some(path) => webhooks::load(&path)?,
+        None => Default::default(),
+    });
+    // Spelled-out markers replace colors in the accessible profile
+    model.accessible = matches.get_flag("accessible");
+    model.color_support = if matches.get_flag("no-color") || model.accessible {
+        ColorSupport::None
     } else {
-        Model::new()
+        view::detect_color_support()
     };
+    if let Some(zone) = zone {
+        model.date_display.zone = Some(zone);
+    }
+
+    // Create the default data file so its location exists from the start
+    if let (true, Some(file_path)) = (first_run, file_path) {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        persistence::save(file_path, &model, passphrase.as_deref())?;
+    }
+    // An existing plaintext file opened with --encrypt is rewritten encrypted
+    // right away rather than on the first edit
+    if let (true, false, Some(file_path)) = (encrypting, read_only, file_path) {
+        persistence::save(file_path, &model, passphrase.as_deref())?;
+    }
+
+    // Run a one-off command without entering the TUI
+    if let Some((name, sub_matches)) = matches.subcommand() {
+        match name {
+            "done" => commands::done(&mut model, sub_matches.get_one::<String>("id").unwrap())?,
+            "add" => {
+                let words: Vec<String> = sub_matches
+                    .get_many::<String>("description")
+                    .unwrap()
+                    .cloned()
+                    .collect();
+                commands::add(&mut model, &words)?;
+            }
+            "capture" => capture(&mut model).await?,
+            // Queries are read-only, so the data file is left as it is
+            "list" => {
+                let output = if sub_matches.get_flag("count") {
+                    commands::ListOutput::Count
+                } else if sub_matches.get_flag("quiet") {
+                    commands::ListOutput::Quiet
+                } else {
+                    commands::ListOutput::Tasks
+                };
+                let filter = sub_matches.get_one::<String>("filter").map(String::as_str);
+                let code = if sub_matches.get_flag("watch") {
+                    commands::watch_list(model, passphrase.as_deref(), filter, output)?
+                } else {
+                    commands::list(&mut model, filter, output)
+                };
+                process::exit(code);
+            }
+            // Serving only reads, so nothing is saved when it stops
+            "serve" => {
+                let addr = sub_matches.get_one::<String>("addr").unwrap();
+                let listener = TcpListener::bind(addr)?;
+                eprintln!("Serving http://{}/calendar.ics", listener.local_addr()?);
+                let dates = model.date_display.clone();
+                let passphrase = passphrase.as_deref();
+                return serve::run(listener, || {
+                    let mut model = match (file_path, vault_dir) {
+                        (Some(file_path), _) => {
+                            persistence::load_with_progress(file_path, passphrase, |_, _| {})?
+                        }
+                        (None, Some(dir)) => {
+                            let mut model = Model::new();
+                            model.tasks = Vault::load(Path::new(dir), &dates)?.1;
+                            model
+                        }
+                        (None, None) => model.clone(),
+                    };
+                    model.date_display = dates.clone();
+                    Ok(model)
+                });
+            }
+            "print" => process::exit(commands::print(
+                &model,
+                sub_matches.get_one::<String>("filter").map(String::as_str),
+                sub_matches.get_one::<usize>("width").copied(),
+            )),
+            _ => unreachable!("unknown subcommand {}", name),
+        }
+        let mut journal = Journal::default();
+        events::dispatch(&mut model, &mut [&mut journal, &mut webhooks]);
+        webhooks.finish();
+        for error in journal.error.into_iter().chain(webhooks.failures()) {
+            eprintln!("{}", error);
+        }
+        if let Some(file_path) = file_path {
+            persistence::save(file_path, &model, passphrase.as_deref())?;
+        } else if let Some(vault) = &mut vault {
+            vault.save(&model.tasks)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = scripting::default_dir() {
+        let (scripts, errors) = scripting::Scripts::load_dir(&dir);
+        model.scripts = scripts;
+        if !errors.is_empty() {
+            model.notify(
+                Severity::Error,
+                &format!("Failed to load scripts: {}", errors.join("; ")),
+            );
+        }
+    }
+    if first_run {
+        model.overlay = Overlay::Welcome;
+    } else if let Some(kind) = model.rollover_on_start.filter(|_| !read_only) {
+        update(Msg::Rollover(kind), &mut model);
+    }
+    if model.caldav_config.is_some() && !read_only {
+        update(Msg::Sync, &mut model);
+    }
+    // Filter up front, where it can be timed, instead of in the first draw
+    if matches.get_flag("profile-startup") {
+        let view = model.effective_view();
+        let filtering = Instant::now();
+        model.filter_now(&view);
+        let filter = filtering.elapsed();
+        model.startup_profile = Some(StartupProfile {
+            tasks: model.flattened().len(),
+            deserialize,
+            filter,
+            first_draw: None,
+        });
+        if filter > SLOW_FILTER_THRESHOLD {
+            model.overlay = Overlay::SlowStartup;
+        }
+    }
+
+    let mut terminal = view::init()?;
 
     // Run the application
-    let result = run_app(&mut terminal, &mut model);
+    let autosave = match vault {
+        _ if read_only => None,
+        Some(vault) => Some(Autosave::vault(vault)),
+        None => file_path.map(|file_path| Autosave::new(file_path, passphrase.as_deref())),
+    };
+    let recorder = match matches.get_one::<String>("record") {
+        Some(log) => Some(Recorder::start(log, &model)?),
+        None => None,
+    };
+    let result = run_app(&mut terminal, &mut model, autosave, recorder, &mut webhooks).await;
+    webhooks.finish();
 
     // Terminal closing
     view::restore()?;
+    if let Some(profile) = model.startup_profile {
+        print_startup_profile(&profile);
+    }
 
-    // Save application state if a file path was provided
-    if let Some(file_path) = file_path {
-        let data = serde_json::to_string_pretty(&model)?;
-        fs::write(file_path, data)?;
+    // Quitting saved already; save anyway if the session ended otherwise
+    let saved = read_only || matches!(model.mode, Mode::Quit);
+    if let (Some(file_path), false) = (file_path, saved) {
+        persistence::save(file_path, &model, passphrase.as_deref())?;
     }
 
     result
@@ -0,0 +1,157 @@
+//! Recording of the messages sent to `update`, and `chors replay` to rebuild
+//! the model from such a log.
+//!
+//! A log is a JSON line per record. Every TUI session starts with a snapshot
+//! of the model it was loaded with, followed by the messages in the order
+//! they were applied. Since new tasks get fresh ids on every run, each
+//! message also records the ids of the tasks it created, which lets the
+//! replay translate ids in later messages.
+//!
+//! Messages that reach outside the model, like opening URLs, exporting or
+//! running scripts, are skipped when replaying (see
+//! [`Msg::has_side_effects`]), so replaying a log from someone else can't
+//! open or write anything.
+
+use crate::view;
+use chors_core::{
+    events::Event,
+    model::{Model, Msg},
+    scripting,
+    update::update,
+};
+use chrono::{DateTime, Local};
+use color_eyre::{eyre::eyre, Result};
+use ratatui::{backend::TestBackend, Terminal};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    Session {
+        at: DateTime<Local>,
+        model: Box<Model>,
+    },
+    Message {
+        at: DateTime<Local>,
+        msg: Msg,
+        added: Vec<Uuid>,
+    },
+}
+
+/// Appends the messages of a session to a log file.
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Opens `path` for appending and starts a session from `model`.
+    pub fn start(path: &str, model: &Model) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut recorder = Self { file };
+        recorder.write(&Record::Session {
+            at: Local::now(),
+            model: Box::new(model.clone()),
+        })?;
+        Ok(recorder)
+    }
+
+    /// Logs `msg` along with the tasks it added, taken from the events it
+    /// emitted. Ticks and no-ops change nothing and are skipped.
+    pub fn record(&mut self, msg: &Msg, events: &[Event]) -> Result<()> {
+        if let Msg::Tick | Msg::NoOp = msg {
+            return Ok(());
+        }
+        let added = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::TaskAdded(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        self.write(&Record::Message {
+            at: Local::now(),
+            msg: msg.clone(),
+            added,
+        })
+    }
+
+    fn write(&mut self, record: &Record) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        writeln!(self.file, "{}", line)?;
+        Ok(())
+    }
+}
+
+/// What [`replay`] rebuilt from a log.
+pub struct Replay {
+    pub model: Model,
+    pub sessions: usize,
+    pub messages: usize,
+    /// Messages with side effects, left out.
+    pub skipped: usize,
+}
+
+/// Re-runs every recorded session through `update`. Each message is followed
+/// by a frame on an off-screen terminal, as in the TUI, since navigation
+/// depends on what the list last showed.
+pub fn replay(path: &str) -> Result<Replay> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut terminal = Terminal::new(TestBackend::new(120, 40))?;
+    let mut model = None;
+    let mut ids = HashMap::new();
+    let (mut sessions, mut messages, mut skipped) = (0, 0, 0);
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record =
+            serde_json::from_str(&line).map_err(|err| eyre!("{}:{}: {}", path, number + 1, err))?;
+        match record {
+            Record::Session { model: start, .. } => {
+                let model = model.insert(*start);
+                if let Some(dir) = scripting::default_dir() {
+                    model.scripts = scripting::Scripts::load_dir(&dir).0;
+                }
+                terminal.draw(|frame| view::ui(frame, model))?;
+                ids.clear();
+                sessions += 1;
+            }
+            Record::Message { msg, added, .. } => {
+                let model = model
+                    .as_mut()
+                    .ok_or_else(|| eyre!("{}: message before the first session", path))?;
+                if msg.has_side_effects() {
+                    skipped += 1;
+                    continue;
+                }
+                update(msg.map_ids(|id| *ids.get(&id).unwrap_or(&id)), model);
+                let replayed = model
+                    .take_events()
+                    .into_iter()
+                    .filter_map(|event| match event {
+                        Event::TaskAdded(id) => Some(id),
+                        _ => None,
+                    });
+                ids.extend(added.into_iter().zip(replayed));
+                terminal.draw(|frame| view::ui(frame, model))?;
+                messages += 1;
+            }
+        }
+    }
+
+    let model = model.ok_or_else(|| eyre!("{} contains no recorded session", path))?;
+    Ok(Replay {
+        model,
+        sessions,
+        messages,
+        skipped,
+    })
+}
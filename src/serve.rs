@@ -0,0 +1,85 @@
+//! `chors serve`: a small read-only HTTP server publishing the due tasks as
+//! `/calendar.ics`, for phone calendars to subscribe to. The tasks are read
+//! again for every request so the feed follows edits made elsewhere.
+//!
+//! `/calendar.ics?todos` serves the tasks as `VTODO`s instead of events, for
+//! calendars that show them.
+
+use chors_core::{caldav, model::Model};
+use chrono::Utc;
+use color_eyre::Result;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+/// The status line, content type and body answering `request_line`, e.g.
+/// `GET /calendar.ics HTTP/1.1`.
+pub fn respond(
+    request_line: &str,
+    load: &mut dyn FnMut() -> Result<Model>,
+) -> (&'static str, &'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if !matches!(method, "GET" | "HEAD") {
+        return ("405 Method Not Allowed", "text/plain", "Read only\n".into());
+    }
+    if path != "/calendar.ics" {
+        return ("404 Not Found", "text/plain", "Not found\n".into());
+    }
+    match load() {
+        Ok(model) => {
+            let todos = query.split('&').any(|param| param == "todos");
+            (
+                "200 OK",
+                "text/calendar; charset=utf-8",
+                caldav::feed(&model, todos, Utc::now()),
+            )
+        }
+        Err(error) => (
+            "500 Internal Server Error",
+            "text/plain",
+            format!("{}\n", error),
+        ),
+    }
+}
+
+fn handle(stream: TcpStream, load: &mut dyn FnMut() -> Result<Model>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers, nothing in them changes the answer
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+    let (status, content_type, body) = respond(&request_line, load);
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    if !request_line.starts_with("HEAD") {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+/// Answers requests on `listener` one at a time, until the process is
+/// stopped. A failed connection is reported and the next one served.
+pub fn run(listener: TcpListener, mut load: impl FnMut() -> Result<Model>) -> Result<()> {
+    for stream in listener.incoming() {
+        if let Err(error) = stream.and_then(|stream| handle(stream, &mut load)) {
+            eprintln!("Request failed: {}", error);
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,18 @@
-use crate::model::{Mode, Model, Overlay, Task, View};
-use chrono::Datelike;
+use chors_core::{
+    columns::{Column, ColumnSpec},
+    dates::{self, DateDisplay},
+    model::{
+        delegate, flatten_tasks, is_url, subtasks_at, ChangeKind, ColorSupport, CompletedStyle,
+        CompletionPolicy, Filter, GroupBy, ListState, Mode, Model, Overlay, Priority, SortKey,
+        Task, TimelineScale, View, ACTIVITY_WEEKS, SLOW_FILTER_THRESHOLD,
+    },
+    resolve,
+    rollover::{Rollover, OVERDUE_TAG},
+    status::Severity,
+    summary,
+    symbols::Symbols,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -7,22 +20,28 @@ use crossterm::{
 use indexmap::IndexMap;
 use ratatui::{
     backend::CrosstermBackend,
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{self, Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    env,
     io::{self, stdout, Stdout},
+    time::Instant,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use uuid::Uuid;
 
 type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 struct UIList<'a> {
-    pub items: Vec<ListItem<'a>>,
+    /// The tasks to list with their indentation. Lines are only built for
+    /// the ones that can be on screen.
+    pub rows: Vec<(&'a Task, usize)>,
     pub nav: IndexMap<Uuid, Vec<Uuid>>,
     pub tags: HashSet<String>,
     pub contexts: HashSet<String>,
@@ -43,18 +62,38 @@ pub fn ui(frame: &mut Frame, model: &mut Model) {
             model,
             Rect::new(size.x, size.y, size.width, available_height),
         ),
+        Mode::Planning => render_planning_mode(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Mode::Timeline => render_timeline_mode(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Mode::Stats => render_stats_mode(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
         Mode::Quit => {}
     }
 
     match model.overlay {
-        Overlay::None => {}
-        Overlay::AddingTask | Overlay::AddingSubtask | Overlay::AddingFilterCriterion => {
-            render_input_overlay(
-                frame,
-                model,
-                Rect::new(size.x, size.y, size.width, available_height),
-            )
-        }
+        Overlay::None | Overlay::Command => {}
+        Overlay::AddingTask
+        | Overlay::AddingSubtask
+        | Overlay::AddingFilterCriterion
+        | Overlay::AddingAttachment
+        | Overlay::MovingTask
+        | Overlay::CancellingTask
+        | Overlay::RenamingTag
+        | Overlay::MergingTag => render_input_overlay(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
         Overlay::View => render_view_overlay(
             frame,
             model,
@@ -69,6 +108,41 @@ pub fn ui(frame: &mut Frame, model: &mut Model) {
             frame,
             Rect::new(size.x, size.y, size.width, available_height),
         ),
+        Overlay::TaskForm => render_task_form_overlay(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Overlay::Confirm => render_confirm_overlay(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Overlay::SwitchingList => render_list_switcher_overlay(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Overlay::SaveFailed => render_save_failed_overlay(frame, model, size),
+        Overlay::Welcome => render_welcome_overlay(
+            frame,
+            model,
+            Rect::new(size.x, size.y, size.width, available_height),
+        ),
+        Overlay::Messages => render_messages_overlay(frame, model, size),
+        Overlay::History => render_history_overlay(frame, model, size),
+        Overlay::Comments => render_comments_overlay(frame, model, size),
+        Overlay::Rollover => render_rollover_overlay(frame, model, size),
+        Overlay::Summary => render_summary_overlay(frame, model, size),
+        Overlay::SlowStartup => render_slow_startup_overlay(frame, model, size),
+        Overlay::Jump => render_jump_overlay(frame, model, size),
+        Overlay::FilterSuggestions => render_filter_suggestions_overlay(frame, model, size),
+        Overlay::Views => render_views_overlay(frame, model, size),
+        Overlay::SettingMark => render_marks_overlay(frame, model, size, true),
+        Overlay::Marks => render_marks_overlay(frame, model, size, false),
+        Overlay::Tags => render_tags_overlay(frame, model, size),
+        Overlay::FocusPicker => render_focus_overlay(frame, model, size),
+        Overlay::Columns => render_columns_overlay(frame, model, size),
         Overlay::Debug => render_debug_overlay(
             frame,
             model,
@@ -77,6 +151,134 @@ pub fn ui(frame: &mut Frame, model: &mut Model) {
     }
 
     render_taskbar(frame, model, size);
+    degrade_colors(frame.buffer_mut(), model.color_support);
+    if model.accessible {
+        asciify(frame.buffer_mut());
+    }
+}
+
+/// The configured glyphs, or spelled-out markers in the accessible profile.
+fn symbols(model: &Model) -> Symbols {
+    match model.accessible {
+        true => Symbols::accessible(),
+        false => model.symbols.clone(),
+    }
+}
+
+/// Replaces the box-drawing and block glyphs the views draw with ASCII.
+/// Text typed by the user is left alone.
+fn asciify(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        let ascii = match cell.symbol() {
+            "─" | "━" => "-",
+            "│" | "┃" | "▕" | "▏" => "|",
+            "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" => "+",
+            "█" | "■" => "#",
+            "▌" => "=",
+            "·" => ".",
+            "•" | "◆" => "*",
+            "▶" => ">",
+            "◀" => "<",
+            "…" => "~",
+            "☐" => "o",
+            "☑" => "x",
+            _ => continue,
+        };
+        cell.set_symbol(ascii);
+    }
+}
+
+/// What the environment says about colors: `NO_COLOR` or a dumb terminal
+/// turn them off, and terminals that don't advertise 256 colors get the 16
+/// basic ones.
+pub fn detect_color_support() -> ColorSupport {
+    let var = |name| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    if !var("NO_COLOR").is_empty() || term == "dumb" {
+        ColorSupport::None
+    } else if term.contains("256color")
+        || matches!(var("COLORTERM").as_str(), "truecolor" | "24bit")
+    {
+        ColorSupport::Indexed
+    } else {
+        ColorSupport::Basic
+    }
+}
+
+/// Rewrites the finished frame for terminals with fewer colors than the
+/// views are written for. Without colors, backgrounds (selections, the
+/// taskbar) become reversed text so they stay visible.
+fn degrade_colors(buffer: &mut Buffer, support: ColorSupport) {
+    if support == ColorSupport::Indexed {
+        return;
+    }
+    for cell in buffer.content.iter_mut() {
+        match support {
+            ColorSupport::Indexed => {}
+            ColorSupport::Basic => {
+                cell.fg = basic_color(cell.fg);
+                cell.bg = basic_color(cell.bg);
+            }
+            ColorSupport::None => {
+                if cell.bg != Color::Reset {
+                    cell.modifier |= Modifier::REVERSED;
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
+
+/// The nearest of the 16 named colors.
+fn basic_color(color: Color) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let (r, g, b) = match color {
+        Color::Indexed(index @ 0..=15) => return NAMED[index as usize],
+        Color::Indexed(index @ 16..=231) => {
+            let index = (index - 16) as usize;
+            (CUBE[index / 36], CUBE[index / 6 % 6], CUBE[index % 6])
+        }
+        Color::Indexed(index) => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+        Color::Rgb(r, g, b) => (r, g, b),
+        color => return color,
+    };
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    if max - min < 40 {
+        return match max {
+            0..=63 => Color::Black,
+            64..=127 => Color::DarkGray,
+            128..=199 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    // Channels over half as bright as the brightest pick the hue
+    let bit = |channel: u8| (channel > max / 2) as usize;
+    let hue = bit(r) | bit(g) << 1 | bit(b) << 2;
+    let bright = if max > 200 { 8 } else { 0 };
+    NAMED[hue + bright]
 }
 
 fn render_taskbar(frame: &mut Frame, model: &Model, size: Rect) {
@@ -92,46 +294,642 @@ fn render_taskbar(frame: &mut Frame, model: &Model, size: Rect) {
     );
     let input_area = Rect::new(size.x, size.height - input_height, size.width, input_height);
 
-    let info_paragraph = Paragraph::new(Span::from(model.taskbar_info.clone()))
+    let mut status = String::new();
+    // The count is for the list's filters; other modes don't refresh them
+    if let (Mode::List, Some(count)) = (&model.mode, model.match_count()) {
+        status.push_str(&format!(" {}", count));
+    }
+    if model.read_only {
+        status.push_str(" [read-only]");
+    }
+    if let Some(context) = &model.focus_context {
+        status.push_str(&format!(" [focus: {}]", context));
+    }
+    if !model.zoom.is_empty() {
+        status.push_str(" [zoomed] (Backspace/Esc: out)");
+    }
+    if !model.current_view.display.show_completed {
+        status.push_str(" [completed hidden]");
+    }
+    if let Some(job) = &model.filter_job {
+        status.push_str(&format!(" {} filtering…", job.spinner()));
+    }
+    // The selection's ancestors keep deep tasks in context when their parents
+    // are scrolled off or filtered out; the zoom root stands in when nothing
+    // is selected
+    let path = model.get_path();
+    let crumbs = model.path_descriptions(if path.is_empty() { &model.zoom } else { &path });
+    let mut info_text = model.taskbar_info.clone();
+    if !crumbs.is_empty() {
+        let room = (size.width as usize).saturating_sub(info_text.width() + status.width() + 1);
+        info_text.push(' ');
+        info_text.push_str(&breadcrumb(&crumbs, room));
+    }
+    info_text.push_str(&status);
+    let info_paragraph = Paragraph::new(Span::from(info_text))
         .style(Style::default().bg(Color::DarkGray).fg(Color::White));
 
-    let input_text = if model.command_input.starts_with(':') {
-        model.command_input.clone()
+    let input_paragraph = if let Overlay::Command = model.overlay {
+        Paragraph::new(format!(":{}", model.input.text()))
+    } else if model.command_input.starts_with(':') {
+        Paragraph::new(model.command_input.clone())
+    } else if let Some(message) = model.messages.current() {
+        let mut text = message.text.clone();
+        let waiting = model.messages.waiting();
+        if waiting > 0 {
+            text.push_str(&format!(" (+{} more)", waiting));
+        }
+        Paragraph::new(text).style(severity_style(message.severity))
     } else {
-        model.taskbar_message.clone()
+        Paragraph::new("")
     };
 
-    let input_paragraph = Paragraph::new(Span::from(input_text));
-
     frame.render_widget(info_paragraph, info_area);
     frame.render_widget(input_paragraph, input_area);
+
+    if let Overlay::Command = model.overlay {
+        frame.set_cursor(
+            input_area.x + model.input.cursor_width() as u16 + 1,
+            input_area.y,
+        );
+    }
+
+    if let Some(count) = model.pending_count {
+        let count_paragraph = Paragraph::new(count.to_string()).alignment(Alignment::Right);
+        frame.render_widget(count_paragraph, input_area);
+    }
 }
 
 fn render_list_mode(frame: &mut Frame, model: &mut Model, size: Rect) {
-    let ui_list = build_task_list(&model.tasks, Vec::new(), &model.current_view, false, 0);
+    let size = if model.show_sidebar {
+        let sidebar_width = 24.min(size.width / 2);
+        render_sidebar(
+            frame,
+            model,
+            Rect::new(size.x, size.y, sidebar_width, size.height),
+        );
+        Rect::new(
+            size.x + sidebar_width,
+            size.y,
+            size.width - sidebar_width,
+            size.height,
+        )
+    } else {
+        size
+    };
+
+    let id_column = model.columns.iter().any(|spec| spec.column == Column::Id);
+    let short_ids = if model.show_short_ids || id_column {
+        resolve::short_ids(model.flattened())
+    } else {
+        HashMap::new()
+    };
+    model.refresh_filter_preview();
+    let view = model.effective_view();
+    model.refresh_match_cache(&view);
+    // A matching ancestor of the zoom root shows the whole zoomed subtree, as
+    // it would unzoomed
+    let zoom_match = model
+        .zoom
+        .iter()
+        .any(|id| model.match_cache.get(id).copied().unwrap_or(false));
+    let symbols = symbols(model);
+    let context = ListContext {
+        view: &view,
+        matches: &model.match_cache,
+        dates: &model.date_display,
+        completion: model.completion_policy,
+        symbols: &symbols,
+        completed_style: model.completed_style,
+        accessible: model.accessible,
+        focus: model.focus_context.as_deref(),
+        columns: &model.columns,
+    };
+    let ui_list = build_task_list(
+        subtasks_at(&model.tasks, &model.zoom),
+        model.zoom.clone(),
+        &context,
+        zoom_match,
+        0,
+    );
     model.nav = ui_list.nav;
     model.tags = ui_list.tags;
     model.contexts = ui_list.contexts;
+    model
+        .list_state
+        .select(model.selected.and_then(|id| model.nav.get_index_of(&id)));
 
-    // TODO: make these wrap into the area at some point (right now they cut off)
-    let list = List::new(ui_list.items)
-        .block(Block::default().borders(Borders::ALL).title("Tasks"))
+    let selected = model.list_state.selected();
+    let total = ui_list.rows.len();
+    // Lines are only built for rows that can be on screen. The list scrolls
+    // from the offset until the selection shows, and no more rows than it is
+    // tall fit below either.
+    let height = size.height.saturating_sub(2) as usize;
+    let offset = model.list_state.offset().min(total.saturating_sub(1));
+    // With animations on, a jump more than a screen away scrolls there over a
+    // few frames, drawing the list at the scroll's offset until it arrives
+    let now = Instant::now();
+    if let Some(selected) = selected.filter(|_| model.animations) {
+        let target = if selected < offset {
+            selected
+        } else {
+            (selected + 1).saturating_sub(height).max(offset)
+        };
+        if target.abs_diff(offset) > height && model.animation.scroll_offset(now).is_none() {
+            model.animation.scroll(offset, target, now);
+        }
+    }
+    let scrolling = model
+        .animation
+        .scroll_offset(now)
+        .filter(|_| model.animations);
+    let offset = scrolling.unwrap_or(offset).min(total.saturating_sub(1));
+    let anchor = selected.unwrap_or(offset);
+    let first = offset.min(anchor);
+    let end = (offset.max(anchor) + height + 1).min(total);
+    let number_width = total.to_string().len();
+    let wrap_width = size.width.saturating_sub(2) as usize;
+    let columns = &model.columns;
+    let at = columns
+        .iter()
+        .position(|spec| spec.column == Column::Description)
+        .unwrap_or(columns.len());
+    let (before, after) = (&columns[..at], columns.get(at + 1..).unwrap_or_default());
+    let items: Vec<ListItem> = ui_list.rows[first..end]
+        .iter()
+        .enumerate()
+        .map(|(index, &(task, depth))| {
+            let index = first + index;
+            let short_id = model
+                .nav
+                .get_index(index)
+                .and_then(|(id, _)| short_ids.get(id))
+                .map(String::as_str);
+            let cells = |specs: &[ColumnSpec]| -> Vec<Span> {
+                specs
+                    .iter()
+                    .map(|spec| column_cell(spec, task, &context, short_id))
+                    .collect()
+            };
+            let (before, after) = (cells(before), cells(after));
+            let mut line = task_line(task, &context, depth);
+            // Columns after the description need it cut to a fixed width
+            let width = columns.get(at).and_then(|spec| spec.width);
+            if width.is_some() || !after.is_empty() {
+                let used: usize = before
+                    .iter()
+                    .chain(&after)
+                    .map(|span| span.width())
+                    .sum::<usize>()
+                    + number_width
+                    + short_id
+                        .filter(|_| model.show_short_ids)
+                        .map_or(0, |id| id.width() + 1);
+                let width = width.map_or(wrap_width.saturating_sub(used), usize::from);
+                line = Line::from(fit_spans(line.spans, width));
+            }
+            let mut prefix_spans = TASK_PREFIX_SPANS + 1 + before.len();
+            line.spans.splice(0..0, before);
+            line.spans.extend(after);
+            let number = match selected {
+                Some(selected) if model.relative_line_numbers && index != selected => {
+                    index.abs_diff(selected)
+                }
+                _ => index + 1,
+            };
+            line.spans.insert(
+                0,
+                Span::styled(
+                    format!("{:>width$}", number, width = number_width),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            );
+            if let Some(short_id) = short_id.filter(|_| model.show_short_ids) {
+                line.spans.insert(
+                    1,
+                    Span::styled(format!(" {}", short_id), Style::default().fg(Color::Blue)),
+                );
+                prefix_spans += 1;
+            }
+            let hang = line.spans[..prefix_spans.min(line.spans.len())]
+                .iter()
+                .map(|span| span.width())
+                .sum();
+            let item = ListItem::new(wrap_line(line, wrap_width, hang));
+            if model.animation.is_flashing(task.id, now) {
+                item.style(Style::default().fg(Color::Black).bg(Color::Yellow))
+            } else {
+                item
+            }
+        })
+        .collect();
+
+    let display = &model.current_view.display;
+    let mut title = model.current_list.clone();
+    if model.review_stash.is_some() {
+        title.push_str(" | done today & yesterday (T: back)");
+    }
+    if display.sort != SortKey::Manual {
+        title.push_str(&format!(" | sort: {}", display.sort.as_str()));
+    }
+    if display.group_by != GroupBy::None {
+        title.push_str(&format!(" | group: {}", display.group_by.as_str()));
+    }
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().bg(Color::Indexed(8)));
 
-    frame.render_stateful_widget(list, size, &mut model.list_state);
+    match scrolling {
+        // Highlighting a selection that is still off screen would make the
+        // list scroll to it at once
+        Some(offset) => {
+            model.list_state.set_offset(offset);
+            let shown =
+                selected.filter(|&selected| selected >= offset && selected < offset + height);
+            model.list_state.select(shown);
+            render_list_window(frame, list, size, &mut model.list_state, first);
+            model.list_state.select(selected);
+        }
+        None => render_list_window(frame, list, size, &mut model.list_state, first),
+    }
+}
+
+/// Draws `list` with the model's list state, keeping the scroll offset
+/// ratatui settles on so the next frame scrolls from the same place.
+fn render_list(frame: &mut Frame, list: List, area: Rect, state: &mut ListState) {
+    render_list_window(frame, list, area, state, 0);
+}
+
+/// [`render_list`] for a `list` holding only the rows from index `first` on.
+fn render_list_window(
+    frame: &mut Frame,
+    list: List,
+    area: Rect,
+    state: &mut ListState,
+    first: usize,
+) {
+    let mut widget_state = widgets::ListState::default()
+        .with_offset(state.offset().saturating_sub(first))
+        .with_selected(state.selected().map(|selected| selected - first));
+    frame.render_stateful_widget(list, area, &mut widget_state);
+    state.set_offset(widget_state.offset() + first);
+}
+
+fn render_jump_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    frame.render_widget(Clear, area);
+    let block = Block::default().borders(Borders::ALL).title("Jump to task");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [input_area, results_area, hint_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(inner);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            "Enter: go, Up/Down: pick, Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+        hint_area,
+    );
+
+    let query = model.input.text();
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::DarkGray)),
+            Span::styled(query, Style::default().fg(Color::Yellow)),
+        ])),
+        input_area,
+    );
+    frame.set_cursor(
+        input_area.x + 2 + model.input.cursor_width() as u16,
+        input_area.y,
+    );
+
+    let width = results_area.width as usize;
+    let items: Vec<ListItem> = model
+        .jump_results(query)
+        .iter()
+        .filter_map(|path| {
+            let task = model.get_task(path)?;
+            let mut spans = vec![Span::raw(task.description.clone())];
+            let ancestors = model.path_descriptions(&path[..path.len() - 1]);
+            if !ancestors.is_empty() {
+                let room = width.saturating_sub(task.description.width() + 3);
+                spans.push(Span::styled(
+                    format!("  {}", breadcrumb(&ancestors, room)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Some(ListItem::new(Line::from(spans)))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().bg(Color::Indexed(8)));
+    render_list(frame, list, results_area, &mut model.jump_state);
+}
+
+fn render_views_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let area = centered_rect(60, 60, size);
+    let items: Vec<ListItem> = model
+        .saved_views
+        .keys()
+        .map(|name| {
+            // Shown as pressed: plain digits are counts
+            let key = model
+                .view_keys
+                .iter()
+                .find(|(_, view)| *view == name)
+                .map_or("     ".to_string(), |(&key, _)| format!("Alt-{}", key));
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}  ", key), Style::default().fg(Color::Yellow)),
+                Span::raw(name.clone()),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Views (Enter: load, 1-9: bind)"),
+        )
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+    frame.render_widget(Clear, area);
+    render_list(frame, list, area, &mut model.views_state);
+}
+
+fn render_filter_suggestions_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(60, 40, size);
+    let items: Vec<ListItem> = model
+        .filter_suggestions()
+        .into_iter()
+        .enumerate()
+        .map(|(index, filter)| {
+            let color = match filter {
+                Filter::Tag(_) => Color::Magenta,
+                Filter::Context(_) => Color::Cyan,
+                Filter::Waiting(_) => Color::LightBlue,
+                _ => Color::Yellow,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}  ", index + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(filter.to_string(), Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+    let title = if items.is_empty() {
+        "Narrow To (no task selected, Esc: close)"
+    } else {
+        "Narrow To (1-9: apply, Esc: close)"
+    };
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(Clear, area);
+    frame.render_widget(list, area);
+}
+
+/// The marks with their tasks, for both picking a register to set and one
+/// to jump to.
+fn render_marks_overlay(frame: &mut Frame, model: &mut Model, size: Rect, setting: bool) {
+    let area = centered_rect(60, 50, size);
+    let marks: Vec<(char, Uuid)> = model.marks.iter().map(|(&r, &id)| (r, id)).collect();
+    let width = area.width.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = marks
+        .into_iter()
+        .map(|(register, id)| {
+            let mut spans = vec![Span::styled(
+                format!("{}  ", register),
+                Style::default().fg(Color::Yellow),
+            )];
+            match model.path_of(id) {
+                Some(path) => {
+                    let task = model
+                        .get_task(&path)
+                        .expect("path_of returns existing tasks");
+                    spans.push(Span::raw(task.description.clone()));
+                    let ancestors = model.path_descriptions(&path[..path.len() - 1]);
+                    if !ancestors.is_empty() {
+                        let room = width.saturating_sub(task.description.width() + 6);
+                        spans.push(Span::styled(
+                            format!("  {}", breadcrumb(&ancestors, room)),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+                None => spans.push(Span::styled(
+                    "(deleted)",
+                    Style::default().fg(Color::DarkGray),
+                )),
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let title = match (setting, items.is_empty()) {
+        (true, _) => "Set Mark (a-z: register, Esc: cancel)",
+        (false, true) => "Marks (none yet, M<a-z> sets one, Esc: close)",
+        (false, false) => "Marks (a-z: jump, Esc: close)",
+    };
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(Clear, area);
+    frame.render_widget(list, area);
+}
+
+fn render_tags_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let area = centered_rect(50, 60, size);
+    let items: Vec<ListItem> = model
+        .tag_counts()
+        .into_iter()
+        .map(|(tag, count)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(tag, Style::default().fg(Color::Magenta)),
+                Span::styled(
+                    format!(" ({})", count),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+    let title = if items.is_empty() {
+        "Tags (none yet, Esc: close)"
+    } else {
+        "Tags (r: rename, m: merge, d: delete, Esc: close)"
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+    frame.render_widget(Clear, area);
+    render_list(frame, list, area, &mut model.tag_state);
+}
+
+fn render_focus_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let area = centered_rect(40, 50, size);
+    let items: Vec<ListItem> = model
+        .focus_choices()
+        .into_iter()
+        .map(|choice| {
+            let marker = if choice == model.focus_context {
+                "* "
+            } else {
+                "  "
+            };
+            let label = match choice {
+                Some(context) => Span::styled(context, Style::default().fg(Color::Cyan)),
+                None => Span::raw("No focus"),
+            };
+            ListItem::new(Line::from(vec![Span::raw(marker), label]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Focus Context (Enter: choose, Esc: close)"),
+        )
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+    frame.render_widget(Clear, area);
+    render_list(frame, list, area, &mut model.focus_state);
+}
+
+fn render_columns_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let area = centered_rect(50, 50, size);
+    let items: Vec<ListItem> = model
+        .column_choices()
+        .into_iter()
+        .map(|column| {
+            let spec = model.columns.iter().find(|spec| spec.column == column);
+            let marker = if spec.is_some() { "[x] " } else { "[ ] " };
+            let width = match spec.map(ColumnSpec::width) {
+                Some(Some(width)) => format!("{} cells", width),
+                Some(None) => "fills the row".to_string(),
+                None => String::new(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::raw(format!("{:<12}", column.as_str())),
+                Span::styled(width, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Columns (Space: show/hide, J/K: move, +/-: width, Esc: close)"),
+        )
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+    frame.render_widget(Clear, area);
+    render_list(frame, list, area, &mut model.column_state);
+}
+
+fn render_sidebar(frame: &mut Frame, model: &mut Model, size: Rect) {
+    let items: Vec<ListItem> = model
+        .sidebar_entries()
+        .into_iter()
+        .map(|(filter, count)| {
+            let (label, color) = match &filter {
+                Filter::Tag(tag) => (tag.as_str(), Color::Magenta),
+                Filter::Context(context) => (context.as_str(), Color::Cyan),
+                Filter::Completed(_) | Filter::CompletedDaysAgo { .. } => {
+                    ("completed", Color::Green)
+                }
+                Filter::Cancelled => ("cancelled", Color::DarkGray),
+                Filter::Waiting(_) => ("waiting", Color::LightBlue),
+                Filter::CreatedDaysAgo { .. } => ("created", Color::White),
+                Filter::Due(_) => ("due", Color::Yellow),
+                Filter::Text { .. } => ("text", Color::White),
+                Filter::Under { .. } => ("under", Color::White),
+                Filter::Snoozed(_) => ("snoozed", Color::DarkGray),
+                Filter::By(_) => ("by", Color::White),
+                Filter::Meta { .. } => ("meta", Color::White),
+                Filter::Not(_) => ("not", Color::White),
+            };
+            let marker = if model.sidebar_filter.as_ref() == Some(&filter) {
+                "* "
+            } else {
+                "  "
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(label.to_string(), Style::default().fg(color)),
+                Span::styled(
+                    format!(" ({})", count),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let border_style = if model.sidebar_focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title("Tags & Contexts"),
+        )
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+
+    render_list(frame, list, size, &mut model.sidebar_state);
 }
 
 // TODO: swap this to tui-textarea at some point
 fn render_input_overlay(frame: &mut Frame, model: &Model, size: Rect) {
     let area = centered_rect(50, 20, size);
-    let input_block = Block::default().borders(Borders::ALL).title("New Task");
-    let input_paragraph = Paragraph::new(model.input.as_str())
+    let tag = model.selected_tag().unwrap_or_default();
+    let title = match model.overlay {
+        Overlay::AddingSubtask => "New Subtask".to_string(),
+        Overlay::AddingFilterCriterion => "Filter Criterion".to_string(),
+        Overlay::AddingAttachment => "Attach File (Tab: complete)".to_string(),
+        Overlay::MovingTask => "Move Under (line or id, empty: top level)".to_string(),
+        Overlay::CancellingTask => "Cancel Task (reason, optional)".to_string(),
+        Overlay::RenamingTag => format!("Rename {} to", tag),
+        Overlay::MergingTag => format!("Merge {} into", tag),
+        _ => "New Task".to_string(),
+    };
+    let input_block = Block::default().borders(Borders::ALL).title(title);
+    let input = model.input.text();
+    let input_style = Style::default().fg(Color::Yellow);
+    let input_text = match (&model.overlay, &model.filter_error) {
+        // The error may refer to an older input while typing; only underline
+        // when its span still falls on character boundaries.
+        (Overlay::AddingFilterCriterion, Some(error))
+            if input.is_char_boundary(error.offset.min(input.len()))
+                && input.is_char_boundary((error.offset + error.len).min(input.len())) =>
+        {
+            let start = error.offset.min(input.len());
+            let end = (error.offset + error.len).min(input.len());
+            let (before, rest) = input.split_at(start);
+            let (offending, after) = rest.split_at(end - start);
+            Text::from(vec![
+                Line::from(vec![
+                    Span::styled(before, input_style),
+                    Span::styled(
+                        offending,
+                        Style::default()
+                            .fg(Color::Red)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    Span::styled(after, input_style),
+                ]),
+                Line::styled(error.to_string(), Style::default().fg(Color::Red)),
+            ])
+        }
+        _ => Text::styled(input, input_style),
+    };
+    let input_paragraph = Paragraph::new(input_text)
         .block(input_block)
-        .style(Style::default().fg(Color::Yellow))
         .wrap(Wrap { trim: false });
     frame.render_widget(input_paragraph, area);
 
-    let cursor_x = area.x + model.input.len() as u16 + 1;
+    let cursor_x = area.x + model.input.cursor_width() as u16 + 1;
     let cursor_y = area.y + 1;
     frame.set_cursor(cursor_x, cursor_y);
 }
@@ -139,19 +937,207 @@ fn render_input_overlay(frame: &mut Frame, model: &Model, size: Rect) {
 fn render_view_overlay(frame: &mut Frame, model: &Model, size: Rect) {
     let area = centered_rect(50, 20, size);
     let input_block = Block::default().borders(Borders::ALL).title("View Name");
-    let input_paragraph = Paragraph::new(model.input.as_str())
+    let input_paragraph = Paragraph::new(model.input.text())
         .block(input_block)
         .style(Style::default().fg(Color::Yellow));
     frame.render_widget(input_paragraph, area);
 
-    let cursor_x = area.x + model.input.len() as u16 + 1;
+    let cursor_x = area.x + model.input.cursor_width() as u16 + 1;
+    let cursor_y = area.y + 1;
+    frame.set_cursor(cursor_x, cursor_y);
+}
+
+fn render_task_form_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(60, 50, size);
+    let title = if model.form_target.is_some() {
+        "Edit Task"
+    } else {
+        "New Task"
+    };
+    let form_block = Block::default().borders(Borders::ALL).title(title);
+
+    let label_width = 13;
+    let mut lines = Vec::new();
+    let mut cursor = (area.x + 1, area.y + 1);
+    for (index, field) in model.form.fields.iter().enumerate() {
+        let focused = index == model.form.focus;
+        let label_style = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        if focused {
+            cursor = (
+                area.x + 1 + label_width + field.input.cursor_width() as u16,
+                area.y + 1 + lines.len() as u16,
+            );
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{:<width$}",
+                    format!("{}:", field.label),
+                    width = label_width as usize
+                ),
+                label_style,
+            ),
+            Span::raw(field.input.text()),
+        ]));
+        if let Some(error) = &field.error {
+            lines.push(Line::from(Span::styled(
+                format!("{:width$}{}", "", error, width = label_width as usize),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Tab/S-Tab: Switch field, Enter: Save, Esc: Cancel",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(form_block), area);
+    frame.set_cursor(cursor.0, cursor.1);
+}
+
+fn render_confirm_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(50, 20, size);
+    let prompt = match &model.pending_confirmation {
+        Some((prompt, _)) => prompt.as_str(),
+        None => "",
+    };
+    let confirm_text = vec![
+        Line::from(Span::raw(prompt)),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y/Enter: Confirm, n/Esc: Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let confirm_paragraph = Paragraph::new(confirm_text)
+        .block(Block::default().borders(Borders::ALL).title("Confirm"))
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(Clear, area);
+    frame.render_widget(confirm_paragraph, area);
+}
+
+fn render_save_failed_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(60, 30, size);
+    let error = model.save_error.as_deref().unwrap_or_default();
+    let mut text = vec![
+        Line::from(Span::raw(error)),
+        Line::from(""),
+        Line::from(Span::raw(
+            "Your changes are still here; the next change tries again.",
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter/Esc: Back to the list, Q: Quit without saving",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    if error.contains("changed on disk") {
+        text.push(Line::from(Span::styled(
+            "r: Reload the changed notes, dropping the edits made here to them",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("Save failed"))
+        .style(Style::default().fg(Color::Red))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_list_switcher_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(50, 40, size);
+    let input = model.input.text();
+    let mut lines = vec![Line::from(Span::styled(
+        input,
+        Style::default().fg(Color::Yellow),
+    ))];
+    lines.push(Line::from(""));
+    for name in model.list_names() {
+        let style = if name == model.current_list {
+            Style::default().fg(Color::Green)
+        } else if !input.is_empty() && name.starts_with(input) {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(name, style)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: switch (a new name creates a list), Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    )));
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Switch List"))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+
+    let cursor_x = area.x + model.input.cursor_width() as u16 + 1;
     let cursor_y = area.y + 1;
     frame.set_cursor(cursor_x, cursor_y);
 }
 
+fn render_welcome_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(60, 30, size);
+    let data_file = model.data_file.as_deref().unwrap_or("nowhere");
+    let welcome_text = vec![
+        Line::from(Span::raw("Welcome to chors!")),
+        Line::from(""),
+        Line::from(Span::raw("Your tasks are saved to:")),
+        Line::from(Span::styled(data_file, Style::default().fg(Color::Yellow))),
+        Line::from(Span::raw("Pass -f <FILE> to use a different file.")),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Press any key to start, ? for help",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let welcome_paragraph = Paragraph::new(welcome_text)
+        .block(Block::default().borders(Borders::ALL).title("Welcome"))
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(Clear, area);
+    frame.render_widget(welcome_paragraph, area);
+}
+
+fn render_slow_startup_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(60, 30, size);
+    let profile = model.startup_profile.unwrap_or_default();
+    let text = vec![
+        Line::from(Span::raw(format!(
+            "Filtering {} tasks took {:.0?} at startup (more than {:.0?}).",
+            profile.tasks, profile.filter, SLOW_FILTER_THRESHOLD
+        ))),
+        Line::from(""),
+        Line::from(Span::raw(
+            "The full startup timings are printed when chors exits.",
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Press any key to continue",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("Slow startup"))
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
 fn render_navigation_overlay(frame: &mut Frame, model: &Model, size: Rect) {
     let navigation_width = 30;
-    let navigation_height = 6;
+    let navigation_height = 7;
     let area = Rect::new(
         size.width.saturating_sub(navigation_width + 1),
         size.height.saturating_sub(navigation_height + 1),
@@ -167,14 +1153,15 @@ fn render_navigation_overlay(frame: &mut Frame, model: &Model, size: Rect) {
         ]),
         Line::from(Span::raw("Options:")),
         Line::from(Span::raw("<n>g: Go to line <n>")),
-        Line::from(Span::raw("e: Go to last line")),
+        Line::from(Span::raw("g: Go to first line")),
+        Line::from(Span::raw("e/G: Go to last line")),
     ];
     let navigation_paragraph = Paragraph::new(navigation_text)
         .block(navigation_block)
         .style(Style::default().fg(Color::White));
     frame.render_widget(navigation_paragraph, area);
 
-    let cursor_x = area.x + model.navigation_input.len() as u16 + 13;
+    let cursor_x = area.x + model.navigation_input.width() as u16 + 13;
     let cursor_y = area.y + 1;
     frame.set_cursor(cursor_x, cursor_y);
 }
@@ -189,16 +1176,109 @@ fn render_help_overlay(frame: &mut Frame, size: Rect) {
         Line::from(Span::raw("q: Quit")),
         Line::from(Span::raw("a: Add Task")),
         Line::from(Span::raw("A: Add Subtask")),
+        Line::from(Span::raw("n: New Task (form)")),
+        Line::from(Span::raw("e: Edit Task (form)")),
         Line::from(Span::raw("v: View Mode")),
         Line::from(Span::raw("f: Add Filter Criterion")),
         Line::from(Span::raw("c: Toggle Task Completion")),
-        Line::from(Span::raw("k: Navigate Up")),
-        Line::from(Span::raw("j: Navigate Down")),
+        Line::from(Span::raw("d: Delete Task")),
+        Line::from(Span::raw("D: Duplicate Task")),
+        Line::from(Span::raw("o: Open URL in Task")),
+        Line::from(Span::raw("P: Attach File, O: Open Attachment")),
+        Line::from(Span::raw("X: Complete All Visible Tasks")),
+        Line::from(Span::raw("<n>k: Navigate Up (n times)")),
+        Line::from(Span::raw("<n>j: Navigate Down (n times)")),
         Line::from(Span::raw("p: Debug Overlay")),
         Line::from(Span::raw("g: Navigation Mode")),
+        Line::from(Span::raw("<n>G: Go to line <n> (default: last)")),
+        Line::from(Span::raw("N: Toggle relative line numbers")),
+        Line::from(Span::raw("I: Toggle short task ids")),
+        Line::from(Span::raw("S: Toggle tags/contexts sidebar, Tab: Focus it")),
+        Line::from(Span::raw("s: Cycle sort key, B: Cycle grouping")),
+        Line::from(Span::raw("H: Show/hide completed tasks")),
+        Line::from(Span::raw("T: Review tasks done today and yesterday")),
+        Line::from(Span::raw(
+            "W: Waiting for (tasks delegated with >>name), again: back",
+        )),
+        Line::from(Span::raw(
+            "z: Zoom into the selected task, Backspace: Zoom out, Esc: Show all",
+        )),
+        Line::from(Span::raw(
+            "+/-: Adjust the progress of the selected task by 10%",
+        )),
         Line::from(Span::raw("C: Calendar Mode")),
+        Line::from(Span::raw("w: Plan the week, 1-7 in it: Due on that day")),
+        Line::from(Span::raw(
+            "t: Timeline of dated tasks, z in it: Week/month/quarter",
+        )),
+        Line::from(Span::raw(
+            "E: Export the list to Markdown, :export html for a web page",
+        )),
+        Line::from(Span::raw(
+            "Y: Activity heatmap, hjkl in it: Pick a day, Tab: Created/completed",
+        )),
+        Line::from(Span::raw("L: Switch or create a list")),
+        Line::from(Span::raw(
+            "i: Jump to the inbox, m: Move task under another",
+        )),
+        Line::from(Span::raw("C-p: Jump to any task by fuzzy search")),
+        Line::from(Span::raw("C-o/C-n: Back/forward to tasks you jumped from")),
+        Line::from(Span::raw("M<a-z>: Mark the task, '<a-z>: Jump to a mark")),
+        Line::from(Span::raw(
+            "*: Narrow the list to tasks like the selected one",
+        )),
+        Line::from(Span::raw(
+            "V or :views: Load saved views, bind them to Alt-1..9",
+        )),
+        Line::from(Span::raw(":<name> [args]: Run a user script")),
+        Line::from(Span::raw(":messages: Show recent notifications and errors")),
+        Line::from(Span::raw("h or :history: Show the selected task's history")),
+        Line::from(Span::raw(
+            "r or :comments: Read and add comments on the selected task",
+        )),
+        Line::from(Span::raw("# or :tags: Rename, merge or delete tags")),
+        Line::from(Span::raw(
+            "F or :focus [@context|off]: Only show tasks in a context",
+        )),
+        Line::from(Span::raw(
+            ":snooze @context|off: Hide the selected task until focusing on a context",
+        )),
+        Line::from(Span::raw(
+            ":rollover [move|flag]: Move tasks due on past days to today, or tag them #overdue",
+        )),
+        Line::from(Span::raw(
+            ":rollover start move|flag|off: Roll over when the app starts",
+        )),
+        Line::from(Span::raw(
+            ":sync: Sync with the CalDAV server (also on start and quit)",
+        )),
+        Line::from(Span::raw(
+            ":summary: Today's completed and added tasks, and tomorrow's due ones",
+        )),
+        Line::from(Span::raw(
+            ":journal <path>|off: Append every completion to a Markdown file",
+        )),
+        Line::from(Span::raw(
+            ":completion [default] auto|manual|percent|inherit: How parents follow subtasks",
+        )),
+        Line::from(Span::raw(
+            ":done-style dim|strike|off: How finished tasks are set apart",
+        )),
+        Line::from(Span::raw(
+            ":animations on|off: Scroll to far jumps and flash tasks an undo changed",
+        )),
+        Line::from(Span::raw(
+            ":columns [id description due:16 ...]: Pick, order and size the row columns",
+        )),
+        Line::from(Span::raw(
+            ":text-match fold|exact: Whether text: filters ignore case and accents",
+        )),
         Line::from(Span::raw("?: Show Help")),
         Line::from(Span::raw("Esc: Return to Normal Mode")),
+        Line::from(Span::raw(
+            "Input: C-a/C-e Home/End, C-u/C-k Kill, M-d Kill Word",
+        )),
+        Line::from(Span::raw("Input: C-y Yank, C-t Transpose, Up/Down History")),
     ];
 
     let help_paragraph = Paragraph::new(help_text)
@@ -208,19 +1288,317 @@ fn render_help_overlay(frame: &mut Frame, size: Rect) {
     frame.render_widget(help_paragraph, help_area);
 }
 
+fn severity_style(severity: Severity) -> Style {
+    match severity {
+        Severity::Info => Style::default(),
+        Severity::Success => Style::default().fg(Color::Green),
+        Severity::Warning => Style::default().fg(Color::Yellow),
+        Severity::Error => Style::default().fg(Color::Red),
+    }
+}
+
+fn render_messages_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Messages (j/k: scroll, Esc: close)");
+    let lines: Vec<Line> = model
+        .messages
+        .log()
+        .rev()
+        .map(|message| {
+            Line::from(vec![
+                Span::styled(
+                    message.at.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(message.text.clone(), severity_style(message.severity)),
+            ])
+        })
+        .collect();
+    let text = if lines.is_empty() {
+        Text::from("No messages yet.")
+    } else {
+        Text::from(lines)
+    };
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((model.debug_scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_history_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let Some(task) = model.get_task(&model.get_path()) else {
+        let block = Block::default().borders(Borders::ALL).title("History");
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new("No task selected.").block(block), area);
+        return;
+    };
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "History of {} (j/k: scroll, Esc: close)",
+        task.description
+    ));
+    let now = Utc::now();
+    let mut lines = Vec::new();
+    if let Some(created) = task.created_at() {
+        let by = task.author.as_ref().map(|author| format!(" by {}", author));
+        lines.push(Line::from(format!(
+            "Created {}{}",
+            model.date_display.date_time(created, now),
+            by.unwrap_or_default()
+        )));
+    }
+    if let (Some(completed), Some(by)) = (task.completed_at, &task.completed_by) {
+        lines.push(Line::from(format!(
+            "Completed {} by {}",
+            model.date_display.date_time(completed, now),
+            by
+        )));
+    }
+    lines.extend([Line::from(history_summary(task, now)), Line::from("")]);
+    lines.extend(task.history.iter().rev().map(|change| {
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", model.date_display.date_time(change.at, now)),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(change.kind.as_str()),
+        ])
+    }));
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((model.debug_scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_comments_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let Some(task) = model.get_task(&model.get_path()) else {
+        let block = Block::default().borders(Borders::ALL).title("Comments");
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new("No task selected.").block(block), area);
+        return;
+    };
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Comments on {} (Enter: add, Up/Down: scroll, Esc: close)",
+        task.description
+    ));
+    let now = Utc::now();
+    let mut lines = Vec::new();
+    for comment in &task.comments {
+        let by = comment.author.as_ref().map(|author| format!(" {}", author));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}{}",
+                model.date_display.date_time(comment.at, now),
+                by.unwrap_or_default()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.extend(
+            comment
+                .text
+                .lines()
+                .map(|line| Line::from(line.to_string())),
+        );
+        lines.push(Line::from(""));
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            "No comments yet.",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    frame.render_widget(Clear, area);
+    let [thread_area, input_area] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(area);
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((model.debug_scroll, 0));
+    frame.render_widget(paragraph, thread_area);
+    let input = Paragraph::new(Text::styled(
+        model.input.text(),
+        Style::default().fg(Color::Yellow),
+    ))
+    .block(Block::default().borders(Borders::ALL).title("New comment"));
+    frame.render_widget(input, input_area);
+    frame.set_cursor(
+        input_area.x + model.input.cursor_width() as u16 + 1,
+        input_area.y + 1,
+    );
+}
+
+fn render_rollover_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let Some(report) = &model.last_rollover else {
+        return;
+    };
+    let title = match report.kind {
+        Rollover::Move => format!("Moved {} Tasks to Today", report.tasks.len()),
+        Rollover::Flag => format!("Tagged {} Tasks {}", report.tasks.len(), OVERDUE_TAG),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} (u: undo, Esc: close)", title));
+    let lines: Vec<Line> = report
+        .tasks
+        .iter()
+        .map(|task| {
+            let due = model.date_display.local_date(task.due_time);
+            Line::from(vec![
+                Span::styled(
+                    format!("was due {}  ", model.date_display.date(due)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(task.description.clone()),
+            ])
+        })
+        .collect();
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .scroll((model.debug_scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_summary_overlay(frame: &mut Frame, model: &Model, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let summary = summary::summarize(model, Utc::now());
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "{} (y: copy as Markdown, Esc: close)",
+        summary.title(&model.date_display)
+    ));
+    let mut lines = Vec::new();
+    for (heading, tasks) in summary.sections() {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            format!("{} ({})", heading, tasks.len()),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        if tasks.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  none",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.extend(tasks.iter().map(|task| Line::from(format!("  {}", task))));
+    }
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .scroll((model.debug_scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// E.g. "completed 3 times, moved once, last edited 2 days ago".
+fn history_summary(task: &Task, now: DateTime<Utc>) -> String {
+    let mut parts = Vec::new();
+    for kind in [
+        ChangeKind::Completed,
+        ChangeKind::Reopened,
+        ChangeKind::Cancelled,
+        ChangeKind::Moved,
+    ] {
+        match task
+            .history
+            .iter()
+            .filter(|change| change.kind == kind)
+            .count()
+        {
+            0 => {}
+            1 => parts.push(format!("{} once", kind.as_str())),
+            times => parts.push(format!("{} {} times", kind.as_str(), times)),
+        }
+    }
+    if let Some(edit) = task
+        .history
+        .iter()
+        .rfind(|change| change.kind == ChangeKind::Edited)
+    {
+        parts.push(format!("last edited {}", dates::relative(edit.at, now)));
+    }
+    if parts.is_empty() {
+        "No changes recorded yet.".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
 fn render_debug_overlay(frame: &mut Frame, model: &mut Model, size: Rect) {
-    let debug_area = centered_rect(50, 50, size);
+    let debug_area = centered_rect(80, 80, size);
     let debug_block = Block::default()
         .borders(Borders::ALL)
-        .title("Debug Overlay");
-    let debug_content = format!("{:#?}", model); // Display model state
-    let debug_paragraph = Paragraph::new(debug_content)
+        .title("Debug Overlay (j/k: scroll, p: close)");
+
+    let tasks = flatten_tasks(&model.tasks);
+    let completed = tasks
+        .iter()
+        .filter(|(_, path)| model.get_task(path).is_some_and(|task| task.is_done()))
+        .count();
+    let selected_path = model
+        .get_path()
+        .iter()
+        .scan(&model.tasks, |tasks, id| {
+            let task = tasks.get(id)?;
+            *tasks = &task.subtasks;
+            Some(task.description.clone())
+        })
+        .collect::<Vec<_>>()
+        .join(" > ");
+    let stats = model.frame_stats;
+    let summary = format!(
+        "Tasks: {} total, {} completed, {} visible\n\
+         Selected: {}\n\
+         History: {} task inputs, {} filter inputs\n\
+         Frames: {} drawn, last {:.2?}, average {:.2?}, max {:.2?}\n\
+         \n\
+         Filter:\n{:#?}\n\
+         \n\
+         Model:\n{:#?}",
+        tasks.len(),
+        completed,
+        model.nav.len(),
+        if selected_path.is_empty() {
+            "none"
+        } else {
+            &selected_path
+        },
+        model.task_history.len(),
+        model.filter_history.len(),
+        stats.frames,
+        stats.last,
+        stats.average(),
+        stats.max,
+        model.current_view.filter_lists,
+        model,
+    );
+    let debug_paragraph = Paragraph::new(summary)
         .block(debug_block)
         .style(Style::default().fg(Color::Red))
         .scroll((model.debug_scroll, 0));
+    frame.render_widget(Clear, debug_area);
     frame.render_widget(debug_paragraph, debug_area);
 }
 
+/// The whole screen of `chors capture`: a single task input.
+pub fn render_capture(frame: &mut Frame, model: &Model) {
+    let size = frame.size();
+    let area = Rect::new(size.x, size.y, size.width, size.height.min(3));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Capture task (Enter: add, Esc: cancel)");
+    frame.render_widget(Paragraph::new(model.input.text()).block(block), area);
+    frame.set_cursor(area.x + 1 + model.input.cursor_width() as u16, area.y + 1);
+}
+
 // Terminal initialization
 pub fn init() -> io::Result<Tui> {
     execute!(stdout(), EnterAlternateScreen)?;
@@ -236,39 +1614,129 @@ pub fn restore() -> io::Result<()> {
     Ok(())
 }
 
+/// What rows are built from besides the tasks themselves.
+struct ListContext<'a> {
+    view: &'a View,
+    matches: &'a HashMap<Uuid, bool>,
+    dates: &'a DateDisplay,
+    /// The model's default, for tasks without their own policy.
+    completion: CompletionPolicy,
+    symbols: &'a Symbols,
+    completed_style: CompletedStyle,
+    accessible: bool,
+    focus: Option<&'a str>,
+    columns: &'a [ColumnSpec],
+}
+
+impl ListContext<'_> {
+    /// Whether `column` has a cell of its own instead of being shown inline.
+    fn has_column(&self, column: Column) -> bool {
+        self.columns.iter().any(|spec| spec.column == column)
+    }
+}
+
+/// The cell of a column other than the description, padded or cut to its
+/// width after a separating space.
+fn column_cell<'a>(
+    spec: &ColumnSpec,
+    task: &Task,
+    context: &ListContext,
+    short_id: Option<&str>,
+) -> Span<'a> {
+    let now = Utc::now();
+    let (text, color) = match spec.column {
+        Column::Description => (String::new(), Color::Reset),
+        Column::Due => (
+            task.due_time
+                .map(|due| context.dates.date_time(due, now))
+                .unwrap_or_default(),
+            Color::Red,
+        ),
+        Column::Priority => match task.priority {
+            Some(priority) => (
+                context.symbols.priority(priority).to_string(),
+                priority_color(priority),
+            ),
+            None => (String::new(), Color::Reset),
+        },
+        Column::Estimate => (
+            task.meta.get("estimate").cloned().unwrap_or_default(),
+            Color::Yellow,
+        ),
+        Column::Age => (
+            task.created_at()
+                .map(|created| format!("{}d", (now - created).num_days()))
+                .unwrap_or_default(),
+            Color::DarkGray,
+        ),
+        Column::Id => (short_id.unwrap_or_default().to_string(), Color::Blue),
+    };
+    let width = usize::from(spec.width().unwrap_or(0));
+    let text = shorten(&text, width);
+    let padding = width.saturating_sub(text.width());
+    Span::styled(
+        format!(" {}{}", text, " ".repeat(padding)),
+        Style::default().fg(color),
+    )
+}
+
+/// Cuts or pads `spans` to exactly `width` columns.
+fn fit_spans(spans: Vec<Span<'_>>, width: usize) -> Vec<Span<'_>> {
+    let mut fitted = Vec::new();
+    let mut used = 0;
+    for span in spans {
+        let span_width = span.width();
+        if used + span_width > width {
+            let rest = shorten(&span.content, width - used);
+            used += rest.width();
+            fitted.push(Span::styled(rest, span.style));
+            break;
+        }
+        used += span_width;
+        fitted.push(span);
+    }
+    fitted.push(Span::raw(" ".repeat(width.saturating_sub(used))));
+    fitted
+}
+
 fn build_task_list<'a>(
     tasks: &'a IndexMap<Uuid, Task>,
     path: Vec<Uuid>,
-    view: &'a View,
+    context: &ListContext,
     parent_match: bool,
     depth: usize,
 ) -> UIList<'a> {
-    let mut items = Vec::new();
+    let ListContext { view, matches, .. } = context;
+    let mut rows = Vec::new();
     let mut nav = IndexMap::new();
     let mut tags = HashSet::new();
     let mut contexts = HashSet::new();
 
-    for task in tasks.values() {
+    for task in view.display.order(tasks, context.dates) {
         let mut current_path = path.clone();
         current_path.push(task.id);
 
-        if view.matches(task) | parent_match {
+        // Hiding completed and snoozed tasks acts as an implicit conjunct on
+        // top of the view, so it also applies to subtasks of matching parents.
+        let hidden = view.hides(task, context.focus);
+        let task_matches = matches
+            .get(&task.id)
+            .copied()
+            .unwrap_or_else(|| view.matches_at(task, &path, context.dates));
+        if !hidden && (task_matches | parent_match) {
             nav.insert(task.id, current_path.clone());
 
-            add_task_to_ui_list(task, &mut items, &mut tags, &mut contexts, depth);
-            let sub = build_task_list(&task.subtasks, current_path, view, true, depth + 1);
-            items.extend(sub.items);
+            rows.push((task, depth));
+            collect_words(task, &mut tags, &mut contexts);
+            let sub = build_task_list(&task.subtasks, current_path, context, true, depth + 1);
+            rows.extend(sub.rows);
             nav.extend(sub.nav);
             tags.extend(sub.tags);
             contexts.extend(sub.contexts);
         } else {
-            let sub = build_task_list(&task.subtasks, current_path, view, false, depth);
-            if !sub.items.is_empty() {
-                // let mut current_path = path.clone();
-                // current_path.push(task.id);
-                // nav.insert(task.id, current_path.clone());
-                // add_task_to_ui_list(task, &mut items, &mut tags, &mut contexts, 0);
-                items.extend(sub.items);
+            let sub = build_task_list(&task.subtasks, current_path, context, false, depth);
+            if !sub.rows.is_empty() {
+                rows.extend(sub.rows);
                 nav.extend(sub.nav);
                 tags.extend(sub.tags);
                 contexts.extend(sub.contexts);
@@ -277,26 +1745,117 @@ fn build_task_list<'a>(
     }
 
     UIList {
-        items,
+        rows,
         nav,
         tags,
         contexts,
     }
 }
 
-fn add_task_to_ui_list<'a>(
-    task: &'a Task,
-    items: &mut Vec<ListItem<'a>>,
-    tags: &mut HashSet<String>,
-    contexts: &mut HashSet<String>,
-    indent_level: usize,
-) {
+/// Number of spans `task_line` emits before the description:
+/// indentation, status checkbox and a separating space.
+const TASK_PREFIX_SPANS: usize = 3;
+
+/// Soft-wraps a task row at word boundaries to fit `width`, indenting
+/// continuation lines by `hang` so they line up with the description.
+/// Joins `crumbs` into a breadcrumb at most `width` columns wide. Long
+/// ancestors are shortened first, then dropped from the middle, so the top
+/// level and the last crumb stay as long as possible.
+fn breadcrumb(crumbs: &[&str], width: usize) -> String {
+    const SEPARATOR: &str = " > ";
+    const ANCESTOR_WIDTH: usize = 16;
+    let fits = |parts: &[String]| parts.join(SEPARATOR).width() <= width;
+
+    let mut parts: Vec<String> = crumbs.iter().map(|crumb| crumb.to_string()).collect();
+    if fits(&parts) {
+        return parts.join(SEPARATOR);
+    }
+    let last = parts.len().saturating_sub(1);
+    for part in &mut parts[..last] {
+        *part = shorten(part, ANCESTOR_WIDTH);
+    }
+    while !fits(&parts) && parts.len() > 2 {
+        if parts[1] != "…" {
+            parts[1] = "…".to_string();
+        } else if parts.len() > 3 {
+            parts.remove(2);
+        } else {
+            break;
+        }
+    }
+    shorten(&parts.join(SEPARATOR), width)
+}
+
+/// Cuts `text` to at most `width` columns, marking the cut with an ellipsis.
+fn shorten(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut short = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        short.push(ch);
+        used += ch_width;
+    }
+    if width > 0 {
+        short.push('…');
+    }
+    short
+}
+
+fn wrap_line(line: Line<'_>, width: usize, hang: usize) -> Text<'_> {
+    if hang >= width || line.width() <= width {
+        return Text::from(line);
+    }
+
+    let mut lines = Vec::new();
+    let mut current: Vec<Span> = Vec::new();
+    let mut current_width = 0;
+    for span in line.spans {
+        for chunk in span.content.split_inclusive(' ') {
+            let chunk_width = chunk.trim_end().width();
+            if current_width + chunk_width > width && current_width > hang {
+                lines.push(Line::from(std::mem::take(&mut current)));
+                current.push(Span::raw(" ".repeat(hang)));
+                current_width = hang;
+            }
+            current.push(Span::styled(chunk.to_string(), span.style));
+            current_width += chunk.width();
+        }
+    }
+    if !current.is_empty() {
+        lines.push(Line::from(current));
+    }
+    Text::from(lines)
+}
+
+/// Gathers the tags and contexts in `task`'s description.
+fn collect_words(task: &Task, tags: &mut HashSet<String>, contexts: &mut HashSet<String>) {
+    for word in task.description.split_whitespace() {
+        if word.starts_with('#') {
+            tags.insert(word.to_string());
+        } else if word.starts_with('@') {
+            contexts.insert(word.to_string());
+        }
+    }
+}
+
+fn task_line<'a>(task: &'a Task, context: &ListContext, indent_level: usize) -> Line<'a> {
+    let dates = context.dates;
     let indent = "  ".repeat(indent_level);
-    let status = if task.completed {
-        Span::styled("[x]", Style::default().fg(Color::Green))
+    let symbols = context.symbols;
+    let color = if task.cancelled.is_some() {
+        Color::DarkGray
+    } else if task.completed {
+        Color::Green
     } else {
-        Span::styled("[ ]", Style::default().fg(Color::Yellow))
+        Color::Yellow
     };
+    let status = Span::styled(symbols.status(task).to_string(), Style::default().fg(color));
     let mut description_spans = Vec::new();
     description_spans.push(Span::raw(format!("{} ", indent)));
     description_spans.push(status);
@@ -304,46 +1863,161 @@ fn add_task_to_ui_list<'a>(
 
     for word in task.description.split_whitespace() {
         if word.starts_with('#') {
-            tags.insert(word.to_string());
             description_spans.push(Span::styled(word, Style::default().fg(Color::Magenta)));
         } else if word.starts_with('@') {
-            contexts.insert(word.to_string());
             description_spans.push(Span::styled(word, Style::default().fg(Color::Cyan)));
+        } else if delegate(word).is_some() {
+            description_spans.push(Span::styled(word, Style::default().fg(Color::LightBlue)));
+        } else if is_url(word) {
+            description_spans.push(Span::styled(
+                word,
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
         } else {
             description_spans.push(Span::raw(word));
         }
         description_spans.push(Span::raw(" "));
     }
 
+    if let Some(priority) = task
+        .priority
+        .filter(|_| !context.has_column(Column::Priority))
+    {
+        description_spans.push(Span::styled(
+            format!("{} ", symbols.priority(priority)),
+            Style::default().fg(priority_color(priority)),
+        ));
+    }
+
     if let Some(start_time) = task.start_time {
         description_spans.push(Span::styled(
-            format!("[Start: {}]", start_time.format("%Y-%m-%d %H:%M")),
+            format!("[Start: {}]", dates.date_time(start_time, Utc::now())),
             Style::default().fg(Color::Blue),
         ));
     }
 
-    if let Some(due_time) = task.due_time {
+    if let Some(due_time) = task.due_time.filter(|_| !context.has_column(Column::Due)) {
         description_spans.push(Span::styled(
-            format!("[Due: {}]", due_time.format("%Y-%m-%d %H:%M")),
+            format!("[Due: {}]", dates.date_time(due_time, Utc::now())),
             Style::default().fg(Color::Red),
         ));
     }
 
-    let total_subtasks = task.subtasks.len();
+    match task.attachments.len() {
+        0 => {}
+        1 => description_spans.push(Span::raw(format!("{} ", symbols.attachment))),
+        n => description_spans.push(Span::raw(format!("{}{} ", symbols.attachment, n))),
+    }
+
+    if !task.reminders.is_empty() {
+        description_spans.push(Span::raw(format!("{} ", symbols.reminder)));
+    }
+
+    if !task.comments.is_empty() {
+        description_spans.push(Span::raw(format!(
+            "{}{} ",
+            symbols.comment,
+            task.comments.len()
+        )));
+    }
+
+    if let Some(percent) = task
+        .percent
+        .filter(|_| task.subtasks.is_empty() && !task.completed)
+    {
+        let progress = match context.accessible {
+            true => format!("[{}% done] ", percent),
+            false => format!("{} {}% ", progress_bar(percent), percent),
+        };
+        description_spans.push(Span::styled(progress, Style::default().fg(Color::Yellow)));
+    }
+
+    if let Some(reason) = task.cancelled.as_ref().and_then(|c| c.reason.as_ref()) {
+        description_spans.push(Span::styled(
+            format!("(won't do: {}) ", reason),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    if let Some(snooze) = &task.snoozed {
+        description_spans.push(Span::styled(
+            format!("(snoozed {}) ", snooze.describe()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    // Cancelled subtasks neither count as done nor as left to do
+    let total_subtasks = task
+        .subtasks
+        .values()
+        .filter(|t| t.cancelled.is_none())
+        .count();
     if total_subtasks > 0 {
-        let completed_subtasks = task.subtasks.values().filter(|t| t.completed).count();
+        let completed_subtasks = task.subtasks.values().filter(|t| t.is_done()).count();
         let color = if completed_subtasks == total_subtasks {
             Color::Green
         } else {
             Color::Yellow
         };
-        description_spans.push(Span::styled(
-            format!("[{}/{}]", completed_subtasks, total_subtasks),
-            Style::default().fg(color),
-        ));
+        let percent = task.progress() * 100.0;
+        let progress = match task.completion.unwrap_or(context.completion) {
+            CompletionPolicy::Percent => format!("[{:.0}%]", percent),
+            // Partial progress below makes the count undersell it
+            CompletionPolicy::Auto | CompletionPolicy::Manual
+                if !task.completed
+                    && completed_subtasks * 100 < percent.round() as usize * total_subtasks =>
+            {
+                format!(
+                    "[{}/{} {:.0}%]",
+                    completed_subtasks, total_subtasks, percent
+                )
+            }
+            CompletionPolicy::Auto | CompletionPolicy::Manual => {
+                format!("[{}/{}]", completed_subtasks, total_subtasks)
+            }
+        };
+        description_spans.push(Span::styled(progress, Style::default().fg(color)));
+    }
+
+    if task.completed {
+        let mut modifier = Modifier::empty();
+        if context.completed_style.dim {
+            modifier |= Modifier::DIM;
+        }
+        if context.completed_style.strike {
+            modifier |= Modifier::CROSSED_OUT;
+        }
+        // Everything but the indentation, so the strike starts at the checkbox
+        for span in &mut description_spans[1..] {
+            span.style = span.style.add_modifier(modifier);
+        }
     }
 
-    items.push(ListItem::new(Line::from(description_spans)));
+    Line::from(description_spans)
+}
+
+fn priority_color(priority: Priority) -> Color {
+    match priority {
+        Priority::High => Color::Red,
+        Priority::Medium => Color::LightRed,
+        Priority::Low => Color::DarkGray,
+    }
+}
+
+/// A five cell bar for a percentage, e.g. `▕██▌  ▏` for 50%.
+fn progress_bar(percent: u8) -> String {
+    const CELLS: usize = 5;
+    let halves = (usize::from(percent) * CELLS * 2 + 50) / 100;
+    let mut bar = String::from("▕");
+    bar.push_str(&"█".repeat(halves / 2));
+    if halves % 2 == 1 {
+        bar.push('▌');
+    }
+    bar.push_str(&" ".repeat(CELLS - halves.div_ceil(2)));
+    bar.push('▏');
+    bar
 }
 
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -382,67 +2056,78 @@ fn render_calendar_mode(frame: &mut Frame, model: &Model, area: Rect) {
 }
 
 fn render_calendar(frame: &mut Frame, model: &Model, area: Rect) {
-    let now = chrono::Local::now();
-    let (year, month, today) = (now.year(), now.month(), now.day());
-    let days_in_month = days_in_month(year, month);
+    let dates = &model.date_display;
+    let today = dates.local_date(Utc::now());
+    let (year, month) = (today.year(), today.month());
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
 
     let calendar_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .constraints(
+            [
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
         .split(area);
 
     let header =
         Paragraph::new(format!("{} {}", month_name(month), year)).alignment(Alignment::Center);
     frame.render_widget(header, calendar_layout[0]);
 
-    let calendar_area = calendar_layout[1];
+    let calendar_area = calendar_layout[2];
     let day_width = calendar_area.width / 7;
     let day_height = calendar_area.height / 6;
 
+    for (column, weekday) in dates.weekdays().enumerate() {
+        let name_area = Rect::new(
+            calendar_area.x + column as u16 * day_width,
+            calendar_layout[1].y,
+            day_width,
+            1,
+        );
+        let name = Paragraph::new(weekday.to_string()).alignment(Alignment::Center);
+        frame.render_widget(name, name_area);
+    }
+
+    // The grid starts on the configured first day of the week, so the first
+    // row may begin in the previous month.
+    let mut day = dates.start_of_week(first);
     for week in 0..6 {
-        for day in 0..7 {
-            let day_number = week * 7 + day + 1;
-            if day_number <= days_in_month {
+        for column in 0..7 {
+            if day.month() == month {
                 let day_area = Rect::new(
-                    calendar_area.x + (day as u16) * day_width,
-                    calendar_area.y + (week as u16) * day_height,
+                    calendar_area.x + column * day_width,
+                    calendar_area.y + week * day_height,
                     day_width,
                     day_height,
                 );
 
                 let mut style = Style::default();
-                if day_number == today {
+                if day == today {
                     style = style.bg(Color::Blue);
                 }
 
                 let day_block = Block::default().borders(Borders::ALL).style(style);
                 frame.render_widget(day_block, day_area);
 
-                let day_text = Paragraph::new(day_number.to_string()).alignment(Alignment::Center);
+                let day_text = Paragraph::new(day.day().to_string()).alignment(Alignment::Center);
                 frame.render_widget(day_text, day_area);
 
-                // Here, you would render tasks for this day
-                // You'll need to implement a function to get tasks for a specific day
-                render_tasks_for_day(frame, model, day_area, year, month, day_number);
+                render_tasks_for_day(frame, model, day_area, day);
             }
+            day = day.succ_opt().unwrap();
         }
     }
 }
 
-fn render_tasks_for_day(
-    frame: &mut Frame,
-    model: &Model,
-    area: Rect,
-    year: i32,
-    month: u32,
-    day: u32,
-) {
+fn render_tasks_for_day(frame: &mut Frame, model: &Model, area: Rect, day: NaiveDate) {
+    let dates = &model.date_display;
     let tasks_for_day = model.tasks.values().filter(|task| {
-        if let Some(start_time) = task.start_time {
-            start_time.year() == year && start_time.month() == month && start_time.day() == day
-        } else {
-            false
-        }
+        task.start_time
+            .is_some_and(|start_time| dates.local_date(start_time) == day)
     });
 
     let task_area = Rect::new(area.x + 1, area.y + 2, area.width - 2, area.height - 3);
@@ -463,21 +2148,315 @@ fn render_tasks_for_day(
     frame.render_widget(tasks_list, task_area);
 }
 
-fn days_in_month(year: i32, month: u32) -> u32 {
-    chrono::NaiveDate::from_ymd_opt(
-        match month {
-            12 => year + 1,
-            _ => year,
-        },
-        match month {
-            12 => 1,
-            _ => month + 1,
-        },
-        1,
-    )
-    .unwrap()
-    .signed_duration_since(chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap())
-    .num_days() as u32
+/// The backlog on the left and the next seven days as columns, with the
+/// number key that schedules the selected backlog task into each.
+fn render_planning_mode(frame: &mut Frame, model: &mut Model, area: Rect) {
+    let now = Utc::now();
+    let symbols = symbols(model);
+    let dates = &model.date_display;
+    let today = dates.local_date(now);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(25), Constraint::Percentage(75)].as_ref())
+        .split(area);
+
+    let backlog: Vec<ListItem> = model
+        .planning_backlog(now)
+        .iter()
+        .filter_map(|path| model.get_task(path))
+        .map(|task| {
+            let style = match task.due_time {
+                Some(_) => Style::default().fg(Color::Red),
+                None => Style::default(),
+            };
+            ListItem::new(Span::styled(task.description.clone(), style))
+        })
+        .collect();
+    let backlog = List::new(backlog)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Backlog (1-7: plan, w: back)"),
+        )
+        .highlight_style(Style::default().bg(Color::Indexed(8)));
+
+    let day_width = columns[1].width / 7;
+    for offset in 0..7u16 {
+        let day = today + chrono::Duration::days(offset.into());
+        // The last column takes what the division left over
+        let width = match offset {
+            6 => columns[1].width - 6 * day_width,
+            _ => day_width,
+        };
+        let day_area = Rect::new(
+            columns[1].x + offset * day_width,
+            columns[1].y,
+            width,
+            columns[1].height,
+        );
+        let title = format!("{} {} {}", offset + 1, day.weekday(), day.day());
+        let mut style = Style::default();
+        if day == today {
+            style = style.fg(Color::Blue);
+        }
+        let lines: Vec<Line> = flatten_tasks(&model.tasks)
+            .iter()
+            .filter_map(|(_, path)| model.get_task(path))
+            .filter(|task| {
+                task.due_time
+                    .is_some_and(|due| dates.local_date(due) == day)
+            })
+            .map(|task| {
+                let style = if task.completed {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::Yellow)
+                };
+                Line::from(Span::styled(
+                    format!("{} {}", symbols.status(task), task.description),
+                    style,
+                ))
+            })
+            .collect();
+        let tasks = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(style)
+                .title(title),
+        );
+        frame.render_widget(tasks, day_area);
+    }
+
+    render_list(frame, backlog, columns[0], &mut model.planning_state);
+}
+
+/// Dated tasks in the current view as bars from their start to their due
+/// date, with the description on the left and today's column marked.
+fn render_timeline_mode(frame: &mut Frame, model: &mut Model, area: Rect) {
+    let rows = model.timeline_rows();
+    model.nav = rows
+        .iter()
+        .map(|path| (*path.last().unwrap(), path.clone()))
+        .collect();
+    model
+        .list_state
+        .select(model.selected.and_then(|id| model.nav.get_index_of(&id)));
+
+    let now = Utc::now();
+    let dates = &model.date_display;
+    let today = dates.local_date(now);
+    let scale = model.timeline_scale;
+    let days = scale.days();
+    let first_day = dates.start_of_week(today) - chrono::Duration::weeks(scale.lead_weeks());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Timeline: {} (z: zoom, t: back)", scale.as_str()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(inner);
+
+    let label_width = (inner.width / 3).min(30) as usize;
+    let chart_width = (inner.width as usize).saturating_sub(label_width + 1);
+    if chart_width == 0 {
+        return;
+    }
+    // The first column of a day, which may be off either edge of the chart
+    let column = |day: NaiveDate| (day - first_day).num_days() * chart_width as i64 / days;
+    let today_column = column(today) as usize;
+
+    let mut header = vec![' '; chart_width];
+    for offset in 0..days {
+        let day = first_day + chrono::Duration::days(offset);
+        let label = match scale {
+            TimelineScale::Week => format!("{:.2} {}", day.weekday().to_string(), day.day()),
+            _ if dates.start_of_week(day) == day => match day.day() {
+                1..=7 => format!("{} {}", &month_name(day.month())[..3], day.day()),
+                _ => day.day().to_string(),
+            },
+            _ => continue,
+        };
+        let start = column(day) as usize;
+        // Leave a gap after the previous label rather than overwrite it
+        if start > 0 && header[start - 1] != ' ' {
+            continue;
+        }
+        for (cell, ch) in header[start..].iter_mut().zip(label.chars()) {
+            *cell = ch;
+        }
+    }
+    let header = Line::from(vec![
+        Span::raw(" ".repeat(label_width + 1)),
+        Span::raw(header.into_iter().collect::<String>()),
+    ]);
+    frame.render_widget(Paragraph::new(header), parts[0]);
+
+    let items: Vec<ListItem> = rows
+        .iter()
+        .filter_map(|path| Some((path.len() - 1, model.get_task(path)?)))
+        .map(|(depth, task)| {
+            let style = if task.completed {
+                Style::default().fg(Color::DarkGray)
+            } else if task.due_time.is_some_and(|due| due < now) {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Yellow)
+            };
+            let label = shorten(
+                &format!("{}{}", "  ".repeat(depth), task.description),
+                label_width,
+            );
+            let padding = label_width.saturating_sub(label.width());
+
+            let start = task.start_time.map(|start| dates.local_date(start));
+            let due = task.due_time.map(|due| dates.local_date(due));
+            let (first, last, glyph) = match (start, due) {
+                (Some(start), Some(due)) => (start.min(due), start.max(due), '█'),
+                (Some(start), None) => (start, start, '▶'),
+                (None, Some(due)) => (due, due, '◆'),
+                (None, None) => unreachable!("the timeline only lists dated tasks"),
+            };
+            let from = column(first);
+            // Bars fill their days; the start and due markers take one cell
+            let to = match glyph {
+                '█' => (column(last + chrono::Duration::days(1)) - 1).max(from),
+                _ => from,
+            };
+            let mut cells: Vec<(char, Style)> = (0..chart_width)
+                .map(|index| match index == today_column {
+                    true => ('│', Style::default().fg(Color::Blue)),
+                    false => (' ', Style::default()),
+                })
+                .collect();
+            if to < 0 {
+                cells[0] = ('◀', style);
+            } else if from >= chart_width as i64 {
+                cells[chart_width - 1] = ('▶', style);
+            } else {
+                let from = from.max(0) as usize;
+                let to = (to as usize).min(chart_width - 1);
+                for cell in &mut cells[from..=to] {
+                    *cell = (glyph, style);
+                }
+            }
+
+            let mut spans = vec![
+                Span::styled(label, style),
+                Span::raw(" ".repeat(padding + 1)),
+            ];
+            spans.extend(
+                cells
+                    .into_iter()
+                    .map(|(ch, style)| Span::styled(ch.to_string(), style)),
+            );
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().bg(Color::Indexed(8)));
+    render_list(frame, list, parts[1], &mut model.list_state);
+}
+
+/// A contributions-style heatmap, weeks as columns and weekdays as rows,
+/// with the tasks of the picked day listed underneath.
+fn render_stats_mode(frame: &mut Frame, model: &mut Model, area: Rect) {
+    const SHADES: [u8; 4] = [22, 28, 34, 40];
+
+    let symbols = symbols(model);
+    let dates = &model.date_display;
+    let today = dates.local_date(Utc::now());
+    let picked = model.activity_day.unwrap_or(today);
+    let activity = model.activity(model.activity_metric);
+
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Activity: {} (Tab: created/completed, Y: back)",
+        model.activity_metric.as_str()
+    ));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(9), Constraint::Min(0)].as_ref())
+        .split(inner);
+
+    // Two columns per week after the weekday labels; the current week is on
+    // the right unless the picked day has scrolled further back
+    let weeks = (inner.width.saturating_sub(3) as i64 / 2).clamp(1, ACTIVITY_WEEKS + 1);
+    let last_week = dates
+        .start_of_week(today)
+        .min(dates.start_of_week(picked) + chrono::Duration::weeks(weeks - 1));
+    let first_week = last_week - chrono::Duration::weeks(weeks - 1);
+    let busiest = activity.values().map(Vec::len).max().unwrap_or(0);
+
+    let mut months = vec![' '; 3 + 2 * weeks as usize];
+    for week in 0..weeks {
+        let start = first_week + chrono::Duration::weeks(week);
+        let Some(first) = (0..7)
+            .map(|offset| start + chrono::Duration::days(offset))
+            .find(|day| day.day() == 1)
+        else {
+            continue;
+        };
+        let x = 3 + 2 * week as usize;
+        if months[x - 1] != ' ' {
+            continue;
+        }
+        for (cell, ch) in months[x..]
+            .iter_mut()
+            .zip(month_name(first.month()).chars().take(3))
+        {
+            *cell = ch;
+        }
+    }
+    let mut lines = vec![Line::from(months.into_iter().collect::<String>())];
+    for (row, weekday) in dates.weekdays().enumerate() {
+        let mut spans = vec![Span::styled(
+            format!("{:.2} ", weekday.to_string()),
+            Style::default().fg(Color::DarkGray),
+        )];
+        for week in 0..weeks {
+            let day = first_week + chrono::Duration::days(7 * week + row as i64);
+            if day > today {
+                spans.push(Span::raw("  "));
+                continue;
+            }
+            let count = activity.get(&day).map_or(0, Vec::len);
+            let (glyph, mut style) = match count {
+                0 => ("·", Style::default().fg(Color::DarkGray)),
+                _ => {
+                    let level = (count * SHADES.len()).div_ceil(busiest) - 1;
+                    ("■", Style::default().fg(Color::Indexed(SHADES[level])))
+                }
+            };
+            if day == picked {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(glyph, style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+    frame.render_widget(Paragraph::new(lines), parts[0]);
+
+    let tasks = activity.get(&picked).map(Vec::as_slice).unwrap_or_default();
+    let mut items = vec![ListItem::new(Line::from(Span::styled(
+        format!(
+            "{}: {} {}",
+            dates.date(picked),
+            tasks.len(),
+            model.activity_metric.as_str()
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    )))];
+    items.extend(
+        tasks
+            .iter()
+            .filter_map(|path| model.get_task(path))
+            .map(|task| ListItem::new(format!("{} {}", symbols.status(task), task.description))),
+    );
+    frame.render_widget(List::new(items), parts[1]);
 }
 
 fn month_name(month: u32) -> &'static str {
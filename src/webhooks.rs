@@ -0,0 +1,194 @@
+//! JSON notifications to URLs when tasks are created, completed or deleted,
+//! read from `$XDG_CONFIG_HOME/chors/webhooks` (or the platform equivalent):
+//!
+//! ```text
+//! # Blank lines and comments are ignored; * stands for every event
+//! completed = https://homeassistant.local/api/webhook/chores-done
+//! * = https://n8n.example.com/webhook/chors
+//! ```
+//!
+//! Each event is POSTed as e.g.
+//! `{"event": "completed", "at": "...", "task": {"id": "...", ...}}`. A
+//! deleted task is gone by the time it is sent, so its payload only has the
+//! id; deleting a task with subtasks sends one for each of them too.
+//! Requests are sent one after another from a worker thread so a slow
+//! endpoint never stalls the interface.
+
+use chors_core::{
+    events::{Event, Subscriber},
+    model::Model,
+};
+use chrono::Utc;
+use color_eyre::{eyre::bail, Result};
+use serde_json::{json, Value};
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use uuid::Uuid;
+
+const EVENTS: [&str; 4] = ["created", "completed", "deleted", "*"];
+
+/// The URLs to notify, each with the event it is for or `*`.
+pub type Hooks = Vec<(String, String)>;
+
+pub fn default_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("chors").join("webhooks"))
+}
+
+/// Reads the webhooks at `path`; a missing file means none.
+pub fn load(path: &PathBuf) -> Result<Hooks> {
+    match fs::read_to_string(path) {
+        Ok(source) => {
+            parse(&source).map_err(|error| error.wrap_err(format!("in {}", path.to_string_lossy())))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Hooks::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn parse(source: &str) -> Result<Hooks> {
+    let mut hooks = Hooks::new();
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((event, url)) = line.split_once('=') else {
+            bail!("line {}: expected `event = url`", number + 1);
+        };
+        let (event, url) = (event.trim(), url.trim());
+        if !EVENTS.contains(&event) {
+            bail!(
+                "line {}: unknown event `{}`, expected one of {}",
+                number + 1,
+                event,
+                EVENTS.join(", ")
+            );
+        }
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            bail!("line {}: `{}` is not an http(s) URL", number + 1, url);
+        }
+        hooks.push((event.to_string(), url.to_string()));
+    }
+    Ok(hooks)
+}
+
+/// The name hooks know `event` by and the task it is about, if it is one
+/// they hear about.
+pub fn event_name(event: &Event) -> Option<(&'static str, Uuid)> {
+    match event {
+        Event::TaskAdded(id) => Some(("created", *id)),
+        Event::TaskCompleted(id) => Some(("completed", *id)),
+        Event::TaskRemoved(id) => Some(("deleted", *id)),
+        _ => None,
+    }
+}
+
+/// The JSON body sent for event `name` about task `id`.
+pub fn payload(name: &str, id: Uuid, model: &Model) -> Value {
+    let path = model.path_of(id);
+    let task = match path
+        .as_deref()
+        .and_then(|path| Some((path, model.get_task(path)?)))
+    {
+        Some((path, task)) => {
+            let mut tags: Vec<&String> = task.tags.iter().collect();
+            let mut contexts: Vec<&String> = task.contexts.iter().collect();
+            tags.sort();
+            contexts.sort();
+            json!({
+                "id": task.id,
+                "description": task.description,
+                "tags": tags,
+                "contexts": contexts,
+                "parent": path.len().checked_sub(2).map(|at| path[at]),
+                "due_time": task.due_time,
+                "completed_at": task.completed_at,
+            })
+        }
+        None => json!({ "id": id }),
+    };
+    json!({ "event": name, "at": Utc::now(), "task": task })
+}
+
+/// Posts task events to the configured URLs. Failures come back from the
+/// worker thread through [`Webhooks::failures`].
+pub struct Webhooks {
+    hooks: Hooks,
+    requests: Option<Sender<(String, String)>>,
+    worker: Option<JoinHandle<()>>,
+    failed: Sender<String>,
+    failures: Receiver<String>,
+}
+
+impl Webhooks {
+    pub fn new(hooks: Hooks) -> Self {
+        let (failed, failures) = mpsc::channel();
+        Self {
+            hooks,
+            requests: None,
+            worker: None,
+            failed,
+            failures,
+        }
+    }
+
+    /// Errors from requests that finished since the last call.
+    pub fn failures(&self) -> Vec<String> {
+        self.failures.try_iter().collect()
+    }
+
+    /// Waits for the queued requests to be sent, e.g. before exiting.
+    pub fn finish(&mut self) {
+        self.requests = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+
+    /// Queues `body` for `url`, starting the worker on the first request.
+    fn send(&mut self, url: &str, body: &Value) {
+        let requests = self.requests.get_or_insert_with(|| {
+            let (requests, queue) = mpsc::channel::<(String, String)>();
+            let failed = self.failed.clone();
+            self.worker = Some(thread::spawn(move || {
+                for (url, body) in queue {
+                    let result = ureq::post(&url)
+                        .timeout(Duration::from_secs(10))
+                        .set("Content-Type", "application/json")
+                        .send_string(&body);
+                    if let Err(error) = result {
+                        let _ = failed.send(format!("Webhook {} failed: {}", url, error));
+                    }
+                }
+            }));
+            requests
+        });
+        let _ = requests.send((url.to_string(), body.to_string()));
+    }
+}
+
+impl Subscriber for Webhooks {
+    fn notify(&mut self, event: &Event, model: &Model) {
+        let Some((name, id)) = event_name(event) else {
+            return;
+        };
+        let urls: Vec<String> = self
+            .hooks
+            .iter()
+            .filter(|(event, _)| event == name || event == "*")
+            .map(|(_, url)| url.clone())
+            .collect();
+        if urls.is_empty() {
+            return;
+        }
+        let body = payload(name, id, model);
+        for url in urls {
+            self.send(&url, &body);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Parsing the aliases file and expanding aliases on the command line.
+
+use chors::aliases;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn parses_quoted_expansions() {
+    let aliases = aliases::parse(
+        r#"
+        # Daily queries
+        alias today = list --filter "due:today completed:false"
+        shopping = print --filter '#groceries' --width 40
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        aliases["today"],
+        ["list", "--filter", "due:today completed:false"]
+    );
+    assert_eq!(
+        aliases["shopping"],
+        ["print", "--filter", "#groceries", "--width", "40"]
+    );
+}
+
+#[test]
+fn reports_the_broken_line() {
+    let error = aliases::parse("today = list\nbroken\n").unwrap_err();
+    assert!(error.to_string().contains("line 2"));
+    assert!(aliases::parse("x = list --filter \"open").is_err());
+}
+
+#[test]
+fn expands_in_the_subcommand_position_only() {
+    let aliases = aliases::parse("today = list --count\nlist = print\n").unwrap();
+    assert_eq!(
+        aliases::expand(args("chors -f tasks.json today -q"), &aliases),
+        args("chors -f tasks.json list --count -q")
+    );
+    // The file name isn't taken for a subcommand, nor built-ins for aliases
+    assert_eq!(
+        aliases::expand(args("chors -f today list"), &aliases),
+        args("chors -f today list")
+    );
+}
@@ -0,0 +1,81 @@
+//! Property tests for which tasks a filtered list shows: a matching task
+//! brings its whole subtree along, and nothing else is listed.
+
+use chors::view;
+use chors_core::model::{FilterList, Model, Task};
+use indexmap::IndexMap;
+use proptest::prelude::*;
+use ratatui::{backend::TestBackend, Terminal};
+use uuid::Uuid;
+
+const WORDS: [&str; 6] = ["task", "#a", "#b", "@x", "@y", "later"];
+const CRITERIA: [&str; 6] = ["#a", "#b", "@x", "@y", "completed:true", "completed:false"];
+
+#[derive(Debug, Clone)]
+struct Node {
+    words: Vec<&'static str>,
+    completed: bool,
+    children: Vec<Node>,
+}
+
+fn tree() -> impl Strategy<Value = IndexMap<Uuid, Task>> {
+    let words = || prop::collection::vec(prop::sample::select(&WORDS[..]), 1..4);
+    let leaf = (words(), any::<bool>()).prop_map(|(words, completed)| Node {
+        words,
+        completed,
+        children: Vec::new(),
+    });
+    let node = leaf.prop_recursive(4, 40, 4, move |inner| {
+        (words(), any::<bool>(), prop::collection::vec(inner, 1..4)).prop_map(
+            |(words, completed, children)| Node {
+                words,
+                completed,
+                children,
+            },
+        )
+    });
+    prop::collection::vec(node, 1..5).prop_map(|nodes| build(&nodes))
+}
+
+fn build(nodes: &[Node]) -> IndexMap<Uuid, Task> {
+    nodes
+        .iter()
+        .map(|node| {
+            let mut task = Task::new(&node.words.join(" "));
+            task.completed = node.completed;
+            task.subtasks = build(&node.children);
+            (task.id, task)
+        })
+        .collect()
+}
+
+fn expected(tasks: &IndexMap<Uuid, Task>, filter: &FilterList, parent_shown: bool) -> Vec<Uuid> {
+    let mut shown = Vec::new();
+    for task in tasks.values() {
+        let visible = parent_shown || filter.matches(task);
+        if visible {
+            shown.push(task.id);
+        }
+        shown.extend(expected(&task.subtasks, filter, visible));
+    }
+    shown
+}
+
+proptest! {
+    #[test]
+    fn matches_bring_their_subtrees(
+        tasks in tree(),
+        criteria in prop::collection::vec(prop::sample::select(&CRITERIA[..]), 1..3),
+    ) {
+        let filter = FilterList::parse(&criteria.join(" ")).unwrap();
+        let mut model = Model::new();
+        model.tasks = tasks;
+        model.current_view.filter_lists.push(filter.clone());
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| view::ui(frame, &mut model)).unwrap();
+
+        let shown: Vec<Uuid> = model.nav.keys().copied().collect();
+        prop_assert_eq!(shown, expected(&model.tasks, &filter, false));
+    }
+}
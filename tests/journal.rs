@@ -0,0 +1,86 @@
+//! Appending completions to the journal file.
+
+use chors::journal::Journal;
+use chors_core::{
+    dates::{self, DateDisplay},
+    events,
+    model::{CompletionPolicy, Model, Msg, Task},
+    update::update,
+};
+use chrono::{TimeZone, Utc};
+use std::fs;
+
+#[test]
+fn completions_are_appended_as_checked_items() {
+    let dir = std::env::temp_dir().join(format!("chors-journal-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let file = dir.join("log").join("done.md");
+    let mut model = Model::new();
+    model.date_display = DateDisplay {
+        zone: Some(dates::parse_zone("UTC").unwrap()),
+        ..DateDisplay::default()
+    };
+    update(
+        Msg::RunCommand(format!("journal {}", file.display())),
+        &mut model,
+    );
+    assert_eq!(model.journal.as_ref(), Some(&file));
+
+    let mut report = Task::new("Write the report #work");
+    report.completed = true;
+    report.completed_at = Some(Utc.with_ymd_and_hms(2024, 3, 8, 14, 5, 0).unwrap());
+    let id = report.id;
+    model.tasks.insert(id, report);
+    let call = Task::new("Call the plumber");
+    let path = vec![call.id];
+    model.tasks.insert(call.id, call);
+    model.emit(events::Event::TaskCompleted(id));
+    update(Msg::CompleteTasks(vec![path]), &mut model);
+
+    let mut journal = Journal::default();
+    events::dispatch(&mut model, &mut [&mut journal]);
+    assert_eq!(journal.error, None);
+    let log = fs::read_to_string(&file).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[0], "- [x] 2024-03-08 14:05 Write the report #work");
+    assert!(lines[1].ends_with(" Call the plumber"));
+    assert_eq!(lines.len(), 2);
+
+    update(Msg::RunCommand("journal off".to_string()), &mut model);
+    assert_eq!(model.journal, None);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parents_completed_by_their_last_subtask_are_journaled() {
+    let dir = std::env::temp_dir().join(format!("chors-journal-cascade-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let file = dir.join("done.md");
+    let mut model = Model::new();
+    model.author = Some("alice".to_string());
+    update(
+        Msg::RunCommand(format!("journal {}", file.display())),
+        &mut model,
+    );
+
+    let mut garage = Task::new("Clean the garage");
+    garage.completion = Some(CompletionPolicy::Auto);
+    let sweep = Task::new("Sweep the floor");
+    let path = vec![garage.id, sweep.id];
+    garage.subtasks.insert(sweep.id, sweep);
+    model.tasks.insert(garage.id, garage);
+    update(Msg::CompleteTasks(vec![path.clone()]), &mut model);
+
+    let parent = model.get_task(&path[..1]).unwrap();
+    assert!(parent.completed);
+    assert_eq!(parent.completed_by.as_deref(), Some("alice"));
+    let mut journal = Journal::default();
+    events::dispatch(&mut model, &mut [&mut journal]);
+    assert_eq!(journal.error, None);
+    let log = fs::read_to_string(&file).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert!(lines[0].ends_with(" Sweep the floor"));
+    assert!(lines[1].ends_with(" Clean the garage"));
+    assert_eq!(lines.len(), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+//! The plain-text report behind `chors print` and the exit codes of the
+//! query commands.
+
+use chors::commands::{self, ListOutput, EXIT_MATCHED, EXIT_NO_MATCHES, EXIT_PARSE_ERROR};
+use chors_core::model::{FilterList, Model, Task};
+
+fn model() -> Model {
+    let mut model = Model::new();
+    let mut shop = Task::new("Weekly shop");
+    let mut milk = Task::new("Milk #groceries");
+    milk.completed = true;
+    let bread = Task::new("Sourdough bread from the bakery on the corner #groceries");
+    let mut socks = Task::new("Socks");
+    socks.cancel(None);
+    for task in [milk, bread, socks] {
+        shop.subtasks.insert(task.id, task);
+    }
+    let call = Task::new("Call the plumber");
+    model.tasks.insert(shop.id, shop);
+    model.tasks.insert(call.id, call);
+    model
+}
+
+#[test]
+fn prints_the_whole_tree() {
+    let (report, matched) = commands::report(&model(), None, None);
+    assert_eq!(matched, 5);
+    assert!(report.ends_with(
+        "[ ] Weekly shop
+  [x] Milk #groceries
+  [ ] Sourdough bread from the bakery on the corner #groceries
+  [-] Socks
+[ ] Call the plumber
+"
+    ));
+}
+
+#[test]
+fn filters_keep_ancestors_and_wrap_to_the_width() {
+    let filter = FilterList::parse("#groceries").unwrap();
+    let (report, matched) = commands::report(&model(), Some(&filter), Some(30));
+    assert_eq!(matched, 2);
+    assert_eq!(
+        report,
+        "[ ] Weekly shop
+  [x] Milk #groceries
+  [ ] Sourdough bread from the
+      bakery on the corner
+      #groceries
+"
+    );
+}
+
+#[test]
+fn queries_answer_with_exit_codes() {
+    let mut model = model();
+    let code = |model: &mut Model, filter| commands::list(model, Some(filter), ListOutput::Quiet);
+    assert_eq!(code(&mut model, "#groceries"), EXIT_MATCHED);
+    assert_eq!(code(&mut model, "#hardware"), EXIT_NO_MATCHES);
+    assert_eq!(code(&mut model, "completed:maybe"), EXIT_PARSE_ERROR);
+}
@@ -0,0 +1,38 @@
+//! Replaying recorded sessions.
+
+use chors::replay::{self, Recorder};
+use chors_core::{
+    export,
+    model::{Model, Msg, Task},
+};
+use std::fs;
+
+#[test]
+fn messages_reaching_outside_the_model_are_skipped() {
+    let dir = std::env::temp_dir().join(format!("chors-replay-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("session.log");
+    let journal = dir.join("journal.md");
+
+    let mut recorder = Recorder::start(log.to_str().unwrap(), &Model::new()).unwrap();
+    for msg in [
+        Msg::AddTasksBulk(vec![(Vec::new(), Task::new("Water plants"))]),
+        Msg::RunCommand(format!("journal {}", journal.display())),
+        Msg::RunCommand("cleanup --all".to_string()),
+        Msg::RunCommand("done-style dim".to_string()),
+        Msg::ExportView(export::Format::Markdown),
+        Msg::OpenUrl,
+        Msg::OpenAttachment,
+        Msg::CopySummary,
+    ] {
+        recorder.record(&msg, &[]).unwrap();
+    }
+
+    let replay = replay::replay(log.to_str().unwrap()).unwrap();
+    assert_eq!((replay.messages, replay.skipped), (2, 6));
+    assert_eq!(replay.model.tasks.len(), 1);
+    assert!(replay.model.completed_style.dim);
+    assert_eq!(replay.model.journal, None);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+//! Answering requests for the calendar feed.
+
+use chors::serve;
+use chors_core::model::{Model, Task};
+use chrono::{TimeZone, Utc};
+use color_eyre::{eyre::eyre, Result};
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+fn model() -> Result<Model> {
+    let mut model = Model::new();
+    let mut task = Task::new("Dentist");
+    task.due_time = Some(Utc.with_ymd_and_hms(2024, 3, 8, 9, 30, 0).unwrap());
+    model.tasks.insert(task.id, task);
+    Ok(model)
+}
+
+#[test]
+fn only_the_calendar_is_served() {
+    let (status, content_type, body) = serve::respond("GET /calendar.ics HTTP/1.1", &mut model);
+    assert_eq!(status, "200 OK");
+    assert!(content_type.starts_with("text/calendar"));
+    assert!(body.contains("BEGIN:VEVENT"));
+    let (_, _, body) = serve::respond("GET /calendar.ics?todos HTTP/1.1", &mut model);
+    assert!(body.contains("BEGIN:VTODO"));
+
+    assert_eq!(
+        serve::respond("GET / HTTP/1.1", &mut model).0,
+        "404 Not Found"
+    );
+    assert_eq!(
+        serve::respond("PUT /calendar.ics HTTP/1.1", &mut model).0,
+        "405 Method Not Allowed"
+    );
+    let (status, _, body) = serve::respond("GET /calendar.ics HTTP/1.1", &mut || {
+        Err(eyre!("no such file"))
+    });
+    assert_eq!(status, "500 Internal Server Error");
+    assert_eq!(body, "no such file\n");
+}
+
+#[test]
+fn serves_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve::run(listener, model));
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /calendar.ics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with("END:VCALENDAR\r\n"));
+}
@@ -0,0 +1,689 @@
+//! Renders the TUI into a `TestBackend` after scripted message sequences and
+//! compares the screen against snapshots in `tests/snapshots`. Run with
+//! `INSTA_UPDATE=always` (or `cargo insta review`) to accept intended changes.
+
+use chors::{keys, view};
+use chors_core::{
+    animation,
+    columns::Column,
+    model::{ColorSupport, Direction, Model, Msg, Overlay, Task, View},
+    update::update,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    backend::TestBackend,
+    style::{Color, Modifier},
+    Terminal,
+};
+
+const WIDTH: u16 = 60;
+const HEIGHT: u16 = 24;
+
+/// Drives a model the way the event loop does: every message is followed by
+/// a frame, since rendering is what rebuilds the navigation order.
+struct Harness {
+    model: Model,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut harness = Self {
+            model: Model::new(),
+            terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap(),
+        };
+        harness.draw();
+        harness
+    }
+
+    fn draw(&mut self) {
+        let model = &mut self.model;
+        self.terminal.draw(|frame| view::ui(frame, model)).unwrap();
+    }
+
+    fn send(&mut self, msg: Msg) -> &mut Self {
+        update(msg, &mut self.model);
+        self.draw();
+        self
+    }
+
+    /// Sends what the key press maps to, as the event loop would.
+    fn press(&mut self, code: KeyCode, modifiers: KeyModifiers) -> &mut Self {
+        let msg = keys::key_event_to_msg(&self.model, KeyEvent::new(code, modifiers));
+        self.send(msg)
+    }
+
+    fn type_text(&mut self, text: &str) -> &mut Self {
+        for ch in text.chars() {
+            self.send(Msg::PushChar(ch));
+        }
+        self
+    }
+
+    fn add_task(&mut self, description: &str) -> &mut Self {
+        self.send(Msg::SetOverlay(Overlay::AddingTask))
+            .type_text(description)
+            .send(Msg::AddTask)
+    }
+
+    fn add_subtask(&mut self, description: &str) -> &mut Self {
+        self.send(Msg::SetOverlay(Overlay::AddingSubtask))
+            .type_text(description)
+            .send(Msg::AddSubtask)
+    }
+
+    /// The screen as plain text, one line per row with trailing blanks kept
+    /// so layout shifts show up in the diff.
+    fn screen(&self) -> String {
+        let buffer = self.terminal.backend().buffer();
+        let mut screen = String::new();
+        for y in 0..buffer.area.height {
+            for x in 0..buffer.area.width {
+                screen.push_str(buffer.get(x, y).symbol());
+            }
+            screen.push('\n');
+        }
+        screen
+    }
+}
+
+#[test]
+fn empty_list() {
+    let harness = Harness::new();
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn nested_tasks() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Plan trip #travel")
+        .add_subtask("Book flights @laptop")
+        .add_task("Pack bags")
+        .send(Msg::ToggleTaskCompletion)
+        .send(Msg::NavigateTasks(Direction::Up))
+        .send(Msg::NavigateTasks(Direction::Up))
+        .add_task("Water plants #home");
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn add_task_overlay() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Existing task")
+        .send(Msg::SetOverlay(Overlay::AddingTask))
+        .type_text("Half typed #ta");
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn completed_tasks_hidden() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Done already")
+        .send(Msg::ToggleTaskCompletion)
+        .add_task("Still open")
+        .send(Msg::ToggleShowCompleted);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn filter_parse_error() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Anything")
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("completed:maybe")
+        .send(Msg::AddFilterCriterion);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn delete_confirmation() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Project")
+        .add_subtask("One")
+        .add_task("Two")
+        .add_task("Three")
+        .add_task("Four")
+        // Past the inbox, which the first task was added to
+        .send(Msg::NavigateTasks(Direction::Down))
+        .send(Msg::NavigateTasks(Direction::Down))
+        .send(Msg::DeleteTask);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn sidebar() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Call mom @phone #family")
+        .add_task("Fix bike #home")
+        .send(Msg::ToggleSidebar);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn help_overlay() {
+    let mut harness = Harness::new();
+    harness.send(Msg::SetOverlay(Overlay::Help));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn zoomed_subtree() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Project")
+        .add_subtask("Phase 1")
+        .add_task("Phase 2")
+        .add_subtask("Write report")
+        .send(Msg::NavigateTasks(Direction::Up));
+    let phase = harness.model.get_path();
+    harness.send(Msg::Zoom(phase)).add_task("Review report");
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn long_breadcrumb() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Renovate the summer house")
+        .add_subtask("Ground floor")
+        .add_subtask("Kitchen cabinets and countertops")
+        .add_subtask("Order hinges")
+        .add_subtask("Compare suppliers for soft-close hinges");
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn partial_progress() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Read books")
+        .add_subtask("Dune")
+        .send(Msg::PushCount(3))
+        .send(Msg::AdjustProgress(10))
+        .add_task("Hyperion")
+        .send(Msg::AdjustProgress(50))
+        .send(Msg::AdjustProgress(50));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn cancelled_task() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Move house")
+        .add_subtask("Pack")
+        .send(Msg::ToggleTaskCompletion)
+        .add_task("Repaint the fence")
+        .send(Msg::SetOverlay(Overlay::CancellingTask))
+        .type_text("selling it")
+        .send(Msg::CancelTask)
+        .add_task("Hand over keys");
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn waiting_for_view() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Send contract >>alice")
+        .add_task("Review PR >>bob")
+        .add_task("Sign lease >>alice")
+        .send(Msg::ToggleTaskCompletion)
+        .add_task("Book venue >>alice")
+        .add_task("Water plants")
+        .send(Msg::LoadView("waiting".to_string()));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn focus_context() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Print slides @office")
+        .add_task("Fix sink @home")
+        .add_task("Email Bob @office #work")
+        .send(Msg::RunCommand("focus office".to_string()));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn snoozed_tasks_wait_for_their_context() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Fix sink @home")
+        .add_task("Print slides @office")
+        .send(Msg::RunCommand("snooze office".to_string()));
+    let screen = harness.screen();
+    assert!(screen.contains("Fix sink") && !screen.contains("Print slides"));
+
+    harness.send(Msg::RunCommand("focus office".to_string()));
+    let screen = harness.screen();
+    assert!(!screen.contains("Fix sink"));
+    assert!(screen.contains("Print slides @office (snoozed until @office)"));
+
+    harness.send(Msg::RunCommand("focus off".to_string()));
+    harness
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("snoozed")
+        .send(Msg::AddFilterCriterion);
+    assert!(harness.screen().contains("Print slides"));
+}
+
+#[test]
+fn no_color_keeps_the_selection_visible() {
+    let mut harness = Harness::new();
+    harness.model.color_support = ColorSupport::None;
+    harness.add_task("Call mom #family").add_task("Fix bike");
+    let buffer = harness.terminal.backend().buffer();
+    assert!(buffer
+        .content
+        .iter()
+        .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+    // The last task added is selected, below the inbox and its sibling
+    assert!(buffer.get(5, 3).modifier.contains(Modifier::REVERSED));
+    assert!(!buffer.get(5, 2).modifier.contains(Modifier::REVERSED));
+}
+
+#[test]
+fn accessible_profile() {
+    let mut harness = Harness::new();
+    harness.model.accessible = true;
+    harness
+        .add_task("Call the landlord")
+        .send(Msg::AdjustProgress(50))
+        .add_task("Pay rent")
+        .send(Msg::ToggleTaskCompletion)
+        .add_task("Repaint the fence")
+        .send(Msg::SetOverlay(Overlay::CancellingTask))
+        .send(Msg::CancelTask);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn custom_symbols() {
+    let mut harness = Harness::new();
+    harness.model.symbols = serde_json::from_str(r#"{ "open": "○", "done": "●" }"#).unwrap();
+    harness
+        .add_task("Fix the sink")
+        .add_task("Pay rent")
+        .send(Msg::ToggleTaskCompletion)
+        .add_task("Call the landlord")
+        .send(Msg::SetOverlay(Overlay::CancellingTask))
+        .send(Msg::CancelTask);
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn done_style_dims_and_strikes_finished_rows() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Open")
+        .add_task("Finished")
+        .send(Msg::ToggleTaskCompletion)
+        .send(Msg::RunCommand("done-style dim strike".to_string()));
+    let buffer = harness.terminal.backend().buffer();
+    let finished = Modifier::DIM | Modifier::CROSSED_OUT;
+    // Checkbox of the second task, then of the first
+    assert!(buffer.get(5, 3).modifier.contains(finished));
+    assert!(!buffer.get(5, 2).modifier.intersects(finished));
+}
+
+#[test]
+fn read_only_turns_edits_away() {
+    let mut harness = Harness::new();
+    harness.add_task("Shared chore");
+    harness.model.read_only = true;
+    harness
+        .send(Msg::ToggleTaskCompletion)
+        .send(Msg::SetOverlay(Overlay::AddingTask));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn read_only_turns_editing_commands_away() {
+    let mut harness = Harness::new();
+    harness.add_task("Shared chore");
+    harness.model.read_only = true;
+    let policy = harness.model.completion_policy;
+    for command in [
+        "completion manual",
+        "completion default manual",
+        "rollover start flag",
+        "journal chores.md",
+        "no-such-script",
+    ] {
+        harness.send(Msg::RunCommand(command.to_string()));
+    }
+    let path = harness.model.get_path();
+    assert_eq!(harness.model.get_task(&path).unwrap().completion, None);
+    assert_eq!(harness.model.completion_policy, policy);
+    assert_eq!(harness.model.rollover_on_start, None);
+    assert_eq!(harness.model.journal, None);
+    // Display settings stay open to change
+    harness.send(Msg::RunCommand("done-style strike".to_string()));
+    assert!(harness.model.completed_style.strike);
+}
+
+#[test]
+fn long_lists_scroll_to_the_selection() {
+    let mut harness = Harness::new();
+    let tasks = (1..=100)
+        .map(|number| (Vec::new(), Task::new(&format!("Chore {}", number))))
+        .collect();
+    harness
+        .send(Msg::AddTasksBulk(tasks))
+        .send(Msg::PushCount(6))
+        .send(Msg::PushCount(0))
+        .send(Msg::JumpToEnd);
+    assert!(harness.screen().contains("60 [ ] Chore 60"));
+    harness.send(Msg::JumpToEnd);
+    insta::assert_snapshot!(harness.screen());
+    for _ in 0..30 {
+        harness.send(Msg::NavigateTasks(Direction::Up));
+    }
+    let screen = harness.screen();
+    // The selection scrolls in at the top, nothing above it is drawn
+    assert!(screen
+        .lines()
+        .nth(1)
+        .unwrap()
+        .starts_with("│ 70 [ ] Chore 70"));
+    assert!(!screen.contains("Chore 69"));
+}
+
+fn chores(count: usize) -> Msg {
+    let tasks = (1..=count)
+        .map(|number| (Vec::new(), Task::new(&format!("Chore {}", number))))
+        .collect();
+    Msg::AddTasksBulk(tasks)
+}
+
+fn selected_description(harness: &Harness) -> String {
+    let path = harness.model.get_path();
+    harness.model.get_task(&path).unwrap().description.clone()
+}
+
+#[test]
+fn counted_motions_stop_at_the_ends() {
+    let mut harness = Harness::new();
+    harness
+        .send(chores(5))
+        .send(Msg::PushCount(2))
+        .send(Msg::JumpToEnd)
+        .send(Msg::PushCount(1))
+        .send(Msg::PushCount(0))
+        .send(Msg::PushCount(0))
+        .send(Msg::NavigateTasks(Direction::Down));
+    assert_eq!(selected_description(&harness), "Chore 5");
+    harness
+        .send(Msg::PushCount(9))
+        .send(Msg::NavigateTasks(Direction::Up));
+    assert_eq!(selected_description(&harness), "Chore 1");
+    // Without a count a step still wraps around
+    harness.send(Msg::NavigateTasks(Direction::Up));
+    assert_eq!(selected_description(&harness), "Chore 5");
+}
+
+#[test]
+fn counts_repeat_actions_down_the_list() {
+    let mut harness = Harness::new();
+    harness
+        .send(chores(5))
+        .send(Msg::PushCount(2))
+        .send(Msg::JumpToEnd)
+        .send(Msg::PushCount(3))
+        .send(Msg::DeleteTask);
+    let descriptions: Vec<_> = harness
+        .model
+        .tasks
+        .values()
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(descriptions, ["Chore 1", "Chore 5"]);
+    assert_eq!(selected_description(&harness), "Chore 5");
+
+    harness
+        .send(Msg::JumpToEnd)
+        .send(Msg::PushCount(1))
+        .send(Msg::JumpToEnd)
+        .send(Msg::PushCount(2))
+        .send(Msg::ToggleTaskCompletion);
+    assert!(harness.model.tasks.values().all(|task| task.completed));
+
+    let path = harness.model.get_path();
+    harness
+        .send(Msg::PushCount(3))
+        .send(Msg::DuplicateTask(path));
+    assert_eq!(harness.model.tasks.len(), 5);
+}
+
+#[test]
+fn far_jumps_scroll_over_a_few_frames() {
+    let mut harness = Harness::new();
+    let tasks = (1..=100)
+        .map(|number| (Vec::new(), Task::new(&format!("Chore {}", number))))
+        .collect();
+    harness
+        .send(Msg::AddTasksBulk(tasks))
+        .send(Msg::RunCommand("animations on".to_string()))
+        .send(Msg::JumpToEnd);
+    // The first frame still shows the top, without a selection pulling the
+    // list down at once
+    assert!(harness.screen().contains("Chore 1 "));
+    assert!(!harness.screen().contains("100 [ ] Chore 100"));
+    assert!(harness.model.animation.is_running());
+
+    std::thread::sleep(animation::SCROLL_DURATION);
+    harness.send(Msg::Tick);
+    assert!(harness.screen().contains("100 [ ] Chore 100"));
+    harness.send(Msg::Tick);
+    assert!(!harness.model.animation.is_running());
+}
+
+#[test]
+fn jump_overlay() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Fix bike")
+        .add_subtask("Buy inner tube")
+        .add_task("Call mom #family")
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("#family")
+        .send(Msg::AddFilterCriterion)
+        .send(Msg::SetOverlay(Overlay::Jump))
+        .type_text("tube");
+    insta::assert_snapshot!(harness.screen());
+
+    let target = harness.model.jump_results("tube")[0].clone();
+    harness.send(Msg::JumpToTask(target.clone()));
+    assert_eq!(harness.model.selected, target.last().copied());
+    assert_eq!(harness.model.get_path(), target);
+    assert!(harness
+        .screen()
+        .contains("Cleared the filter to show the task."));
+}
+
+#[test]
+fn marks_overlay() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Fix bike")
+        .add_subtask("Buy inner tube")
+        .send(Msg::SetMark('b'))
+        .send(Msg::SetOverlay(Overlay::Marks));
+    insta::assert_snapshot!(harness.screen());
+}
+
+#[test]
+fn filter_suggestions_narrow_to_the_selected_task() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Call mom #family @phone")
+        .add_task("Fix bike")
+        .send(Msg::NavigateTasks(Direction::Up))
+        .send(Msg::SetOverlay(Overlay::FilterSuggestions));
+    insta::assert_snapshot!(harness.screen());
+
+    let filter = harness.model.filter_suggestions().remove(0);
+    harness.send(Msg::ApplyFilter(filter));
+    let screen = harness.screen();
+    assert!(screen.contains("Call mom") && !screen.contains("Fix bike"));
+}
+
+#[test]
+fn filters_show_how_many_tasks_match() {
+    let mut harness = Harness::new();
+    harness.add_task("Call mom #family").add_task("Fix bike");
+    assert!(!harness.screen().contains("tasks ("));
+
+    harness
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("#family")
+        .send(Msg::AddFilterCriterion);
+    let screen = harness.screen();
+    // The inbox the tasks went into counts too
+    assert!(screen.contains("1 / 3 tasks (0 done)"), "{}", screen);
+}
+
+#[test]
+fn views_overlay_shows_key_bindings() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Call mom #family")
+        .add_task("Fix bike")
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("#family")
+        .send(Msg::AddFilterCriterion)
+        .send(Msg::SaveCurrentView("family".to_string()))
+        .send(Msg::LoadView("default".to_string()))
+        .send(Msg::RunCommand("views".to_string()))
+        .send(Msg::NavigateViews(Direction::Down))
+        .send(Msg::BindViewKey('2'));
+    insta::assert_snapshot!(harness.screen());
+
+    harness.send(Msg::LoadView(harness.model.view_keys[&'2'].clone()));
+    assert!(!harness.screen().contains("Fix bike"));
+}
+
+#[test]
+fn bound_digits_still_start_counts() {
+    let mut harness = Harness::new();
+    harness
+        .send(chores(8))
+        .send(Msg::SetOverlay(Overlay::AddingFilterCriterion))
+        .type_text("text:\"Chore 1\"")
+        .send(Msg::AddFilterCriterion)
+        .send(Msg::SaveCurrentView("first".to_string()))
+        .send(Msg::RunCommand("views".to_string()))
+        .send(Msg::NavigateViews(Direction::Down))
+        .send(Msg::BindViewKey('5'))
+        .send(Msg::SetOverlay(Overlay::None));
+    assert_eq!(harness.model.view_keys[&'5'], "first");
+    harness.model.current_view = View::default();
+    harness
+        .send(Msg::PushCount(1))
+        .send(Msg::JumpToEnd)
+        .press(KeyCode::Char('5'), KeyModifiers::NONE)
+        .press(KeyCode::Char('j'), KeyModifiers::NONE);
+    assert_eq!(selected_description(&harness), "Chore 6");
+    assert!(harness.model.current_view.filter_lists.is_empty());
+
+    harness.press(KeyCode::Char('5'), KeyModifiers::ALT);
+    assert!(!harness.model.current_view.filter_lists.is_empty());
+}
+
+#[test]
+fn comments_are_counted_on_the_row() {
+    let mut harness = Harness::new();
+    harness.model.author = Some("alice".to_string());
+    harness.model.accessible = true;
+    harness
+        .add_task("Fix the gate")
+        .send(Msg::RunCommand("comments".to_string()));
+    assert!(harness.screen().contains("No comments yet."));
+    harness
+        .type_text("Hinge is rusted")
+        .send(Msg::AddComment)
+        .send(Msg::AddComment)
+        .type_text("Fitted a new one")
+        .send(Msg::AddComment);
+    let screen = harness.screen();
+    assert!(screen.contains("alice") && screen.contains("Fitted a new one"));
+
+    harness.send(Msg::SetOverlay(Overlay::None));
+    assert!(harness.screen().contains("Fix the gate [comments]2"));
+    harness.model.read_only = true;
+    harness
+        .send(Msg::RunCommand("comments".to_string()))
+        .type_text("Not allowed")
+        .send(Msg::AddComment)
+        .send(Msg::SetOverlay(Overlay::None));
+    assert!(harness.screen().contains("[comments]2"));
+}
+
+#[test]
+fn task_form_edits_metadata_apart() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Invoice client:Acme for March")
+        .send(Msg::EditTaskForm);
+    let screen = harness.screen();
+    assert!(screen.contains("Invoice for March"));
+    assert!(screen.contains("client:Acme"));
+
+    harness
+        .send(Msg::FormNextField)
+        .send(Msg::FormNextField)
+        .send(Msg::FormNextField)
+        .send(Msg::FormNextField)
+        .type_text(" rate:120")
+        .send(Msg::SubmitTaskForm);
+    let task = harness.model.get_task(&harness.model.get_path()).unwrap();
+    assert_eq!(task.description, "Invoice for March client:Acme rate:120");
+    assert_eq!(task.meta["rate"], "120");
+}
+
+#[test]
+fn columns_are_configurable() {
+    let mut harness = Harness::new();
+    harness
+        .add_task("Invoice client:Acme estimate:2h")
+        .send(Msg::RunCommand(
+            "columns estimate:3 description priority".to_string(),
+        ))
+        .send(Msg::EditTaskForm)
+        .send(Msg::FormNextField)
+        .send(Msg::FormNextField)
+        .type_text("high")
+        .send(Msg::SubmitTaskForm);
+    insta::assert_snapshot!(harness.screen());
+
+    harness.send(Msg::RunCommand("columns".to_string()));
+    assert_eq!(
+        harness.model.column_choices()[..3],
+        [Column::Estimate, Column::Description, Column::Priority]
+    );
+    harness
+        .send(Msg::ToggleColumn(Column::Description))
+        .send(Msg::MoveColumn(Column::Priority, Direction::Up))
+        .send(Msg::ToggleColumn(Column::Estimate))
+        .send(Msg::ResizeColumn(Column::Description, -10));
+    let columns: Vec<_> = harness
+        .model
+        .columns
+        .iter()
+        .map(|spec| (spec.column, spec.width))
+        .collect();
+    assert_eq!(
+        columns,
+        [(Column::Priority, None), (Column::Description, Some(30))]
+    );
+    harness.send(Msg::RunCommand("columns due".to_string()));
+    assert_eq!(harness.model.columns.len(), 2);
+}
@@ -0,0 +1,114 @@
+//! Webhook configuration and the JSON posted for task events.
+
+use chors::webhooks::{self, Webhooks};
+use chors_core::{
+    events::{self, Event},
+    model::{Model, Msg, Task},
+    update::update,
+};
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+#[test]
+fn config_lines_name_an_event_and_a_url() {
+    let hooks = webhooks::parse(
+        "# Home Assistant\ncompleted = https://ha.local/api/webhook/done\n* = http://localhost:5678/hook\n",
+    )
+    .unwrap();
+    assert_eq!(hooks.len(), 2);
+    assert_eq!(hooks[0].0, "completed");
+    assert!(webhooks::parse("edited = https://example.com").is_err());
+    assert!(webhooks::parse("created = example.com").is_err());
+}
+
+/// Accepts `count` requests and returns their bodies.
+fn receive(listener: TcpListener, count: usize) -> thread::JoinHandle<Vec<String>> {
+    thread::spawn(move || (0..count).map(|_| receive_one(&listener)).collect())
+}
+
+fn receive_one(listener: &TcpListener) -> String {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end().to_ascii_lowercase();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+    let mut stream = reader.into_inner();
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    String::from_utf8(body).unwrap()
+}
+
+#[test]
+fn completions_are_posted_as_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = receive(listener, 1);
+
+    let mut model = Model::new();
+    let task = Task::new("Take out the trash #home @house");
+    let path = vec![task.id];
+    model.tasks.insert(task.id, task);
+    update(Msg::CompleteTasks(vec![path.clone()]), &mut model);
+    let mut hooks = Webhooks::new(vec![("completed".to_string(), url)]);
+    events::dispatch(&mut model, &mut [&mut hooks]);
+    hooks.finish();
+    assert_eq!(hooks.failures(), Vec::<String>::new());
+
+    let body: Value = serde_json::from_str(&received.join().unwrap()[0]).unwrap();
+    assert_eq!(body["event"], "completed");
+    assert_eq!(body["task"]["id"], path[0].to_string());
+    assert_eq!(body["task"]["tags"][0], "#home");
+    assert_eq!(body["task"]["contexts"][0], "@house");
+    assert!(body["task"]["completed_at"].is_string());
+
+    let gone = webhooks::payload("deleted", path[0], &Model::new());
+    assert_eq!(gone["task"].as_object().unwrap().len(), 1);
+    assert_eq!(webhooks::event_name(&Event::FilterChanged), None);
+}
+
+#[test]
+fn deleting_a_subtree_posts_each_task_in_it() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = receive(listener, 2);
+
+    let mut model = Model::new();
+    let mut parent = Task::new("Garage");
+    let child = Task::new("Sweep the floor");
+    let child_id = child.id;
+    parent.subtasks.insert(child_id, child);
+    let parent_id = parent.id;
+    model.tasks.insert(parent_id, parent);
+    update(Msg::RemoveTask(vec![parent_id]), &mut model);
+    let mut hooks = Webhooks::new(vec![("deleted".to_string(), url)]);
+    events::dispatch(&mut model, &mut [&mut hooks]);
+    hooks.finish();
+    assert_eq!(hooks.failures(), Vec::<String>::new());
+
+    let ids: Vec<Value> = received
+        .join()
+        .unwrap()
+        .iter()
+        .map(|body| serde_json::from_str::<Value>(body).unwrap()["task"]["id"].clone())
+        .collect();
+    assert_eq!(
+        ids,
+        [parent_id.to_string(), child_id.to_string()].map(Value::from)
+    );
+}